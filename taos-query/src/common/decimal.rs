@@ -0,0 +1,239 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::Ty;
+
+/// Max precision of DECIMAL.
+pub(crate) const MAX_PRECISION: u8 = 38;
+/// Max precision of [Ty::Decimal64].
+const MAX_DECIMAL64_PRECISION: u8 = 18;
+/// Max scale of [rust_decimal::Decimal].
+const MAX_RUST_DECIMAL_SCALE: u8 = 28;
+/// Max mantissa of [rust_decimal::Decimal], `2^96 - 1`.
+const MAX_RUST_DECIMAL_MANTISSA: u128 = (1 << 96) - 1;
+
+/// Data type of DECIMAL columns in `precision`, [Ty::Decimal64] for precision up to 18.
+pub(crate) const fn decimal_ty(precision: u8) -> Ty {
+    match precision {
+        1..=MAX_DECIMAL64_PRECISION => Ty::Decimal64,
+        _ => Ty::Decimal,
+    }
+}
+
+/// An exact DECIMAL value, the scaled integer `value` of `scale` means `value / 10^scale`.
+///
+/// All the 38 digits of DECIMAL are kept, use [Decimal::to_decimal] to convert to
+/// [rust_decimal::Decimal] which keeps 28 digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Decimal {
+    value: i128,
+    precision: u8,
+    scale: u8,
+}
+
+impl Decimal {
+    /// Decimal of scaled integer `value` in `precision` and `scale`, `0` precision means 38.
+    pub const fn new(value: i128, precision: u8, scale: u8) -> Self {
+        Self {
+            value,
+            precision,
+            scale,
+        }
+    }
+
+    /// The scaled integer.
+    pub const fn as_raw_i128(&self) -> i128 {
+        self.value
+    }
+
+    pub const fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub const fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// The data type of this value, [Ty::Decimal64] for precision up to 18.
+    pub const fn ty(&self) -> Ty {
+        decimal_ty(self.precision)
+    }
+
+    /// Rescale to `precision` and `scale`, rounding half away from zero.
+    ///
+    /// Returns `None` if the result is beyond `precision` digits, `0` means the max precision 38.
+    pub fn cast(&self, precision: u8, scale: u8) -> Option<Decimal> {
+        let digits = match precision {
+            1..=MAX_PRECISION => precision,
+            _ => MAX_PRECISION,
+        };
+        let value = if scale >= self.scale {
+            self.value
+                .checked_mul(10i128.checked_pow((scale - self.scale) as u32)?)?
+        } else {
+            match 10i128.checked_pow((self.scale - scale) as u32) {
+                Some(factor) => {
+                    let (quot, rem) = (self.value / factor, self.value % factor);
+                    if rem.unsigned_abs() * 2 >= factor as u128 {
+                        quot + rem.signum()
+                    } else {
+                        quot
+                    }
+                }
+                // Less than half of the factor, any i128 is rounded to zero.
+                None => 0,
+            }
+        };
+        (value.unsigned_abs() < 10u128.pow(digits as u32))
+            .then(|| Decimal::new(value, precision, scale))
+    }
+
+    /// Convert to [f64], digits beyond its precision are lost.
+    pub fn to_f64(self) -> f64 {
+        self.value as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    /// Convert to [rust_decimal::Decimal] exactly, `None` if the value is not representable.
+    pub fn to_decimal(self) -> Option<rust_decimal::Decimal> {
+        let (mut value, mut scale) = (self.value, self.scale);
+        while scale > MAX_RUST_DECIMAL_SCALE || value.unsigned_abs() > MAX_RUST_DECIMAL_MANTISSA {
+            // Only trailing zeros could be dropped.
+            if scale == 0 || value % 10 != 0 {
+                return None;
+            }
+            value /= 10;
+            scale -= 1;
+        }
+        Some(rust_decimal::Decimal::from_i128_with_scale(
+            value,
+            scale as u32,
+        ))
+    }
+}
+
+/// Decimal in the max precision 38 with the scale of `value`.
+impl From<rust_decimal::Decimal> for Decimal {
+    fn from(value: rust_decimal::Decimal) -> Self {
+        Decimal::new(value.mantissa(), MAX_PRECISION, value.scale() as u8)
+    }
+}
+
+/// Parse an exact decimal string like `-123.45` in the max precision 38 and the scale of its
+/// fraction digits.
+impl FromStr for Decimal {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const INVALID: &str = "not a valid decimal string";
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        if (int.is_empty() && frac.is_empty())
+            || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(INVALID);
+        }
+        let scale = u8::try_from(frac.len()).map_err(|_| INVALID)?;
+        let value = format!("{int}{frac}")
+            .parse::<i128>()
+            .ok()
+            .filter(|v| v.unsigned_abs() < 10u128.pow(MAX_PRECISION as u32))
+            .ok_or("decimal is beyond 38 digits")?;
+        Ok(Decimal::new(
+            if negative { -value } else { value },
+            MAX_PRECISION,
+            scale,
+        ))
+    }
+}
+
+/// Formatted exactly, like `-1.05` for scaled integer `-105` of scale 2.
+impl Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.value < 0 { "-" } else { "" };
+        let digits = self.value.unsigned_abs().to_string();
+        if self.scale == 0 {
+            return write!(f, "{sign}{digits}");
+        }
+        let scale = self.scale as usize;
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        write!(f, "{sign}{int}.{frac}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_display() {
+        assert_eq!(Decimal::new(12345, 10, 2).to_string(), "123.45");
+        assert_eq!(Decimal::new(-5, 10, 2).to_string(), "-0.05");
+        assert_eq!(Decimal::new(-5, 10, 0).to_string(), "-5");
+        assert_eq!(
+            Decimal::new(i128::MAX, 38, 2).to_string(),
+            "1701411834604692317316873037158841057.27"
+        );
+    }
+
+    #[test]
+    fn decimal_from_str() {
+        assert_eq!("123.45".parse(), Ok(Decimal::new(12345, 38, 2)));
+        assert_eq!("-0.050".parse(), Ok(Decimal::new(-50, 38, 3)));
+        assert_eq!("+7".parse(), Ok(Decimal::new(7, 38, 0)));
+        assert_eq!(".5".parse(), Ok(Decimal::new(5, 38, 1)));
+        let max = "9".repeat(36) + ".99";
+        assert_eq!(max.parse::<Decimal>().unwrap().to_string(), max);
+        assert!(("1".to_string() + &"0".repeat(38))
+            .parse::<Decimal>()
+            .is_err());
+        for s in ["", ".", "-", "1.2.3", "1e5", "abc"] {
+            assert!(s.parse::<Decimal>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn decimal_cast() {
+        let v = Decimal::new(12345, 38, 3);
+        assert_eq!(v.cast(4, 2), Some(Decimal::new(1235, 4, 2)));
+        assert_eq!(v.cast(3, 2), None);
+        assert_eq!(
+            Decimal::new(-12345, 38, 3).cast(0, 2),
+            Some(Decimal::new(-1235, 0, 2))
+        );
+        assert_eq!(v.cast(10, 5), Some(Decimal::new(1234500, 10, 5)));
+        assert_eq!(Decimal::new(i128::MAX, 38, 0).cast(38, 1), None);
+        assert_eq!(Decimal::new(i128::MAX, 38, 0).cast(38, 0), None);
+        assert_eq!(
+            Decimal::new(1, 38, 60).cast(10, 2),
+            Some(Decimal::new(0, 10, 2))
+        );
+    }
+
+    #[test]
+    fn decimal_to_rust_decimal() {
+        assert_eq!(
+            Decimal::new(12345, 10, 2).to_decimal(),
+            Some(rust_decimal::Decimal::new(12345, 2))
+        );
+        // Trailing zeros beyond 28 digits are dropped.
+        assert_eq!(
+            Decimal::new(10i128.pow(30), 38, 30).to_decimal(),
+            Some(rust_decimal::Decimal::new(1, 0))
+        );
+        assert_eq!(Decimal::new(10i128.pow(30) + 5, 38, 30).to_decimal(), None);
+        assert_eq!(Decimal::new(i128::MAX, 38, 2).to_decimal(), None);
+
+        let v = Decimal::from(rust_decimal::Decimal::new(-15, 1));
+        assert_eq!(v, Decimal::new(-15, 38, 1));
+        assert_eq!(v.ty(), Ty::Decimal);
+        assert_eq!(Decimal::new(1, 18, 0).ty(), Ty::Decimal64);
+    }
+}
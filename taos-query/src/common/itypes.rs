@@ -244,10 +244,24 @@ impl_prim!(
     UBigInt = u64
     Float = f32
     Double = f64
-    Decimal = Decimal
     Json = Json
 );
 
+impl IValue for IDecimal {
+    const TY: Ty = Ty::Decimal;
+    type Inner = Decimal;
+
+    #[inline]
+    fn into_value(self) -> Value {
+        Value::Decimal(self.into())
+    }
+
+    #[inline]
+    fn into_inner(self) -> Self::Inner {
+        self
+    }
+}
+
 pub trait IsPrimitive: Copy {
     const TY: Ty;
     fn is_primitive(&self) -> bool {
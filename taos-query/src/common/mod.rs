@@ -1,4 +1,5 @@
 mod column;
+mod decimal;
 mod describe;
 mod field;
 // mod opts;
@@ -9,6 +10,7 @@ mod ty;
 mod value;
 
 pub use column::*;
+pub use decimal::*;
 pub use describe::*;
 pub use field::*;
 // pub use opts::*;
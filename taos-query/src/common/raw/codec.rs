@@ -0,0 +1,389 @@
+//! [BlockCodec] implementation of [RawBlock].
+//!
+//! [CodecOpts::Raw] uses the [Inlinable] layout, [CodecOpts::Parquet] writes a Parquet file with
+//! one row group and fails without feature `parquet`. Use [ParquetWriter] to export a whole result set.
+use std::fmt::Display;
+
+use taos_error::{Code, Error};
+
+use super::RawBlock;
+use crate::util::Inlinable;
+use crate::{BlockCodec, CodecOpts, Fetchable};
+
+/// Convert errors of encoding, decoding or exporting blocks to [Error] with [Code::Failed].
+pub(crate) fn codec_error(err: impl Display) -> Error {
+    Error::new(Code::Failed, err.to_string())
+}
+
+/// A destination of exported blocks, like a Parquet or CSV writer.
+pub(crate) trait BlockSink {
+    /// Write a block.
+    fn write_block(&mut self, block: &RawBlock) -> Result<(), Error>;
+
+    /// Finish writing, returns the rows written.
+    fn finish(self) -> Result<usize, Error>;
+}
+
+/// Write all blocks of a result set into `sink`, returns the rows written.
+pub(crate) fn write_blocks<F: Fetchable, S: BlockSink>(
+    rs: &mut F,
+    mut sink: S,
+) -> Result<usize, F::Error> {
+    for block in rs.blocks() {
+        sink.write_block(&block?)?;
+    }
+    Ok(sink.finish()?)
+}
+
+/// Fetch blocks of a result set asynchronously, and write them into the synchronous `sink`.
+#[cfg(feature = "async")]
+pub(crate) async fn write_blocks_async<F: crate::AsyncFetchable, S: BlockSink>(
+    rs: &mut F,
+    mut sink: S,
+) -> Result<usize, F::Error> {
+    use futures::TryStreamExt;
+
+    let mut blocks = rs.blocks();
+    while let Some(block) = blocks.try_next().await? {
+        sink.write_block(&block)?;
+    }
+    Ok(sink.finish()?)
+}
+
+impl BlockCodec for RawBlock {
+    /// Encode the block, [CodecOpts::Parquet] fails without feature `parquet`.
+    fn encode(&self, codec: CodecOpts) -> Result<Vec<u8>, Error> {
+        match codec {
+            CodecOpts::Raw => {
+                let mut bytes = Vec::new();
+                self.write_inlined(&mut bytes).map_err(codec_error)?;
+                Ok(bytes)
+            }
+            #[cfg(feature = "parquet")]
+            CodecOpts::Parquet => self.to_parquet().map_err(codec_error),
+            #[cfg(not(feature = "parquet"))]
+            CodecOpts::Parquet => Err(codec_error("parquet codec requires feature `parquet`")),
+        }
+    }
+
+    /// Decode a block encoded by [BlockCodec::encode].
+    ///
+    /// Invalid data is an error, and so is [CodecOpts::Parquet] without feature `parquet`.
+    fn decode(from: &[u8], codec: CodecOpts) -> Result<Self, Error> {
+        match codec {
+            CodecOpts::Raw => {
+                let mut reader = from;
+                Self::read_inlined(&mut reader).map_err(codec_error)
+            }
+            #[cfg(feature = "parquet")]
+            CodecOpts::Parquet => Self::from_parquet(from.to_vec()).map_err(codec_error),
+            #[cfg(not(feature = "parquet"))]
+            CodecOpts::Parquet => Err(codec_error("parquet codec requires feature `parquet`")),
+        }
+    }
+}
+
+#[test]
+fn test_raw_codec_invalid() {
+    use crate::common::{ColumnView, Precision};
+
+    let views = vec![ColumnView::from_ints(vec![Some(1), None])];
+    let raw = RawBlock::from_views(&views, Precision::Millisecond);
+    let bytes = raw.encode(CodecOpts::Raw).unwrap();
+    assert_eq!(
+        RawBlock::decode(&bytes, CodecOpts::Raw)
+            .unwrap()
+            .to_values(),
+        raw.to_values()
+    );
+    assert!(RawBlock::decode(&bytes[..bytes.len() - 1], CodecOpts::Raw).is_err());
+    assert!(RawBlock::decode(&[0xff; 16], CodecOpts::Raw).is_err());
+
+    #[cfg(not(feature = "parquet"))]
+    assert!(raw.encode(CodecOpts::Parquet).is_err());
+}
+
+#[cfg(feature = "parquet")]
+pub use parquet_file::*;
+
+#[cfg(feature = "parquet")]
+mod parquet_file {
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use arrow::array::RecordBatch;
+    use arrow::datatypes::{Schema, SchemaRef};
+    use arrow::error::ArrowError;
+    use bytes::Bytes;
+    use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ParquetRecordBatchReaderBuilder};
+    use parquet::arrow::ArrowWriter;
+    use parquet::errors::ParquetError;
+    use parquet::file::metadata::KeyValue;
+
+    use super::super::record_batch::fields_to_arrow_schema;
+    use super::{codec_error, write_blocks, BlockSink, RawBlock};
+    use crate::common::{ColumnView, Field, Precision};
+    use crate::Fetchable;
+
+    /// Parquet key-value metadata key for the TDengine fields in JSON, like
+    /// `[{"name":"ts","type":9,"length":8}]`.
+    pub const TAOS_SCHEMA_KEY: &str = "taos.schema";
+
+    /// Write raw blocks of the same fields into a Parquet file.
+    ///
+    /// Each [ParquetWriter::write_block] call is buffered into the current row group, which is
+    /// flushed by the writer properties or [ParquetWriter::flush].
+    pub struct ParquetWriter<W: Write + Send> {
+        schema: SchemaRef,
+        inner: ArrowWriter<W>,
+        rows: usize,
+    }
+
+    impl<W: Write + Send> ParquetWriter<W> {
+        /// Write blocks of `fields` into `writer`, the fields are recorded in metadata.
+        pub fn new(
+            writer: W,
+            fields: &[Field],
+            precision: Precision,
+        ) -> Result<Self, ParquetError> {
+            let schema = fields_to_arrow_schema(fields, precision)?;
+            Self::with_schema(writer, schema, fields)
+        }
+
+        fn with_schema(writer: W, schema: Schema, fields: &[Field]) -> Result<Self, ParquetError> {
+            let schema = Arc::new(schema);
+            let mut inner = ArrowWriter::try_new(writer, schema.clone(), None)?;
+            let fields = serde_json::to_string(fields)
+                .map_err(|err| ParquetError::General(err.to_string()))?;
+            inner.append_key_value_metadata(KeyValue::new(TAOS_SCHEMA_KEY.to_string(), fields));
+            Ok(Self {
+                schema,
+                inner,
+                rows: 0,
+            })
+        }
+
+        /// Write a block, its columns should be the same as the fields.
+        pub fn write_block(&mut self, block: &RawBlock) -> Result<(), ParquetError> {
+            let columns = block.columns().map(ColumnView::to_arrow_array).collect();
+            let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+            self.inner.write(&batch)?;
+            self.rows += block.nrows();
+            Ok(())
+        }
+
+        /// Rows written.
+        pub fn rows(&self) -> usize {
+            self.rows
+        }
+
+        /// Flush buffered blocks as a row group.
+        pub fn flush(&mut self) -> Result<(), ParquetError> {
+            self.inner.flush()
+        }
+
+        /// Write the file footer, returns the rows written.
+        pub fn close(self) -> Result<usize, ParquetError> {
+            self.inner.close()?;
+            Ok(self.rows)
+        }
+
+        /// Write the file footer and get the inner writer back.
+        pub fn into_inner(self) -> Result<W, ParquetError> {
+            self.inner.into_inner()
+        }
+    }
+
+    impl<W: Write + Send> BlockSink for ParquetWriter<W> {
+        fn write_block(&mut self, block: &RawBlock) -> Result<(), taos_error::Error> {
+            ParquetWriter::write_block(self, block).map_err(codec_error)
+        }
+
+        fn finish(self) -> Result<usize, taos_error::Error> {
+            self.close().map_err(codec_error)
+        }
+    }
+
+    impl RawBlock {
+        /// Encode the block as a Parquet file.
+        pub fn to_parquet(&self) -> Result<Vec<u8>, ParquetError> {
+            let fields = self.fields();
+            let mut writer = ParquetWriter::with_schema(Vec::new(), self.arrow_schema()?, &fields)?;
+            writer.write_block(self)?;
+            writer.into_inner()
+        }
+
+        /// Decode a Parquet file as one block, all row groups are concatenated.
+        ///
+        /// Use [ParquetBlocks] to read large files one row group at a time.
+        pub fn from_parquet(bytes: impl Into<Bytes>) -> Result<Self, ParquetError> {
+            let builder = ParquetRecordBatchReaderBuilder::try_new(bytes.into())?;
+            let schema = builder.schema().clone();
+            let batches = builder.build()?.collect::<Result<Vec<_>, ArrowError>>()?;
+            let batch = arrow::compute::concat_batches(&schema, &batches)?;
+            Ok(Self::from_record_batch(&batch)?)
+        }
+    }
+
+    /// Read a Parquet file as blocks, one block for each row group.
+    ///
+    /// ```rust,ignore
+    /// for block in ParquetBlocks::new(std::fs::read("meters.parquet")?)? {
+    ///     taos.write_raw_block_to("meters", &block?)?;
+    /// }
+    /// ```
+    pub struct ParquetBlocks {
+        bytes: Bytes,
+        metadata: ArrowReaderMetadata,
+        row_group: usize,
+    }
+
+    impl ParquetBlocks {
+        /// Read the file metadata, row groups are decoded when iterating.
+        pub fn new(bytes: impl Into<Bytes>) -> Result<Self, ParquetError> {
+            let bytes = bytes.into();
+            let metadata = ArrowReaderMetadata::load(&bytes, Default::default())?;
+            Ok(Self {
+                bytes,
+                metadata,
+                row_group: 0,
+            })
+        }
+
+        /// Number of row groups in the file.
+        pub fn row_groups(&self) -> usize {
+            self.metadata.metadata().num_row_groups()
+        }
+
+        /// Decode the `index`-th row group as a block.
+        pub fn read_row_group(&self, index: usize) -> Result<RawBlock, ParquetError> {
+            let row_group = self
+                .metadata
+                .metadata()
+                .row_groups()
+                .get(index)
+                .ok_or_else(|| ParquetError::General(format!("row group {index} out of range")))?;
+            let rows = row_group.num_rows() as usize;
+            let reader = ParquetRecordBatchReaderBuilder::new_with_metadata(
+                self.bytes.clone(),
+                self.metadata.clone(),
+            )
+            .with_row_groups(vec![index])
+            .with_batch_size(rows.max(1))
+            .build()?;
+            let batches = reader.collect::<Result<Vec<_>, ArrowError>>()?;
+            let batch = arrow::compute::concat_batches(self.metadata.schema(), &batches)?;
+            Ok(RawBlock::from_record_batch(&batch)?)
+        }
+    }
+
+    impl Iterator for ParquetBlocks {
+        type Item = Result<RawBlock, ParquetError>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.row_group >= self.row_groups() {
+                return None;
+            }
+            let block = self.read_row_group(self.row_group);
+            self.row_group += 1;
+            Some(block)
+        }
+    }
+
+    /// Export all blocks of a result set into `writer` as a Parquet file, returns the rows written.
+    ///
+    /// ```rust,ignore
+    /// let mut rs = taos.query("select * from meters")?;
+    /// let rows = write_parquet(&mut rs, std::fs::File::create("meters.parquet")?)?;
+    /// ```
+    pub fn write_parquet<F: Fetchable, W: Write + Send>(
+        rs: &mut F,
+        writer: W,
+    ) -> Result<usize, F::Error> {
+        let writer =
+            ParquetWriter::new(writer, rs.fields(), rs.precision()).map_err(codec_error)?;
+        write_blocks(rs, writer)
+    }
+
+    /// Async version of [write_parquet], `writer` is still synchronous.
+    #[cfg(feature = "async")]
+    pub async fn write_parquet_async<F: crate::AsyncFetchable, W: Write + Send>(
+        rs: &mut F,
+        writer: W,
+    ) -> Result<usize, F::Error> {
+        let writer =
+            ParquetWriter::new(writer, rs.fields(), rs.precision()).map_err(codec_error)?;
+        super::write_blocks_async(rs, writer).await
+    }
+
+    #[test]
+    fn test_parquet_codec() {
+        use crate::{BlockCodec, CodecOpts};
+
+        let views = vec![
+            ColumnView::from_millis_timestamp(vec![Some(1654570964022), None]),
+            ColumnView::from_ints(vec![Some(1), None]),
+            ColumnView::from_nchar(vec![None, Some("涛思数据")]),
+        ];
+        let mut raw = RawBlock::from_views(&views, Precision::Millisecond);
+        raw.with_field_names(["ts", "v", "n"])
+            .with_table_name("tb1");
+
+        for codec in [CodecOpts::Raw, CodecOpts::Parquet] {
+            let bytes = raw.encode(codec).unwrap();
+            let back = RawBlock::decode(&bytes, codec).unwrap();
+            assert_eq!(back.field_names(), raw.field_names());
+            assert_eq!(back.table_name(), Some("tb1"));
+            assert_eq!(back.to_values(), raw.to_values());
+        }
+
+        let reader =
+            ParquetRecordBatchReaderBuilder::try_new(Bytes::from(raw.to_parquet().unwrap()))
+                .unwrap();
+        let metadata = reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap();
+        let fields = metadata
+            .iter()
+            .find(|kv| kv.key == TAOS_SCHEMA_KEY)
+            .and_then(|kv| kv.value.as_deref())
+            .unwrap();
+        let fields: Vec<Field> = serde_json::from_str(fields).unwrap();
+        assert_eq!(fields, raw.fields());
+    }
+
+    #[test]
+    fn test_parquet_blocks() {
+        let blocks: Vec<_> = [vec![Some(1), None], vec![Some(3)]]
+            .into_iter()
+            .map(|ints| {
+                let views = vec![ColumnView::from_ints(ints)];
+                let mut raw = RawBlock::from_views(&views, Precision::Millisecond);
+                raw.with_field_names(["v"]);
+                raw
+            })
+            .collect();
+
+        let mut writer =
+            ParquetWriter::new(Vec::new(), &blocks[0].fields(), Precision::Millisecond).unwrap();
+        for block in &blocks {
+            writer.write_block(block).unwrap();
+            writer.flush().unwrap();
+        }
+        let bytes = writer.into_inner().unwrap();
+
+        let reader = ParquetBlocks::new(bytes.clone()).unwrap();
+        assert_eq!(reader.row_groups(), 2);
+        assert!(reader.read_row_group(2).is_err());
+        let read: Vec<_> = reader.map(Result::unwrap).collect();
+        assert_eq!(read.len(), 2);
+        for (read, block) in read.iter().zip(&blocks) {
+            assert_eq!(read.to_values(), block.to_values());
+        }
+
+        let all = RawBlock::from_parquet(bytes).unwrap();
+        assert_eq!(all.nrows(), 3);
+    }
+}
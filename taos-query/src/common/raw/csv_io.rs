@@ -0,0 +1,387 @@
+//! CSV export of raw blocks and result sets, and CSV import into column views for stmt binding.
+use std::io::{Read, Write};
+
+use chrono::{FixedOffset, Local, TimeZone};
+use serde_json::{Map, Value as JsonValue};
+
+use super::codec::{codec_error, write_blocks, BlockSink};
+use super::{ColumnView, RawBlock};
+use crate::common::{BorrowedValue, Field, Precision, Timestamp, Ty};
+use crate::stmt::{rows_to_views, RowsError};
+use crate::Fetchable;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CsvError {
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error(transparent)]
+    Rows(#[from] RowsError),
+    #[error("unknown column `{0}` in csv header")]
+    UnknownColumn(String),
+    #[error("missing column `{0}` in csv header")]
+    MissingColumn(String),
+}
+
+/// How timestamps are written in CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// The raw integer in the precision of the block.
+    Raw,
+    /// RFC3339 string with fractional seconds of the precision, like `2022-06-07T11:02:44.022+08:00`.
+    Rfc3339,
+}
+
+/// Options of CSV import and export.
+///
+/// NULL is an empty string by default, so empty VARCHAR or NCHAR values are written as and read
+/// back as NULL, use [CsvOptions::with_null] like `NULL` to keep them apart.
+///
+/// ```rust,ignore
+/// let opts = CsvOptions::default()
+///     .with_delimiter(b'\t')
+///     .with_null("NULL")
+///     .with_timezone(FixedOffset::east_opt(8 * 3600).unwrap());
+/// block.write_csv(std::io::stdout(), &opts)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    delimiter: u8,
+    header: bool,
+    lenient_header: bool,
+    null: String,
+    timestamp: TimestampFormat,
+    timezone: Option<FixedOffset>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            header: true,
+            lenient_header: false,
+            null: String::new(),
+            timestamp: TimestampFormat::Rfc3339,
+            timezone: None,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Field delimiter, default is `,`.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Write or expect a header line of field names, default is true.
+    ///
+    /// When reading with header, columns are matched to fields by name.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Skip header columns that are not fields and read missing fields as NULL, default is false.
+    ///
+    /// Strict headers fail on unknown or missing columns, so typos in the header are not read
+    /// as NULL silently.
+    pub fn with_lenient_header(mut self, lenient: bool) -> Self {
+        self.lenient_header = lenient;
+        self
+    }
+
+    /// Representation of NULL, default is an empty string which is the same as empty strings.
+    pub fn with_null(mut self, null: impl Into<String>) -> Self {
+        self.null = null.into();
+        self
+    }
+
+    /// Timestamp format, default is [TimestampFormat::Rfc3339].
+    pub fn with_timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.timestamp = format;
+        self
+    }
+
+    /// Timezone of RFC3339 timestamps, default is the local timezone.
+    pub fn with_timezone(mut self, timezone: FixedOffset) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    fn writer<W: Write>(&self, wtr: W) -> csv::Writer<W> {
+        csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(false)
+            .from_writer(wtr)
+    }
+
+    fn format_timestamp(&self, ts: Timestamp) -> String {
+        let format = ts.precision().to_seconds_format();
+        match (self.timestamp, self.timezone) {
+            (TimestampFormat::Raw, _) => ts.as_raw_i64().to_string(),
+            (TimestampFormat::Rfc3339, Some(tz)) => tz
+                .from_utc_datetime(&ts.to_naive_datetime())
+                .to_rfc3339_opts(format, false),
+            (TimestampFormat::Rfc3339, None) => Local
+                .from_utc_datetime(&ts.to_naive_datetime())
+                .to_rfc3339_opts(format, false),
+        }
+    }
+
+    fn format(&self, value: BorrowedValue) -> String {
+        match value {
+            BorrowedValue::Null => self.null.clone(),
+            BorrowedValue::Bool(v) => v.to_string(),
+            BorrowedValue::TinyInt(v) => v.to_string(),
+            BorrowedValue::SmallInt(v) => v.to_string(),
+            BorrowedValue::Int(v) => v.to_string(),
+            BorrowedValue::BigInt(v) => v.to_string(),
+            BorrowedValue::UTinyInt(v) => v.to_string(),
+            BorrowedValue::USmallInt(v) => v.to_string(),
+            BorrowedValue::UInt(v) => v.to_string(),
+            BorrowedValue::UBigInt(v) => v.to_string(),
+            BorrowedValue::Float(v) => v.to_string(),
+            BorrowedValue::Double(v) => v.to_string(),
+            BorrowedValue::Timestamp(ts) => self.format_timestamp(ts),
+            BorrowedValue::VarChar(v) => v.to_string(),
+            BorrowedValue::NChar(v) => v.into_owned(),
+            BorrowedValue::Json(v) => String::from_utf8_lossy(&v).into_owned(),
+            BorrowedValue::Decimal(v) => v.to_string(),
+            value @ (BorrowedValue::VarBinary(_)
+            | BorrowedValue::Blob(_)
+            | BorrowedValue::MediumBlob(_)) => format!("{value}"),
+        }
+    }
+
+    /// Convert a cell to a JSON value by the field type, unparsable cells are kept as strings
+    /// to be reported as mismatched.
+    fn parse(&self, cell: &str, ty: Ty) -> JsonValue {
+        if cell == self.null {
+            return JsonValue::Null;
+        }
+        let value = match ty {
+            Ty::Bool => match cell.to_ascii_lowercase().as_str() {
+                "true" | "1" => Some(JsonValue::Bool(true)),
+                "false" | "0" => Some(JsonValue::Bool(false)),
+                _ => None,
+            },
+            Ty::TinyInt | Ty::SmallInt | Ty::Int | Ty::BigInt | Ty::Timestamp => {
+                cell.parse::<i64>().ok().map(JsonValue::from)
+            }
+            Ty::UTinyInt | Ty::USmallInt | Ty::UInt | Ty::UBigInt => {
+                cell.parse::<u64>().ok().map(JsonValue::from)
+            }
+            Ty::Float | Ty::Double => cell.parse::<f64>().ok().map(JsonValue::from),
+            _ => None,
+        };
+        value.unwrap_or_else(|| JsonValue::String(cell.to_string()))
+    }
+}
+
+impl RawBlock {
+    /// Write the block as CSV, returns the rows written.
+    pub fn write_csv<W: Write>(&self, wtr: W, opts: &CsvOptions) -> Result<usize, csv::Error> {
+        let mut wtr = opts.writer(wtr);
+        if opts.header {
+            wtr.write_record(self.fields().iter().map(Field::name))?;
+        }
+        self.write_csv_records(&mut wtr, opts)?;
+        wtr.flush()?;
+        Ok(self.nrows())
+    }
+
+    fn write_csv_records<W: Write>(
+        &self,
+        wtr: &mut csv::Writer<W>,
+        opts: &CsvOptions,
+    ) -> Result<(), csv::Error> {
+        for row in 0..self.nrows() {
+            wtr.write_record(
+                (0..self.ncols())
+                    .map(|col| opts.format(unsafe { self.get_ref_unchecked(row, col) })),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// CSV writer of blocks with the same fields, the header is written at first.
+struct CsvSink<'a, W: Write> {
+    wtr: csv::Writer<W>,
+    opts: &'a CsvOptions,
+    rows: usize,
+}
+
+impl<'a, W: Write> CsvSink<'a, W> {
+    fn new(wtr: W, fields: &[Field], opts: &'a CsvOptions) -> Result<Self, csv::Error> {
+        let mut wtr = opts.writer(wtr);
+        if opts.header {
+            wtr.write_record(fields.iter().map(Field::name))?;
+        }
+        Ok(Self { wtr, opts, rows: 0 })
+    }
+}
+
+impl<W: Write> BlockSink for CsvSink<'_, W> {
+    fn write_block(&mut self, block: &RawBlock) -> Result<(), taos_error::Error> {
+        block
+            .write_csv_records(&mut self.wtr, self.opts)
+            .map_err(codec_error)?;
+        self.rows += block.nrows();
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<usize, taos_error::Error> {
+        self.wtr.flush().map_err(codec_error)?;
+        Ok(self.rows)
+    }
+}
+
+/// Export all blocks of a result set into `wtr` as CSV, returns the rows written.
+///
+/// ```rust,ignore
+/// let mut rs = taos.query("select * from meters")?;
+/// let rows = write_csv(&mut rs, std::fs::File::create("meters.csv")?, &CsvOptions::default())?;
+/// ```
+pub fn write_csv<F: Fetchable, W: Write>(
+    rs: &mut F,
+    wtr: W,
+    opts: &CsvOptions,
+) -> Result<usize, F::Error> {
+    let sink = CsvSink::new(wtr, rs.fields(), opts).map_err(codec_error)?;
+    write_blocks(rs, sink)
+}
+
+/// Async version of [write_csv], `wtr` is still synchronous.
+#[cfg(feature = "async")]
+pub async fn write_csv_async<F: crate::AsyncFetchable, W: Write>(
+    rs: &mut F,
+    wtr: W,
+    opts: &CsvOptions,
+) -> Result<usize, F::Error> {
+    let sink = CsvSink::new(wtr, rs.fields(), opts).map_err(codec_error)?;
+    super::codec::write_blocks_async(rs, sink).await
+}
+
+/// Parse CSV into column views of `fields`, to bind with stmt.
+///
+/// Cells are converted by the field types as [rows_to_views], timestamps could be integers in
+/// `precision` or RFC3339 strings. With header, columns are matched to fields by name and unknown
+/// or missing columns are errors unless [CsvOptions::with_lenient_header], otherwise by position.
+pub fn csv_to_views<R: Read>(
+    rdr: R,
+    fields: &[Field],
+    precision: Precision,
+    opts: &CsvOptions,
+) -> Result<Vec<ColumnView>, CsvError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(opts.delimiter)
+        .has_headers(opts.header)
+        .from_reader(rdr);
+    let rows: Vec<JsonValue> = if opts.header {
+        let headers = rdr.headers()?.clone();
+        let mut columns: Vec<(String, Option<Ty>)> = Vec::with_capacity(headers.len());
+        for name in headers.iter() {
+            let ty = fields.iter().find(|f| f.name() == name).map(Field::ty);
+            if ty.is_none() && !opts.lenient_header {
+                return Err(CsvError::UnknownColumn(name.to_string()));
+            }
+            columns.push((name.to_string(), ty));
+        }
+        if !opts.lenient_header {
+            if let Some(field) = fields
+                .iter()
+                .find(|f| !headers.iter().any(|name| name == f.name()))
+            {
+                return Err(CsvError::MissingColumn(field.name().to_string()));
+            }
+        }
+        rdr.records()
+            .map(|record| {
+                let record = record?;
+                let row: Map<String, JsonValue> = columns
+                    .iter()
+                    .zip(record.iter())
+                    .filter_map(|((name, ty), cell)| {
+                        ty.map(|ty| (name.clone(), opts.parse(cell, ty)))
+                    })
+                    .collect();
+                Ok(JsonValue::Object(row))
+            })
+            .collect::<Result<_, csv::Error>>()?
+    } else {
+        rdr.records()
+            .map(|record| {
+                let record = record?;
+                let row = fields
+                    .iter()
+                    .zip(record.iter())
+                    .map(|(field, cell)| opts.parse(cell, field.ty()))
+                    .collect();
+                Ok(JsonValue::Array(row))
+            })
+            .collect::<Result<_, csv::Error>>()?
+    };
+    Ok(rows_to_views(&rows, fields, precision)?)
+}
+
+#[test]
+fn test_csv() {
+    let views = vec![
+        ColumnView::from_millis_timestamp(vec![Some(1654570964022), Some(1654570964023)]),
+        ColumnView::from_ints(vec![Some(1), None]),
+        ColumnView::from_nchar(vec![Some("a,b"), None]),
+    ];
+    let mut raw = RawBlock::from_views(&views, Precision::Millisecond);
+    raw.with_field_names(["ts", "v", "n"]);
+
+    let opts = CsvOptions::default()
+        .with_null("NULL")
+        .with_timezone(FixedOffset::east_opt(8 * 3600).unwrap());
+    let mut bytes = Vec::new();
+    assert_eq!(raw.write_csv(&mut bytes, &opts).unwrap(), 2);
+    assert_eq!(
+        String::from_utf8(bytes.clone()).unwrap(),
+        "ts,v,n\n\
+         2022-06-07T11:02:44.022+08:00,1,\"a,b\"\n\
+         2022-06-07T11:02:44.023+08:00,NULL,NULL\n"
+    );
+
+    let fields = raw.fields();
+    let back = csv_to_views(bytes.as_slice(), &fields, Precision::Millisecond, &opts).unwrap();
+    let back = RawBlock::from_views(&back, Precision::Millisecond);
+    assert_eq!(back.to_values(), raw.to_values());
+
+    let opts = opts
+        .with_header(false)
+        .with_delimiter(b'\t')
+        .with_timestamp_format(TimestampFormat::Raw);
+    let mut bytes = Vec::new();
+    raw.write_csv(&mut bytes, &opts).unwrap();
+    assert!(bytes.starts_with(b"1654570964022\t1\ta,b\n"));
+    let views = csv_to_views(bytes.as_slice(), &fields, Precision::Millisecond, &opts).unwrap();
+    assert_eq!(views[1].len(), 2);
+
+    let err = csv_to_views(&b"x\t1\ta"[..], &fields, Precision::Millisecond, &opts).unwrap_err();
+    assert!(err.to_string().contains("column `ts`"));
+}
+
+#[test]
+fn test_csv_header() {
+    let fields = [
+        Field::new("ts", Ty::Timestamp, 8),
+        Field::new("v", Ty::Int, 4),
+    ];
+    let opts = CsvOptions::default();
+
+    let err =
+        csv_to_views(&b"ts,vv\n1,1\n"[..], &fields, Precision::Millisecond, &opts).unwrap_err();
+    assert_eq!(err.to_string(), "unknown column `vv` in csv header");
+    let err = csv_to_views(&b"ts\n1\n"[..], &fields, Precision::Millisecond, &opts).unwrap_err();
+    assert_eq!(err.to_string(), "missing column `v` in csv header");
+
+    let opts = opts.with_lenient_header(true);
+    let views = csv_to_views(&b"vv,ts\n1,1\n"[..], &fields, Precision::Millisecond, &opts).unwrap();
+    let raw = RawBlock::from_views(&views, Precision::Millisecond);
+    assert_eq!(raw.to_values()[0][1], crate::common::Value::Null);
+}
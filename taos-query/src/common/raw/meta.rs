@@ -409,6 +409,29 @@ pub enum JsonMeta {
     Drop(MetaDrop),
 }
 
+impl JsonMeta {
+    /// Rename all tables in the meta, including the super table used by a child table.
+    pub fn rename_tables<F: FnMut(&str) -> String>(&mut self, mut rename: F) {
+        match self {
+            JsonMeta::Create(MetaCreate::Super { table_name, .. })
+            | JsonMeta::Create(MetaCreate::Normal { table_name, .. })
+            | JsonMeta::Drop(MetaDrop::Super { table_name }) => *table_name = rename(table_name),
+            JsonMeta::Create(MetaCreate::Child {
+                table_name, using, ..
+            }) => {
+                *table_name = rename(table_name);
+                *using = rename(using);
+            }
+            JsonMeta::Alter(alter) => alter.table_name = rename(&alter.table_name),
+            JsonMeta::Drop(MetaDrop::Other { table_name_list }) => {
+                for name in table_name_list {
+                    *name = rename(name);
+                }
+            }
+        }
+    }
+}
+
 impl Display for JsonMeta {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -36,6 +36,20 @@ mod de;
 mod rows;
 pub use rows::*;
 
+mod codec;
+pub use codec::*;
+
+mod csv_io;
+pub use csv_io::*;
+
+mod pretty;
+pub use pretty::*;
+
+#[cfg(feature = "arrow")]
+mod record_batch;
+#[cfg(feature = "arrow")]
+pub use record_batch::*;
+
 /// Raw data block format (B for bytes):
 ///
 /// ```text,ignore
@@ -80,23 +94,18 @@ pub struct RawBlock {
 unsafe impl Send for RawBlock {}
 unsafe impl Sync for RawBlock {}
 
+/// Debug the block metadata and fields only, rows are printed by [RawBlock::pretty].
 impl Debug for RawBlock {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // todo: more helpful debug impl.
-        f.debug_struct("Raw")
-            .field("layout", &self.layout)
+        f.debug_struct("RawBlock")
             .field("version", &self.version)
-            .field("data", &"...")
             .field("rows", &self.rows)
             .field("cols", &self.cols)
             .field("precision", &self.precision)
+            .field("database", &self.database)
             .field("table", &self.table)
-            .field("fields", &self.fields)
-            // .field("raw_fields", &self.raw_fields)
             .field("group_id", &self.group_id)
-            .field("schemas", &self.schemas)
-            .field("lengths", &self.lengths)
-            .field("columns", &self.columns)
+            .field("fields", &self.fields())
             .finish()
     }
 }
@@ -124,13 +133,14 @@ impl RawBlock {
         todo!()
     }
 
+    /// Parse a v2 block of `fields`, DECIMAL columns are not supported in v2.
     pub fn parse_from_raw_block_v2(
         bytes: impl Into<Bytes>,
         fields: &[Field],
         lengths: &[u32],
         rows: usize,
         precision: Precision,
-    ) -> Self {
+    ) -> Result<Self, taos_error::Error> {
         use bytes::BufMut;
         debug_assert_eq!(fields.len(), lengths.len());
 
@@ -268,7 +278,7 @@ impl RawBlock {
                 // Float columns.
                 Ty::Float => _primitive_view!(Float, f32),
                 Ty::Double => _primitive_view!(Double, f64),
-                Ty::VarChar => {
+                Ty::VarChar | Ty::VarBinary | Ty::Blob | Ty::MediumBlob => {
                     let start = offset;
                     offset += *length as usize * rows;
                     let data = bytes.slice(start..offset);
@@ -285,7 +295,12 @@ impl RawBlock {
                         }
                     }));
 
-                    columns.push(ColumnView::VarChar(VarCharView { offsets, data }));
+                    columns.push(match field.ty() {
+                        Ty::VarBinary => ColumnView::VarBinary(VarBinaryView { offsets, data }),
+                        Ty::Blob => ColumnView::Blob(VarBinaryView { offsets, data }),
+                        Ty::MediumBlob => ColumnView::MediumBlob(VarBinaryView { offsets, data }),
+                        _ => ColumnView::VarChar(VarCharView { offsets, data }),
+                    });
 
                     data_lengths[i] = *length as u32 * rows as u32;
                 }
@@ -366,14 +381,19 @@ impl RawBlock {
 
                     data_lengths[i] = *length as u32 * rows as u32;
                 }
-                Ty::VarBinary => todo!(),
-                Ty::Decimal => todo!(),
-                Ty::Blob => todo!(),
-                Ty::MediumBlob => todo!(),
+                Ty::Decimal | Ty::Decimal64 => {
+                    return Err(taos_error::Error::new(
+                        taos_error::Code::Failed,
+                        format!(
+                            "DECIMAL column `{}` is not supported in v2 blocks",
+                            field.name()
+                        ),
+                    ))
+                }
             }
         }
 
-        Self {
+        Ok(Self {
             layout,
             version: Version::V2,
             data: Cell::new(bytes),
@@ -388,7 +408,13 @@ impl RawBlock {
             columns,
             group_id: 0,
             // raw_fields: Vec::new(),
-        }
+        })
+    }
+
+    /// Build a block of column views, rows are the length of the first view.
+    pub fn from_views(views: &[ColumnView], precision: Precision) -> Self {
+        let rows = views.first().map_or(0, ColumnView::len);
+        Self::parse_from_raw_block(views_to_raw_block(views), rows, views.len(), precision)
     }
 
     pub fn parse_from_raw_block(
@@ -444,7 +470,7 @@ impl RawBlock {
                 Ty::BigInt => _primitive_value!(BigInt, i64),
                 Ty::Float => _primitive_value!(Float, f32),
                 Ty::Double => _primitive_value!(Double, f64),
-                Ty::VarChar => {
+                Ty::VarChar | Ty::VarBinary | Ty::Blob | Ty::MediumBlob => {
                     let o1 = data_offset;
                     let o2 = data_offset + std::mem::size_of::<i32>() * rows;
                     data_offset = o2 + length;
@@ -452,7 +478,12 @@ impl RawBlock {
                     let offsets = Offsets::from(bytes.slice(o1..o2));
                     let data = bytes.slice(o2..data_offset);
 
-                    ColumnView::VarChar(VarCharView { offsets, data })
+                    match schema.ty {
+                        Ty::VarBinary => ColumnView::VarBinary(VarBinaryView { offsets, data }),
+                        Ty::Blob => ColumnView::Blob(VarBinaryView { offsets, data }),
+                        Ty::MediumBlob => ColumnView::MediumBlob(VarBinaryView { offsets, data }),
+                        _ => ColumnView::VarChar(VarCharView { offsets, data }),
+                    }
                 }
                 Ty::Timestamp => {
                     let o1 = data_offset;
@@ -486,6 +517,20 @@ impl RawBlock {
                 Ty::USmallInt => _primitive_value!(USmallInt, u16),
                 Ty::UInt => _primitive_value!(UInt, u32),
                 Ty::UBigInt => _primitive_value!(UBigInt, u64),
+                Ty::Decimal | Ty::Decimal64 => {
+                    // 16-byte DECIMAL or 8-byte DECIMAL64 values as scaled integers.
+                    let (precision, scale) = views::decimal_precision_scale(schema.len);
+                    let o1 = data_offset;
+                    let o2 = data_offset + ((rows + 7) >> 3);
+                    data_offset = o2 + rows * schema.ty.fixed_length();
+                    ColumnView::Decimal(DecimalView {
+                        ty: schema.ty,
+                        nulls: NullBits(bytes.slice(o1..o2)),
+                        data: bytes.slice(o2..data_offset),
+                        precision,
+                        scale,
+                    })
+                }
                 Ty::Json => {
                     let o1 = data_offset;
                     let o2 = data_offset + std::mem::size_of::<i32>() * rows;
@@ -609,6 +654,22 @@ impl RawBlock {
         &self.fields
     }
 
+    /// Fields of the block, names are `c{index}` if not set.
+    pub fn fields(&self) -> Vec<Field> {
+        self.schemas()
+            .iter()
+            .enumerate()
+            .map(|(col, schema)| {
+                let name = self
+                    .fields
+                    .get(col)
+                    .cloned()
+                    .unwrap_or_else(|| format!("c{col}"));
+                Field::new(name, schema.ty, schema.len)
+            })
+            .collect()
+    }
+
     /// Data view in columns.
     #[inline]
     pub fn columns(&self) -> std::slice::Iter<ColumnView> {
@@ -718,15 +779,44 @@ impl RawBlock {
         self.rows().map(|row| row.into_values()).collect_vec()
     }
 
-    pub fn write<W: std::io::Write>(&self, _wtr: W) -> std::io::Result<usize> {
-        todo!()
+    /// Write the block as CSV with default [CsvOptions], returns the rows written.
+    pub fn write<W: std::io::Write>(&self, wtr: W) -> std::io::Result<usize> {
+        Ok(self.write_csv(wtr, &CsvOptions::default())?)
+    }
+}
+
+/// Check that the columns of a raw block are in `bytes`, as [RawBlock::parse_from_raw_block] does
+/// not check bounds.
+fn check_raw_block(bytes: &[u8], rows: usize, cols: usize) -> Result<(), String> {
+    const SCHEMA_OFFSET: usize = std::mem::size_of::<u32>() + std::mem::size_of::<u64>();
+    let schema_end = SCHEMA_OFFSET + cols * std::mem::size_of::<ColSchema>();
+    let lengths_end = schema_end + cols * std::mem::size_of::<u32>();
+    if bytes.len() < lengths_end {
+        return Err(format!("raw block of {cols} columns is truncated"));
+    }
+    let mut offset = lengths_end;
+    for col in 0..cols {
+        let ty = bytes[SCHEMA_OFFSET + col * std::mem::size_of::<ColSchema>()];
+        let at = schema_end + col * std::mem::size_of::<u32>();
+        let length = u32::from_ne_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+        offset += match ty {
+            1..=7 | 9 | 11..=14 | 17 | 21 => ((rows + 7) >> 3) + rows * Ty::from(ty).fixed_length(),
+            8 | 10 | 15 | 16 | 18 | 19 => rows * std::mem::size_of::<i32>() + length as usize,
+            _ => return Err(format!("unsupported type {ty} of column {col}")),
+        };
+        if offset > bytes.len() {
+            return Err(format!("column {col} of raw block is truncated"));
+        }
     }
+    Ok(())
 }
 
 impl Inlinable for RawBlock {
     fn read_inlined<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
         let layout = reader.read_u32()?;
-        let layout = Layout::from_bits(layout).expect("should be layout");
+        let layout = Layout::from_bits(layout).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid raw block layout")
+        })?;
 
         let precision = layout.precision();
 
@@ -745,6 +835,8 @@ impl Inlinable for RawBlock {
             .try_collect()?;
 
         let bytes = reader.read_inlined_bytes::<4>()?;
+        check_raw_block(&bytes, rows, cols)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
 
         let mut raw = Self::parse_from_raw_block(bytes, rows, cols, precision);
 
@@ -790,6 +882,8 @@ impl Inlinable for RawBlock {
             .try_collect()?;
 
         let bytes = reader.read_inlined_bytes::<4>()?;
+        check_raw_block(&bytes, rows, cols)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
 
         let mut raw = Self::parse_from_raw_block(bytes, rows, cols, precision);
 
@@ -983,7 +1077,8 @@ fn test_raw_from_v2() {
         &[4, 4],
         10,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     assert!(block.lengths.deref() == &[40, 40]);
 
     let bytes = include_bytes!("../../../tests/test.txt");
@@ -1001,7 +1096,8 @@ fn test_raw_from_v2() {
         &[8, 4, 4, 4, 4, 18],
         10,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
 
     #[derive(Debug, serde::Deserialize)]
     struct Record {
@@ -1059,7 +1155,8 @@ fn test_v2_full() {
         ],
         4,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     dbg!(block);
 }
 
@@ -1071,7 +1168,8 @@ fn test_v2_null() {
         &[1],
         2,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     dbg!(&raw);
     let (_ty, _len, null) = unsafe { raw.get_raw_value_unchecked(0, 0) };
     assert!(null.is_null());
@@ -1084,7 +1182,8 @@ fn test_v2_null() {
         &[1],
         1,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     dbg!(&raw);
     let (_ty, _len, null) = unsafe { raw.get_raw_value_unchecked(0, 0) };
     assert!(null.is_null());
@@ -1095,7 +1194,8 @@ fn test_v2_null() {
         &[4],
         1,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     dbg!(&raw);
     let (_ty, _len, null) = unsafe { raw.get_raw_value_unchecked(0, 0) };
     assert!(null.is_null());
@@ -1106,7 +1206,8 @@ fn test_v2_null() {
         &[4],
         1,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     let (_ty, _len, null) = unsafe { raw.get_raw_value_unchecked(0, 0) };
     dbg!(raw);
     assert!(null.is_null());
@@ -1119,7 +1220,8 @@ fn test_from_v2() {
         &[1],
         1,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     let bytes = raw.as_raw_bytes();
     let bytes = Bytes::copy_from_slice(bytes);
     let raw2 = RawBlock::parse_from_raw_block(bytes, raw.nrows(), raw.ncols(), raw.precision());
@@ -1134,7 +1236,8 @@ fn test_from_v2() {
         &[4],
         1,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     dbg!(&raw);
     // dbg!(raw.as_bytes());
     // let v = unsafe { raw.get_ref_unchecked(0, 0) };
@@ -1146,7 +1249,8 @@ fn test_from_v2() {
         &[4],
         1,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     dbg!(&raw);
     // dbg!(raw.as_bytes());
     // let v = unsafe { raw.get_ref_unchecked(0, 0) };
@@ -1158,7 +1262,8 @@ fn test_from_v2() {
         &[4],
         1,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     dbg!(&raw);
     let raw = RawBlock::parse_from_raw_block_v2(
         &[1, 1, 1][..],
@@ -1169,6 +1274,80 @@ fn test_from_v2() {
         &[1, 2],
         1,
         Precision::Millisecond,
-    );
+    )
+    .unwrap();
     dbg!(&raw);
 }
+
+#[test]
+fn test_binary_and_decimal() {
+    use crate::common::Decimal;
+
+    let views = vec![
+        ColumnView::from_var_binary(vec![Some(b"\x00\x01".as_slice()), None]),
+        ColumnView::from_decimals(vec![Some(rust_decimal::Decimal::new(12345, 2)), None]),
+        ColumnView::from_blobs(vec![None, Some(b"blob".as_slice())]),
+    ];
+    let raw = RawBlock::from_views(&views, Precision::Millisecond);
+    assert_eq!(
+        raw.to_values(),
+        vec![
+            vec![
+                Value::VarBinary(vec![0, 1]),
+                Value::Decimal(Decimal::new(12345, 38, 2)),
+                Value::Null,
+            ],
+            vec![Value::Null, Value::Null, Value::Blob(b"blob".to_vec())],
+        ]
+    );
+}
+
+#[test]
+fn test_decimal_block() {
+    use crate::common::Decimal;
+
+    // A block of DECIMAL(10, 2) and DECIMAL(20, 3) columns in the server layout: the first is
+    // DECIMAL64 (type 21) of 8-byte values, the second is DECIMAL (type 17) of 16-byte values,
+    // schema lengths are `width | precision << 16 | scale << 24`.
+    static BYTES: &[u8; 82] = b"\x52\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x00\x15\x00\x08\x00\x0a\x02\x11\x00\x10\x00\x14\x03\x10\x00\x00\x00\x20\x00\x00\x00\x40\xc7\xcf\xff\xff\xff\xff\xff\xff\x00\x00\x00\x00\x00\x00\x00\x00\x00\xd2\x0a\x1f\xeb\x8c\xa9\x54\xab\x00\x00\x00\x00\x00\x00\x00\x00\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff";
+
+    let mut raw =
+        RawBlock::parse_from_raw_block(Bytes::from_static(BYTES), 2, 2, Precision::Millisecond);
+    raw.with_field_names(["d64", "d128"]);
+    let fields = raw.fields();
+    assert_eq!(fields[0].ty(), Ty::Decimal64);
+    assert_eq!(views::decimal_precision_scale(fields[0].bytes()), (10, 2));
+    assert_eq!(fields[1].ty(), Ty::Decimal);
+    assert_eq!(views::decimal_precision_scale(fields[1].bytes()), (20, 3));
+    assert_eq!(
+        raw.to_values(),
+        vec![
+            vec![
+                Value::Decimal(Decimal::new(-12345, 10, 2)),
+                Value::Decimal(Decimal::new(12345678901234567890, 20, 3)),
+            ],
+            vec![Value::Null, Value::Decimal(Decimal::new(-1, 20, 3))],
+        ]
+    );
+    assert_eq!(
+        raw.get_ref(0, 1).unwrap().to_string().unwrap(),
+        "12345678901234567.890"
+    );
+
+    let views = [
+        ColumnView::Decimal(DecimalView::from_scaled([Some(-12345), None], 10, 2)),
+        ColumnView::Decimal(DecimalView::from_scaled(
+            [Some(12345678901234567890), Some(-1)],
+            20,
+            3,
+        )),
+    ];
+    let block = RawBlock::from_views(&views, Precision::Millisecond);
+    assert_eq!(block.to_values(), raw.to_values());
+    // Same bytes except the high byte of the int16 types, which is not written.
+    let bytes = block.as_raw_bytes();
+    assert_eq!(bytes.len(), BYTES.len());
+    for range in [0..13, 14..19, 20..BYTES.len()] {
+        assert_eq!(bytes[range.clone()], BYTES[range]);
+    }
+}
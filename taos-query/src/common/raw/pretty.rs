@@ -0,0 +1,221 @@
+//! Human-readable table rendering of [RawBlock].
+use std::fmt::{self, Display};
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::RawBlock;
+use crate::common::{BorrowedValue, Precision};
+
+const NULL: &str = "NULL";
+const ELLIPSIS: &str = "...";
+
+/// A [RawBlock] rendered as a text table, created by [RawBlock::pretty].
+///
+/// ```text
+/// +-------------------------+------+----------+
+/// | ts                      | v    | n        |
+/// +-------------------------+------+----------+
+/// | 2022-06-07 03:02:44.022 |    1 | 涛思数据 |
+/// | 2022-06-07 03:02:44.023 | NULL | NULL     |
+/// +-------------------------+------+----------+
+/// ```
+///
+/// Timestamps are printed in UTC with the fractional seconds of the block precision, numbers are
+/// right aligned and strings longer than the max width are truncated with `...`.
+#[derive(Debug, Clone, Copy)]
+pub struct PrettyBlock<'a> {
+    block: &'a RawBlock,
+    max_rows: Option<usize>,
+    max_width: usize,
+}
+
+impl<'a> PrettyBlock<'a> {
+    /// Print at most `max_rows` rows, the rest are summarized in a footer line.
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Max display width of a cell, default is 32.
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width.max(ELLIPSIS.len() + 1);
+        self
+    }
+
+    fn format(&self, value: BorrowedValue) -> String {
+        let s = match value {
+            BorrowedValue::Null => return NULL.to_string(),
+            BorrowedValue::Timestamp(ts) => {
+                let format = match ts.precision() {
+                    Precision::Millisecond => "%Y-%m-%d %H:%M:%S%.3f",
+                    Precision::Microsecond => "%Y-%m-%d %H:%M:%S%.6f",
+                    Precision::Nanosecond => "%Y-%m-%d %H:%M:%S%.9f",
+                };
+                return ts.to_naive_datetime().format(format).to_string();
+            }
+            BorrowedValue::VarChar(v) => v.to_string(),
+            BorrowedValue::NChar(v) => v.into_owned(),
+            BorrowedValue::Json(v) => String::from_utf8_lossy(&v).into_owned(),
+            value @ (BorrowedValue::VarBinary(_)
+            | BorrowedValue::Blob(_)
+            | BorrowedValue::MediumBlob(_)) => format!("{value}"),
+            BorrowedValue::Bool(v) => return v.to_string(),
+            BorrowedValue::TinyInt(v) => return v.to_string(),
+            BorrowedValue::SmallInt(v) => return v.to_string(),
+            BorrowedValue::Int(v) => return v.to_string(),
+            BorrowedValue::BigInt(v) => return v.to_string(),
+            BorrowedValue::UTinyInt(v) => return v.to_string(),
+            BorrowedValue::USmallInt(v) => return v.to_string(),
+            BorrowedValue::UInt(v) => return v.to_string(),
+            BorrowedValue::UBigInt(v) => return v.to_string(),
+            BorrowedValue::Float(v) => return v.to_string(),
+            BorrowedValue::Double(v) => return v.to_string(),
+            BorrowedValue::Decimal(v) => return v.to_string(),
+        };
+        if s.width() <= self.max_width {
+            return s;
+        }
+        let mut truncated = String::new();
+        let mut width = 0;
+        for c in s.chars() {
+            width += c.width().unwrap_or_default();
+            if width > self.max_width - ELLIPSIS.len() {
+                break;
+            }
+            truncated.push(c);
+        }
+        truncated.push_str(ELLIPSIS);
+        truncated
+    }
+}
+
+impl Display for PrettyBlock<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let block = self.block;
+        let nrows = block.nrows();
+        let shown = self.max_rows.map_or(nrows, |max| max.min(nrows));
+
+        let header: Vec<String> = block
+            .fields()
+            .iter()
+            .map(|f| f.name().to_string())
+            .collect();
+        let numeric: Vec<bool> = block
+            .fields()
+            .iter()
+            .map(|f| f.ty().is_primitive())
+            .collect();
+        let rows: Vec<Vec<String>> = (0..shown)
+            .map(|row| {
+                (0..block.ncols())
+                    .map(|col| self.format(unsafe { block.get_ref_unchecked(row, col) }))
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = header
+            .iter()
+            .enumerate()
+            .map(|(col, name)| {
+                rows.iter()
+                    .map(|row| row[col].width())
+                    .chain(std::iter::once(name.width()))
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let separator = |f: &mut fmt::Formatter<'_>| {
+            for width in &widths {
+                write!(f, "+{}", "-".repeat(width + 2))?;
+            }
+            writeln!(f, "+")
+        };
+        let line = |f: &mut fmt::Formatter<'_>, cells: &[String], align: bool| {
+            for (col, cell) in cells.iter().enumerate() {
+                let pad = " ".repeat(widths[col] - cell.width());
+                if align && numeric[col] && cell != NULL {
+                    write!(f, "| {pad}{cell} ")?;
+                } else {
+                    write!(f, "| {cell}{pad} ")?;
+                }
+            }
+            writeln!(f, "|")
+        };
+
+        separator(f)?;
+        line(f, &header, false)?;
+        separator(f)?;
+        for row in &rows {
+            line(f, row, true)?;
+        }
+        separator(f)?;
+        if shown < nrows {
+            write!(f, "{} rows in set, {} rows not shown", nrows, nrows - shown)
+        } else {
+            write!(f, "{} rows in set", nrows)
+        }
+    }
+}
+
+impl RawBlock {
+    /// Render the block as a text table, see [PrettyBlock].
+    ///
+    /// ```rust,ignore
+    /// println!("{}", block.pretty().with_max_rows(10));
+    /// ```
+    pub fn pretty(&self) -> PrettyBlock<'_> {
+        PrettyBlock {
+            block: self,
+            max_rows: None,
+            max_width: 32,
+        }
+    }
+}
+
+/// Print all rows of the block as a table, use [RawBlock::pretty] to limit rows or cell width.
+impl Display for RawBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pretty().fmt(f)
+    }
+}
+
+#[test]
+fn test_pretty() {
+    use super::views::ColumnView;
+
+    let views = vec![
+        ColumnView::from_millis_timestamp(vec![Some(1654570964022), Some(1654570964023), None]),
+        ColumnView::from_ints(vec![Some(1), None, Some(-100)]),
+        ColumnView::from_nchar(vec![
+            Some("涛思数据"),
+            None,
+            Some("a long long long string"),
+        ]),
+    ];
+    let mut raw = RawBlock::from_views(&views, Precision::Millisecond);
+    raw.with_field_names(["ts", "v", "n"]);
+
+    assert_eq!(
+        raw.pretty().with_max_width(12).to_string(),
+        "\
++-------------------------+------+--------------+
+| ts                      | v    | n            |
++-------------------------+------+--------------+
+| 2022-06-07 03:02:44.022 |    1 | 涛思数据     |
+| 2022-06-07 03:02:44.023 | NULL | NULL         |
+| NULL                    | -100 | a long lo... |
++-------------------------+------+--------------+
+3 rows in set"
+    );
+    assert_eq!(raw.to_string(), raw.pretty().to_string());
+    assert_eq!(
+        raw.pretty().with_max_rows(1).to_string(),
+        "\
++-------------------------+---+----------+
+| ts                      | v | n        |
++-------------------------+---+----------+
+| 2022-06-07 03:02:44.022 | 1 | 涛思数据 |
++-------------------------+---+----------+
+3 rows in set, 2 rows not shown"
+    );
+}
@@ -0,0 +1,489 @@
+//! Conversion between [RawBlock] and Arrow [RecordBatch], enabled by feature `arrow`.
+//!
+//! Fixed-width columns share memory with the raw block when the data is aligned, variable-length
+//! columns are copied since the raw layout (offsets to length-prefixed values) differs from Arrow.
+//! The TDengine data type of each field is kept in field metadata with key [TAOS_TYPE_KEY], so that
+//! `NCHAR`/`JSON` columns (both are `Utf8` in Arrow) and `BLOB`s (`Binary`) are restored in the
+//! reverse conversion. `DECIMAL` columns are `Decimal128` with the precision and scale of the column.
+use std::collections::HashMap;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, BinaryArray, BooleanArray, PrimitiveArray, RecordBatch, StringArray,
+};
+use arrow::buffer::{Buffer, NullBuffer, ScalarBuffer};
+use arrow::datatypes::*;
+use arrow::error::ArrowError;
+use bytes::Bytes;
+
+use super::views::*;
+use super::{ColumnView, RawBlock};
+use crate::common::{Decimal, Field as TaosField, Precision, Ty};
+
+/// Field metadata key for the TDengine data type name, like `NCHAR`.
+pub const TAOS_TYPE_KEY: &str = "taos.type";
+/// Schema metadata key for the timestamp precision of the block, like `ms`.
+pub const TAOS_PRECISION_KEY: &str = "taos.precision";
+/// Schema metadata key for the table name of the block.
+pub const TAOS_TABLE_KEY: &str = "taos.table";
+
+impl Precision {
+    /// Arrow time unit of the precision.
+    pub const fn to_time_unit(self) -> TimeUnit {
+        match self {
+            Precision::Millisecond => TimeUnit::Millisecond,
+            Precision::Microsecond => TimeUnit::Microsecond,
+            Precision::Nanosecond => TimeUnit::Nanosecond,
+        }
+    }
+}
+
+impl Ty {
+    /// Arrow data type of the TDengine type, timestamps are in `precision`.
+    ///
+    /// `VARCHAR`, `NCHAR` and `JSON` are all `Utf8`, `VARBINARY` and `BLOB`s are `Binary`.
+    /// `DECIMAL` is `None` here, as its precision and scale are in the field, see
+    /// [fields_to_arrow_schema].
+    pub fn to_arrow_type(&self, precision: Precision) -> Option<DataType> {
+        Some(match self {
+            Ty::Bool => DataType::Boolean,
+            Ty::TinyInt => DataType::Int8,
+            Ty::SmallInt => DataType::Int16,
+            Ty::Int => DataType::Int32,
+            Ty::BigInt => DataType::Int64,
+            Ty::UTinyInt => DataType::UInt8,
+            Ty::USmallInt => DataType::UInt16,
+            Ty::UInt => DataType::UInt32,
+            Ty::UBigInt => DataType::UInt64,
+            Ty::Float => DataType::Float32,
+            Ty::Double => DataType::Float64,
+            Ty::Timestamp => DataType::Timestamp(precision.to_time_unit(), None),
+            Ty::VarChar | Ty::NChar | Ty::Json => DataType::Utf8,
+            Ty::VarBinary | Ty::Blob | Ty::MediumBlob => DataType::Binary,
+            Ty::Decimal | Ty::Decimal64 | Ty::Null => return None,
+        })
+    }
+}
+
+/// Arrow `Decimal128` of DECIMAL `precision` and `scale`, precision `0` means the max 38.
+fn decimal_arrow_type(precision: u8, scale: u8) -> DataType {
+    let precision = match precision {
+        0 => DECIMAL128_MAX_PRECISION,
+        precision => precision,
+    };
+    DataType::Decimal128(precision, scale as i8)
+}
+
+/// Arrow buffer of fixed-width `data`, zero-copy if the pointer is aligned to `T`.
+fn scalar_buffer<T: ArrowNativeType>(data: &Bytes) -> ScalarBuffer<T> {
+    let len = data.len() / std::mem::size_of::<T>();
+    let buffer = match NonNull::new(data.as_ptr() as *mut u8) {
+        Some(ptr) if data.as_ptr().align_offset(std::mem::align_of::<T>()) == 0 => unsafe {
+            // The cloned bytes keep the raw block data alive as the buffer owner.
+            Buffer::from_custom_allocation(ptr, data.len(), Arc::new(data.clone()))
+        },
+        _ => Buffer::from_slice_ref(data.as_ref()),
+    };
+    ScalarBuffer::new(buffer, 0, len)
+}
+
+/// Arrow validity bitmap from an iterator of is-null flags, `None` if no value is null.
+fn null_buffer(is_null: impl Iterator<Item = bool>) -> Option<NullBuffer> {
+    let nulls: NullBuffer = is_null.map(|is_null| !is_null).collect::<Vec<_>>().into();
+    (nulls.null_count() > 0).then_some(nulls)
+}
+
+impl ColumnView {
+    /// Convert to an Arrow array, see [RawBlock::to_record_batch].
+    pub fn to_arrow_array(&self) -> ArrayRef {
+        macro_rules! _primitive {
+            ($view:ident, $arrow:ty) => {
+                Arc::new(PrimitiveArray::<$arrow>::new(
+                    scalar_buffer(&$view.data),
+                    null_buffer($view.is_null_iter()),
+                ))
+            };
+        }
+        match self {
+            ColumnView::Bool(view) => Arc::new(BooleanArray::from(view.to_vec())),
+            ColumnView::TinyInt(view) => _primitive!(view, Int8Type),
+            ColumnView::SmallInt(view) => _primitive!(view, Int16Type),
+            ColumnView::Int(view) => _primitive!(view, Int32Type),
+            ColumnView::BigInt(view) => _primitive!(view, Int64Type),
+            ColumnView::UTinyInt(view) => _primitive!(view, UInt8Type),
+            ColumnView::USmallInt(view) => _primitive!(view, UInt16Type),
+            ColumnView::UInt(view) => _primitive!(view, UInt32Type),
+            ColumnView::UBigInt(view) => _primitive!(view, UInt64Type),
+            ColumnView::Float(view) => _primitive!(view, Float32Type),
+            ColumnView::Double(view) => _primitive!(view, Float64Type),
+            ColumnView::Timestamp(view) => match view.precision() {
+                Precision::Millisecond => _primitive!(view, TimestampMillisecondType),
+                Precision::Microsecond => _primitive!(view, TimestampMicrosecondType),
+                Precision::Nanosecond => _primitive!(view, TimestampNanosecondType),
+            },
+            ColumnView::VarChar(view) => Arc::new(StringArray::from(view.to_vec())),
+            ColumnView::NChar(view) => Arc::new(StringArray::from(view.to_vec())),
+            ColumnView::Json(view) => Arc::new(StringArray::from(view.to_vec())),
+            ColumnView::VarBinary(view) | ColumnView::Blob(view) | ColumnView::MediumBlob(view) => {
+                Arc::new(BinaryArray::from(view.iter().collect::<Vec<_>>()))
+            }
+            ColumnView::Decimal(view) => {
+                let values = if view.ty() == Ty::Decimal64 {
+                    // Widen 8-byte DECIMAL64 values to Decimal128.
+                    (0..view.len())
+                        .map(|row| unsafe { view.get_scaled_unchecked(row) }.unwrap_or(0))
+                        .collect()
+                } else {
+                    scalar_buffer(&view.data)
+                };
+                Arc::new(
+                    PrimitiveArray::<Decimal128Type>::new(values, null_buffer(view.is_null_iter()))
+                        .with_data_type(decimal_arrow_type(view.precision(), view.scale())),
+                )
+            }
+        }
+    }
+
+    /// Build a view from an Arrow array as type `ty`, or the default type of the array.
+    ///
+    /// Timestamps are cast to `precision`, strings are `VARCHAR` unless `ty` is `NCHAR`, `JSON` or
+    /// `DECIMAL`, binaries are `VARBINARY` unless `ty` is a `BLOB` type. Arrow decimals are
+    /// `DECIMAL` with the same precision and scale, values out of range are errors.
+    pub fn from_arrow_array(
+        array: &dyn Array,
+        ty: Option<Ty>,
+        precision: Precision,
+    ) -> Result<Self, ArrowError> {
+        macro_rules! _primitive {
+            ($view:ident, $arrow:ty) => {
+                ColumnView::$view($view::from_iter(array.as_primitive::<$arrow>().iter()))
+            };
+        }
+        Ok(match array.data_type() {
+            DataType::Boolean => ColumnView::Bool(BoolView::from_iter(array.as_boolean().iter())),
+            DataType::Int8 => _primitive!(TinyIntView, Int8Type),
+            DataType::Int16 => _primitive!(SmallIntView, Int16Type),
+            DataType::Int32 => _primitive!(IntView, Int32Type),
+            DataType::Int64 => _primitive!(BigIntView, Int64Type),
+            DataType::UInt8 => _primitive!(UTinyIntView, UInt8Type),
+            DataType::UInt16 => _primitive!(USmallIntView, UInt16Type),
+            DataType::UInt32 => _primitive!(UIntView, UInt32Type),
+            DataType::UInt64 => _primitive!(UBigIntView, UInt64Type),
+            DataType::Float32 => _primitive!(FloatView, Float32Type),
+            DataType::Float64 => _primitive!(DoubleView, Float64Type),
+            DataType::Timestamp(unit, _) => {
+                let (from, to) = (unit_scale(unit), unit_scale(&precision.to_time_unit()));
+                let scale = |v: i64| {
+                    if to >= from {
+                        v.checked_mul(to / from).ok_or_else(|| {
+                            ArrowError::ComputeError(format!(
+                                "timestamp {v} overflows when cast from {unit:?} to {precision}"
+                            ))
+                        })
+                    } else {
+                        Ok(v / (from / to))
+                    }
+                };
+                let raw = arrow::compute::cast(array, &DataType::Int64)?;
+                let values = raw
+                    .as_primitive::<Int64Type>()
+                    .iter()
+                    .map(|v| v.map(scale).transpose())
+                    .collect::<Result<Vec<_>, _>>()?;
+                ColumnView::Timestamp(match precision {
+                    Precision::Millisecond => TimestampView::from_millis(values),
+                    Precision::Microsecond => TimestampView::from_micros(values),
+                    Precision::Nanosecond => TimestampView::from_nanos(values),
+                })
+            }
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+                let array = arrow::compute::cast(array, &DataType::Utf8)?;
+                let array = array.as_string::<i32>();
+                match ty {
+                    Some(Ty::NChar) => ColumnView::NChar(NCharView::from_iter(array.iter())),
+                    Some(Ty::Json) => ColumnView::Json(JsonView::from_iter(array.iter())),
+                    Some(Ty::Decimal) => ColumnView::Decimal(parse_decimals(array)?),
+                    _ => ColumnView::VarChar(VarCharView::from_iter(array.iter())),
+                }
+            }
+            DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+                let array = arrow::compute::cast(array, &DataType::Binary)?;
+                let view = VarBinaryView::from_iter(array.as_binary::<i32>().iter());
+                match ty {
+                    Some(Ty::Blob) => ColumnView::Blob(view),
+                    Some(Ty::MediumBlob) => ColumnView::MediumBlob(view),
+                    _ => ColumnView::VarBinary(view),
+                }
+            }
+            DataType::Decimal128(..) | DataType::Decimal256(..) => {
+                ColumnView::Decimal(decimals_from_arrow(array)?)
+            }
+            ty => {
+                return Err(ArrowError::NotYetImplemented(format!(
+                    "unsupported arrow data type: {ty}"
+                )))
+            }
+        })
+    }
+}
+
+/// Convert Arrow decimals to DECIMAL of the same precision and scale, a negative scale is
+/// multiplied into the values with scale 0.
+fn decimals_from_arrow(array: &dyn Array) -> Result<DecimalView, ArrowError> {
+    let (precision, scale) = match array.data_type() {
+        DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale) => {
+            ((*precision).min(DECIMAL128_MAX_PRECISION), *scale)
+        }
+        ty => return Err(ArrowError::CastError(format!("{ty} is not a decimal type"))),
+    };
+    // Cast errors instead of nulls for values out of the range.
+    let options = arrow::compute::CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let array = arrow::compute::cast_with_options(
+        array,
+        &DataType::Decimal128(precision, scale),
+        &options,
+    )?;
+    let shift = scale.min(0).unsigned_abs();
+    let factor = 10i128.checked_pow(shift as u32);
+    let values = array
+        .as_primitive::<Decimal128Type>()
+        .iter()
+        .map(|v| {
+            v.map(|v| {
+                factor.and_then(|f| v.checked_mul(f)).ok_or_else(|| {
+                    ArrowError::ComputeError(format!("decimal {v}e{} overflows", -scale))
+                })
+            })
+            .transpose()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(DecimalView::from_scaled(
+        values,
+        (precision + shift).min(DECIMAL128_MAX_PRECISION),
+        scale.max(0) as u8,
+    ))
+}
+
+fn parse_decimals(array: &StringArray) -> Result<DecimalView, ArrowError> {
+    array
+        .iter()
+        .map(|v| v.map(str::parse::<Decimal>).transpose())
+        .collect::<Result<DecimalView, _>>()
+        .map_err(|err| ArrowError::CastError(err.to_string()))
+}
+
+/// Units per second.
+const fn unit_scale(unit: &TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Second => 1,
+        TimeUnit::Millisecond => 1_000,
+        TimeUnit::Microsecond => 1_000_000,
+        TimeUnit::Nanosecond => 1_000_000_000,
+    }
+}
+
+/// Arrow schema of TDengine `fields`, with data types and `precision` in metadata.
+pub fn fields_to_arrow_schema(
+    fields: &[TaosField],
+    precision: Precision,
+) -> Result<Schema, ArrowError> {
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let ty = field.ty();
+            let data_type = match ty {
+                Ty::Decimal | Ty::Decimal64 => {
+                    let (precision, scale) = decimal_precision_scale(field.bytes());
+                    Some(decimal_arrow_type(precision, scale))
+                }
+                ty => ty.to_arrow_type(precision),
+            }
+            .ok_or_else(|| ArrowError::NotYetImplemented(format!("unsupported data type: {ty}")))?;
+            let metadata = HashMap::from([(TAOS_TYPE_KEY.to_string(), ty.name().to_string())]);
+            Ok(Field::new(field.name(), data_type, true).with_metadata(metadata))
+        })
+        .collect::<Result<Fields, ArrowError>>()?;
+    let metadata = HashMap::from([(
+        TAOS_PRECISION_KEY.to_string(),
+        precision.as_str().to_string(),
+    )]);
+    Ok(Schema::new(fields).with_metadata(metadata))
+}
+
+impl RawBlock {
+    /// Arrow schema of the block, with TDengine types and precision in metadata.
+    pub fn arrow_schema(&self) -> Result<Schema, ArrowError> {
+        let mut schema = fields_to_arrow_schema(&self.fields(), self.precision())?;
+        if let Some(table) = self.table_name() {
+            schema
+                .metadata
+                .insert(TAOS_TABLE_KEY.to_string(), table.to_string());
+        }
+        Ok(schema)
+    }
+
+    /// Convert to an Arrow record batch.
+    ///
+    /// ```rust,ignore
+    /// let batch = block.to_record_batch()?;
+    /// // Query results could be registered in DataFusion or converted to Polars.
+    /// ctx.register_batch("meters", batch)?;
+    /// ```
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let columns = self.columns().map(ColumnView::to_arrow_array).collect();
+        RecordBatch::try_new(Arc::new(self.arrow_schema()?), columns)
+    }
+
+    /// Build a raw block from an Arrow record batch.
+    ///
+    /// The precision is read from schema metadata, or the first timestamp column, default is
+    /// millisecond. Data types in field metadata are used for string columns.
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self, ArrowError> {
+        let schema = batch.schema();
+        let precision = match schema.metadata().get(TAOS_PRECISION_KEY) {
+            Some(precision) => precision
+                .parse()
+                .map_err(|err| ArrowError::SchemaError(format!("{err}")))?,
+            None => schema
+                .fields()
+                .iter()
+                .find_map(|field| match field.data_type() {
+                    DataType::Timestamp(TimeUnit::Microsecond, _) => Some(Precision::Microsecond),
+                    DataType::Timestamp(TimeUnit::Nanosecond, _) => Some(Precision::Nanosecond),
+                    DataType::Timestamp(_, _) => Some(Precision::Millisecond),
+                    _ => None,
+                })
+                .unwrap_or_default(),
+        };
+        let views = schema
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .map(|(field, array)| {
+                let ty = field
+                    .metadata()
+                    .get(TAOS_TYPE_KEY)
+                    .and_then(|ty| ty.parse::<Ty>().ok());
+                ColumnView::from_arrow_array(array.as_ref(), ty, precision)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut raw = RawBlock::from_views(&views, precision);
+        raw.with_field_names(schema.fields().iter().map(|field| field.name().as_str()));
+        if let Some(table) = schema.metadata().get(TAOS_TABLE_KEY) {
+            raw.with_table_name(table);
+        }
+        Ok(raw)
+    }
+}
+
+impl TryFrom<&RecordBatch> for RawBlock {
+    type Error = ArrowError;
+
+    fn try_from(batch: &RecordBatch) -> Result<Self, Self::Error> {
+        Self::from_record_batch(batch)
+    }
+}
+
+#[test]
+fn test_record_batch() {
+    use crate::common::Value;
+    use arrow::array::TimestampNanosecondArray;
+
+    let views = vec![
+        ColumnView::from_millis_timestamp(vec![Some(1654570964022), None]),
+        ColumnView::from_bools(vec![Some(true), None]),
+        ColumnView::from_ints(vec![Some(1), None]),
+        ColumnView::from_doubles(vec![0.1, 0.2]),
+        ColumnView::from_varchar(vec![Some("abc"), None]),
+        ColumnView::from_nchar(vec![None, Some("涛思数据")]),
+        ColumnView::from_var_binary(vec![Some(&b"\x00\xff"[..]), None]),
+        ColumnView::from_decimals(vec![None, Some(rust_decimal::Decimal::new(12345, 2))]),
+    ];
+    let mut raw = RawBlock::from_views(&views, Precision::Millisecond);
+    raw.with_field_names(["ts", "b", "i", "d", "s", "n", "vb", "dec"]);
+
+    let batch = raw.to_record_batch().unwrap();
+    assert_eq!(batch.num_rows(), 2);
+    assert_eq!(
+        batch.schema().field(0).data_type(),
+        &DataType::Timestamp(TimeUnit::Millisecond, None)
+    );
+    assert_eq!(batch.schema().field(5).data_type(), &DataType::Utf8);
+    assert_eq!(batch.schema().field(5).metadata()[TAOS_TYPE_KEY], "NCHAR");
+    assert_eq!(batch.schema().field(6).data_type(), &DataType::Binary);
+    assert_eq!(batch.schema().field(7).metadata()[TAOS_TYPE_KEY], "DECIMAL");
+    assert_eq!(
+        batch.schema().field(7).data_type(),
+        &DataType::Decimal128(38, 2)
+    );
+    assert_eq!(batch.column(7).data_type(), &DataType::Decimal128(38, 2));
+    let ints = batch.column(2).as_primitive::<Int32Type>();
+    assert_eq!(ints.iter().collect::<Vec<_>>(), [Some(1), None]);
+    let ts = batch.column(0).as_primitive::<TimestampMillisecondType>();
+    assert_eq!(ts.value(0), 1654570964022);
+    assert!(ts.is_null(1));
+
+    let back = RawBlock::from_record_batch(&batch).unwrap();
+    assert_eq!(back.field_names(), raw.field_names());
+    assert_eq!(
+        back.columns().map(ColumnView::as_ty).collect::<Vec<_>>(),
+        raw.columns().map(ColumnView::as_ty).collect::<Vec<_>>()
+    );
+    assert_eq!(back.to_values(), raw.to_values());
+    assert_eq!(back.to_values()[1][5], Value::NChar("涛思数据".to_string()));
+
+    // Timestamps in other units are cast to the block precision.
+    let ns = TimestampNanosecondArray::from(vec![Some(1654570964022000000), None]);
+    let view = ColumnView::from_arrow_array(&ns, None, Precision::Millisecond).unwrap();
+    let array = view.to_arrow_array();
+    let ms = array.as_primitive::<TimestampMillisecondType>();
+    assert_eq!(ms.iter().collect::<Vec<_>>(), [Some(1654570964022), None]);
+
+    // Overflowed timestamps are errors.
+    let s = arrow::array::TimestampSecondArray::from(vec![Some(i64::MAX / 10)]);
+    let err = ColumnView::from_arrow_array(&s, None, Precision::Nanosecond).unwrap_err();
+    assert!(matches!(err, ArrowError::ComputeError(_)), "{err}");
+}
+
+#[test]
+fn test_record_batch_decimal() {
+    use arrow::array::Decimal128Array;
+
+    let array = Decimal128Array::from(vec![Some(12345), None, Some(-5)])
+        .with_precision_and_scale(10, 2)
+        .unwrap();
+    let view = ColumnView::from_arrow_array(&array, None, Precision::Millisecond).unwrap();
+    match &view {
+        ColumnView::Decimal(view) => {
+            assert_eq!((view.precision(), view.scale()), (10, 2));
+            assert_eq!(view.ty(), Ty::Decimal64);
+            assert_eq!(view.get_string(0).as_deref(), Some("123.45"));
+        }
+        view => panic!("unexpected view: {view:?}"),
+    }
+    // DECIMAL64 values are widened.
+    let back = view.to_arrow_array();
+    assert_eq!(back.data_type(), &DataType::Decimal128(10, 2));
+    assert_eq!(
+        back.as_primitive::<Decimal128Type>()
+            .iter()
+            .collect::<Vec<_>>(),
+        [Some(12345), None, Some(-5)]
+    );
+
+    // A negative scale is multiplied into the values.
+    let array = Decimal128Array::from(vec![Some(12)])
+        .with_precision_and_scale(5, -2)
+        .unwrap();
+    match ColumnView::from_arrow_array(&array, None, Precision::Millisecond).unwrap() {
+        ColumnView::Decimal(view) => {
+            assert_eq!((view.precision(), view.scale()), (7, 0));
+            assert_eq!(view.get_scaled(0), Some(1200));
+        }
+        view => panic!("unexpected view: {view:?}"),
+    }
+}
@@ -0,0 +1,338 @@
+//! DECIMAL column view.
+//!
+//! Values are scaled integers as the server stores them: each value is a little-endian two's
+//! complement integer `v` of the column scale, meaning `v / 10^scale`, 8 bytes for
+//! [Ty::Decimal64] and 16 bytes for [Ty::Decimal]. The column length in schemas (as
+//! [crate::common::Field::bytes]) packs the byte width, precision and scale, see [decimal_bytes].
+use std::ffi::c_void;
+
+use crate::common::{decimal_ty, BorrowedValue, Decimal, Ty, MAX_PRECISION};
+
+use super::{NullBits, NullsIter};
+
+use bytes::Bytes;
+
+type Item = Decimal;
+type View = DecimalView;
+
+/// Pack DECIMAL `precision` and `scale` as the column length like the server does, the byte
+/// width in the lowest byte, precision in the third and scale in the highest.
+pub const fn decimal_bytes(precision: u8, scale: u8) -> u32 {
+    decimal_ty(precision).fixed_length() as u32 | (precision as u32) << 16 | (scale as u32) << 24
+}
+
+/// Unpack DECIMAL precision and scale from the column length, see [decimal_bytes].
+pub const fn decimal_precision_scale(bytes: u32) -> (u8, u8) {
+    ((bytes >> 16) as u8, (bytes >> 24) as u8)
+}
+
+#[derive(Debug, Clone)]
+pub struct DecimalView {
+    pub(crate) ty: Ty,
+    pub(crate) nulls: NullBits,
+    pub(crate) data: Bytes,
+    pub(crate) precision: u8,
+    pub(crate) scale: u8,
+}
+
+impl DecimalView {
+    /// Build a view from scaled integers of `scale`.
+    ///
+    /// Values are stored in 8 bytes as [Ty::Decimal64] for precision up to 18 if all of them fit,
+    /// otherwise in 16 bytes as [Ty::Decimal].
+    pub fn from_scaled<I: IntoIterator<Item = Option<i128>>>(
+        values: I,
+        precision: u8,
+        scale: u8,
+    ) -> Self {
+        let (nulls, values): (Vec<bool>, Vec<i128>) = values
+            .into_iter()
+            .map(|v| (v.is_none(), v.unwrap_or(0)))
+            .unzip();
+        let ty = match decimal_ty(precision) {
+            Ty::Decimal64 if values.iter().all(|v| i64::try_from(*v).is_ok()) => Ty::Decimal64,
+            _ => Ty::Decimal,
+        };
+        let data: Vec<u8> = if ty == Ty::Decimal64 {
+            values
+                .iter()
+                .flat_map(|v| (*v as i64).to_le_bytes())
+                .collect()
+        } else {
+            values.iter().flat_map(|v| v.to_le_bytes()).collect()
+        };
+        Self {
+            ty,
+            nulls: NullBits::from_iter(nulls),
+            data: Bytes::from(data),
+            precision,
+            scale,
+        }
+    }
+
+    /// Rows
+    pub fn len(&self) -> usize {
+        self.data.len() / self.item_size()
+    }
+
+    /// [Ty::Decimal64] or [Ty::Decimal] by the byte width of values.
+    pub fn ty(&self) -> Ty {
+        self.ty
+    }
+
+    /// Byte width of each value.
+    fn item_size(&self) -> usize {
+        self.ty.fixed_length()
+    }
+
+    /// Precision of the column.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Scale of the column.
+    pub fn scale(&self) -> u8 {
+        self.scale
+    }
+
+    /// Column length in the raw block schema, see [decimal_bytes].
+    pub(crate) fn schema_bytes(&self) -> u32 {
+        self.item_size() as u32 | (self.precision as u32) << 16 | (self.scale as u32) << 24
+    }
+
+    /// Build a nulls vector.
+    pub fn to_nulls_vec(&self) -> Vec<bool> {
+        self.is_null_iter().collect()
+    }
+
+    /// A iterator only decide if the value at some row index is NULL or not.
+    pub fn is_null_iter(&self) -> NullsIter {
+        NullsIter {
+            nulls: &self.nulls,
+            row: 0,
+            len: self.len(),
+        }
+    }
+
+    /// Check if the value at `row` index is NULL or not.
+    pub fn is_null(&self, row: usize) -> bool {
+        if row < self.len() {
+            unsafe { self.is_null_unchecked(row) }
+        } else {
+            false
+        }
+    }
+
+    /// Unsafe version for [methods.is_null]
+    pub unsafe fn is_null_unchecked(&self, row: usize) -> bool {
+        self.nulls.is_null_unchecked(row)
+    }
+
+    /// Get nullable scaled integer at `row` index, the value is `v / 10^scale`.
+    pub fn get_scaled(&self, row: usize) -> Option<i128> {
+        if row < self.len() {
+            unsafe { self.get_scaled_unchecked(row) }
+        } else {
+            None
+        }
+    }
+
+    /// Unsafe version for [DecimalView::get_scaled].
+    pub unsafe fn get_scaled_unchecked(&self, row: usize) -> Option<i128> {
+        if self.nulls.is_null_unchecked(row) {
+            None
+        } else {
+            let ptr = self.data.as_ptr().add(row * self.item_size());
+            Some(if self.ty == Ty::Decimal64 {
+                std::ptr::read_unaligned(ptr as *const i64) as i128
+            } else {
+                std::ptr::read_unaligned(ptr as *const i128)
+            })
+        }
+    }
+
+    /// Get nullable value at `row` index as an exact string, like `123.45`.
+    pub fn get_string(&self, row: usize) -> Option<String> {
+        self.get(row).map(|value| value.to_string())
+    }
+
+    /// Get nullable value at `row` index.
+    pub fn get(&self, row: usize) -> Option<Item> {
+        if row < self.len() {
+            unsafe { self.get_unchecked(row) }
+        } else {
+            None
+        }
+    }
+
+    /// Get nullable value at `row` index.
+    pub unsafe fn get_unchecked(&self, row: usize) -> Option<Item> {
+        self.get_scaled_unchecked(row)
+            .map(|value| Decimal::new(value, self.precision, self.scale))
+    }
+
+    pub unsafe fn get_value_unchecked(&self, row: usize) -> BorrowedValue {
+        self.get_unchecked(row)
+            .map(BorrowedValue::Decimal)
+            .unwrap_or(BorrowedValue::Null)
+    }
+
+    /// Pointer to the scaled integer at `row`, see the module docs for the layout.
+    pub unsafe fn get_raw_value_unchecked(&self, row: usize) -> (Ty, u32, *const c_void) {
+        if self.nulls.is_null_unchecked(row) {
+            (self.ty, self.item_size() as _, std::ptr::null())
+        } else {
+            (
+                self.ty,
+                self.item_size() as _,
+                self.data.as_ptr().add(row * self.item_size()) as _,
+            )
+        }
+    }
+
+    /// A iterator to nullable values of current row.
+    pub fn iter(&self) -> DecimalViewIter {
+        DecimalViewIter { view: self, row: 0 }
+    }
+
+    /// Convert data to a vector of all nullable values.
+    pub fn to_vec(&self) -> Vec<Option<Item>> {
+        self.iter().collect()
+    }
+
+    /// Convert data to a vector of all nullable values as exact strings.
+    pub fn to_string_vec(&self) -> Vec<Option<String>> {
+        (0..self.len()).map(|row| self.get_string(row)).collect()
+    }
+
+    /// Write column data as raw bytes.
+    pub(crate) fn write_raw_into<W: std::io::Write>(&self, mut wtr: W) -> std::io::Result<usize> {
+        let nulls = self.nulls.0.as_ref();
+        wtr.write_all(nulls)?;
+        wtr.write_all(&self.data)?;
+        Ok(nulls.len() + self.data.len())
+    }
+}
+
+pub struct DecimalViewIter<'a> {
+    view: &'a DecimalView,
+    row: usize,
+}
+
+impl<'a> Iterator for DecimalViewIter<'a> {
+    type Item = Option<Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row < self.view.len() {
+            let row = self.row;
+            self.row += 1;
+            Some(unsafe { self.view.get_unchecked(row) })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.row < self.view.len() {
+            let len = self.view.len() - self.row;
+            (len, Some(len))
+        } else {
+            (0, Some(0))
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for DecimalViewIter<'a> {
+    fn len(&self) -> usize {
+        self.view.len() - self.row
+    }
+}
+
+/// Collect decimals with precision 38 and the max scale of the values, the scale is lowered when
+/// some values do not fit in 38 digits with it.
+impl<A: Into<Option<Item>>> FromIterator<A> for View {
+    fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
+        let values: Vec<Option<Item>> = iter.into_iter().map(Into::into).collect();
+        let mut scale = values
+            .iter()
+            .flatten()
+            .map(|v| v.scale())
+            .max()
+            .unwrap_or(0);
+        // Decimals of 38 digits fit with scale 0.
+        while scale > 0
+            && values
+                .iter()
+                .flatten()
+                .any(|v| v.cast(MAX_PRECISION, scale).is_none())
+        {
+            scale -= 1;
+        }
+        Self::from_scaled(
+            values.into_iter().map(|v| {
+                v.and_then(|v| v.cast(MAX_PRECISION, scale))
+                    .map(|v| v.as_raw_i128())
+            }),
+            MAX_PRECISION,
+            scale,
+        )
+    }
+}
+
+#[test]
+fn test_decimal_view() {
+    use rust_decimal::Decimal as RustDecimal;
+
+    let view = DecimalView::from_scaled([Some(12345), None, Some(-5), Some(i128::MAX)], 38, 2);
+    assert_eq!(view.ty(), Ty::Decimal);
+    assert_eq!(view.schema_bytes(), decimal_bytes(38, 2));
+    assert_eq!(view.schema_bytes(), 16 | 38 << 16 | 2 << 24);
+    assert_eq!(decimal_precision_scale(view.schema_bytes()), (38, 2));
+    assert_eq!(&view.data[..16], &12345i128.to_le_bytes());
+    assert_eq!(
+        view.to_string_vec(),
+        [
+            Some("123.45".to_string()),
+            None,
+            Some("-0.05".to_string()),
+            Some("1701411834604692317316873037158841057.27".to_string())
+        ]
+    );
+    assert_eq!(view.get(0), Some(Decimal::new(12345, 38, 2)));
+    assert_eq!(
+        view.get(2).and_then(|v| v.to_decimal()),
+        Some(RustDecimal::new(-5, 2))
+    );
+    // Beyond the range of rust_decimal, the exact value is kept.
+    assert_eq!(view.get(3), Some(Decimal::new(i128::MAX, 38, 2)));
+    assert_eq!(view.get(3).unwrap().to_decimal(), None);
+    match unsafe { view.get_value_unchecked(3) } {
+        BorrowedValue::Decimal(v) => assert_eq!(v.as_raw_i128(), i128::MAX),
+        v => panic!("unexpected value {v:?}"),
+    }
+
+    let view = DecimalView::from_scaled([Some(-12345), None], 10, 2);
+    assert_eq!(view.ty(), Ty::Decimal64);
+    assert_eq!(view.schema_bytes(), decimal_bytes(10, 2));
+    assert_eq!(view.schema_bytes(), 8 | 10 << 16 | 2 << 24);
+    assert_eq!(
+        &view.data[..],
+        &[(-12345i64).to_le_bytes(), [0; 8]].concat()
+    );
+    assert_eq!(view.get_scaled(0), Some(-12345));
+    assert_eq!(view.get_string(0).as_deref(), Some("-123.45"));
+
+    let view = DecimalView::from_iter([
+        Some(Decimal::from(RustDecimal::new(15, 1))),
+        Some(Decimal::from(RustDecimal::new(-2, 0))),
+    ]);
+    assert_eq!(view.scale(), 1);
+    assert_eq!(
+        view.to_vec(),
+        [
+            Some(Decimal::new(15, 38, 1)),
+            Some(Decimal::new(-20, 38, 1))
+        ]
+    );
+}
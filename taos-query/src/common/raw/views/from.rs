@@ -1,3 +1,5 @@
+use rust_decimal::Decimal;
+
 use crate::common::itypes::{IJson, INChar, IVarChar};
 
 use super::*;
@@ -145,3 +147,27 @@ impl From<Vec<Option<INChar>>> for ColumnView {
         ColumnView::NChar(NCharView::from_iter::<INChar, _, _, _>(values.into_iter()))
     }
 }
+
+impl From<Vec<Decimal>> for ColumnView {
+    fn from(values: Vec<Decimal>) -> Self {
+        ColumnView::from_decimals(values)
+    }
+}
+
+impl From<Vec<Option<Decimal>>> for ColumnView {
+    fn from(values: Vec<Option<Decimal>>) -> Self {
+        ColumnView::from_decimals(values)
+    }
+}
+
+impl From<Vec<Vec<u8>>> for ColumnView {
+    fn from(values: Vec<Vec<u8>>) -> Self {
+        ColumnView::VarBinary(VarBinaryView::from_iter::<Vec<u8>, _, _, _>(values))
+    }
+}
+
+impl From<Vec<Option<Vec<u8>>>> for ColumnView {
+    fn from(values: Vec<Option<Vec<u8>>>) -> Self {
+        ColumnView::VarBinary(VarBinaryView::from_iter::<Vec<u8>, _, _, _>(values))
+    }
+}
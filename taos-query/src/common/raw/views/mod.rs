@@ -46,6 +46,12 @@ pub use n_char_view::NCharView;
 mod json_view;
 pub use json_view::JsonView;
 
+mod var_binary_view;
+pub use var_binary_view::VarBinaryView;
+
+mod decimal_view;
+pub use decimal_view::{decimal_bytes, decimal_precision_scale, DecimalView};
+
 mod schema;
 pub(crate) use schema::*;
 
@@ -76,21 +82,25 @@ pub(crate) enum Version {
 
 // #[derive(Debug)]
 pub enum ColumnView {
-    Bool(BoolView),           // 1
-    TinyInt(TinyIntView),     // 2
-    SmallInt(SmallIntView),   // 3
-    Int(IntView),             // 4
-    BigInt(BigIntView),       // 5
-    Float(FloatView),         // 6
-    Double(DoubleView),       // 7
-    VarChar(VarCharView),     // 8
-    Timestamp(TimestampView), // 9
-    NChar(NCharView),         // 10
-    UTinyInt(UTinyIntView),   // 11
-    USmallInt(USmallIntView), // 12
-    UInt(UIntView),           // 13
-    UBigInt(UBigIntView),     // 14
-    Json(JsonView),           // 15
+    Bool(BoolView),            // 1
+    TinyInt(TinyIntView),      // 2
+    SmallInt(SmallIntView),    // 3
+    Int(IntView),              // 4
+    BigInt(BigIntView),        // 5
+    Float(FloatView),          // 6
+    Double(DoubleView),        // 7
+    VarChar(VarCharView),      // 8
+    Timestamp(TimestampView),  // 9
+    NChar(NCharView),          // 10
+    UTinyInt(UTinyIntView),    // 11
+    USmallInt(USmallIntView),  // 12
+    UInt(UIntView),            // 13
+    UBigInt(UBigIntView),      // 14
+    Json(JsonView),            // 15
+    VarBinary(VarBinaryView),  // 16
+    Decimal(DecimalView),      // 17
+    Blob(VarBinaryView),       // 18
+    MediumBlob(VarBinaryView), // 19
 }
 
 impl Debug for ColumnView {
@@ -111,6 +121,10 @@ impl Debug for ColumnView {
             Self::UInt(view) => f.debug_tuple("UInt").field(&view.to_vec()).finish(),
             Self::UBigInt(view) => f.debug_tuple("UBigInt").field(&view.to_vec()).finish(),
             Self::Json(view) => f.debug_tuple("Json").field(&view.to_vec()).finish(),
+            Self::VarBinary(view) => f.debug_tuple("VarBinary").field(&view.to_vec()).finish(),
+            Self::Decimal(view) => f.debug_tuple("Decimal").field(&view.to_vec()).finish(),
+            Self::Blob(view) => f.debug_tuple("Blob").field(&view.to_vec()).finish(),
+            Self::MediumBlob(view) => f.debug_tuple("MediumBlob").field(&view.to_vec()).finish(),
         }
     }
 }
@@ -226,6 +240,35 @@ impl ColumnView {
     ) -> Self {
         ColumnView::NChar(NCharView::from_iter(iter))
     }
+    /// Decimals in precision 38 and the max scale of `values`, see [DecimalView::from_scaled] to
+    /// build a column of some precision and scale.
+    pub fn from_decimals(values: Vec<impl Into<Option<rust_decimal::Decimal>>>) -> Self {
+        ColumnView::Decimal(DecimalView::from_iter(
+            values
+                .into_iter()
+                .map(|v| v.into().map(crate::common::Decimal::from)),
+        ))
+    }
+    pub fn from_var_binary<
+        B: AsRef<[u8]>,
+        T: Into<Option<B>>,
+        I: ExactSizeIterator<Item = T>,
+        V: IntoIterator<Item = T, IntoIter = I>,
+    >(
+        iter: V,
+    ) -> Self {
+        ColumnView::VarBinary(VarBinaryView::from_iter(iter))
+    }
+    pub fn from_blobs<
+        B: AsRef<[u8]>,
+        T: Into<Option<B>>,
+        I: ExactSizeIterator<Item = T>,
+        V: IntoIterator<Item = T, IntoIter = I>,
+    >(
+        iter: V,
+    ) -> Self {
+        ColumnView::Blob(VarBinaryView::from_iter(iter))
+    }
 
     /// It's equal to the cols
     pub fn len(&self) -> usize {
@@ -245,6 +288,10 @@ impl ColumnView {
             ColumnView::UInt(view) => view.len(),
             ColumnView::UBigInt(view) => view.len(),
             ColumnView::Json(view) => view.len(),
+            ColumnView::VarBinary(view) => view.len(),
+            ColumnView::Decimal(view) => view.len(),
+            ColumnView::Blob(view) => view.len(),
+            ColumnView::MediumBlob(view) => view.len(),
         }
     }
 
@@ -267,6 +314,10 @@ impl ColumnView {
             ColumnView::UInt(view) => view.is_null_unchecked(row),
             ColumnView::UBigInt(view) => view.is_null_unchecked(row),
             ColumnView::Json(view) => view.is_null_unchecked(row),
+            ColumnView::VarBinary(view) => view.is_null_unchecked(row),
+            ColumnView::Decimal(view) => view.is_null_unchecked(row),
+            ColumnView::Blob(view) => view.is_null_unchecked(row),
+            ColumnView::MediumBlob(view) => view.is_null_unchecked(row),
         }
     }
 
@@ -289,6 +340,10 @@ impl ColumnView {
             ColumnView::UInt(view) => view.get_value_unchecked(row),
             ColumnView::UBigInt(view) => view.get_value_unchecked(row),
             ColumnView::Json(view) => view.get_value_unchecked(row),
+            ColumnView::VarBinary(view) => view.get_value_unchecked(row, Ty::VarBinary),
+            ColumnView::Decimal(view) => view.get_value_unchecked(row),
+            ColumnView::Blob(view) => view.get_value_unchecked(row, Ty::Blob),
+            ColumnView::MediumBlob(view) => view.get_value_unchecked(row, Ty::MediumBlob),
         }
     }
 
@@ -311,6 +366,10 @@ impl ColumnView {
             ColumnView::UInt(view) => view.get_raw_value_unchecked(row),
             ColumnView::UBigInt(view) => view.get_raw_value_unchecked(row),
             ColumnView::Json(view) => view.get_raw_value_unchecked(row),
+            ColumnView::VarBinary(view) => view.get_raw_value_unchecked(row, Ty::VarBinary),
+            ColumnView::Decimal(view) => view.get_raw_value_unchecked(row),
+            ColumnView::Blob(view) => view.get_raw_value_unchecked(row, Ty::Blob),
+            ColumnView::MediumBlob(view) => view.get_raw_value_unchecked(row, Ty::MediumBlob),
         }
     }
 
@@ -374,6 +433,24 @@ impl ColumnView {
                 view.as_raw_slice().to_vec(),
             ),
             ColumnView::Json(_) => todo!(),
+            ColumnView::VarBinary(view) => Column::VarBinary(
+                bitvec_simd::BitVec::from_bool_iterator(view.is_null_iter()),
+                view.iter()
+                    .map(|v| v.unwrap_or_default().to_vec())
+                    .collect(),
+            ),
+            ColumnView::Decimal(view) => Column::Decimal(
+                bitvec_simd::BitVec::from_bool_iterator(view.is_null_iter()),
+                view.iter()
+                    .map(|v| v.map(|v| v.to_f64()).unwrap_or_default())
+                    .collect(),
+            ),
+            ColumnView::Blob(view) | ColumnView::MediumBlob(view) => Column::Blob(
+                bitvec_simd::BitVec::from_bool_iterator(view.is_null_iter()),
+                view.iter()
+                    .map(|v| v.unwrap_or_default().to_vec())
+                    .collect(),
+            ),
         }
     }
 
@@ -394,10 +471,15 @@ impl ColumnView {
             ColumnView::UInt(view) => view.write_raw_into(wtr),
             ColumnView::UBigInt(view) => view.write_raw_into(wtr),
             ColumnView::Json(view) => view.write_raw_into(wtr),
+            ColumnView::VarBinary(view) => view.write_raw_into(wtr),
+            ColumnView::Decimal(view) => view.write_raw_into(wtr),
+            ColumnView::Blob(view) => view.write_raw_into(wtr),
+            ColumnView::MediumBlob(view) => view.write_raw_into(wtr),
         }
     }
 
-    fn as_ty(&self) -> Ty {
+    /// The data type of the column.
+    pub fn as_ty(&self) -> Ty {
         match self {
             ColumnView::Bool(_) => Ty::Bool,
             ColumnView::TinyInt(_) => Ty::TinyInt,
@@ -414,6 +496,10 @@ impl ColumnView {
             ColumnView::UInt(_) => Ty::UInt,
             ColumnView::UBigInt(_) => Ty::UBigInt,
             ColumnView::Json(_) => Ty::Json,
+            ColumnView::VarBinary(_) => Ty::VarBinary,
+            ColumnView::Decimal(view) => view.ty(),
+            ColumnView::Blob(_) => Ty::Blob,
+            ColumnView::MediumBlob(_) => Ty::MediumBlob,
         }
     }
 }
@@ -431,10 +517,11 @@ pub fn views_to_raw_block(views: &[ColumnView]) -> Vec<u8> {
         .iter()
         .map(|view| {
             let ty = view.as_ty();
-            ColSchema {
-                ty,
-                len: ty.fixed_length() as _,
-            }
+            let len = match view {
+                ColumnView::Decimal(view) => view.schema_bytes(),
+                _ => ty.fixed_length() as _,
+            };
+            ColSchema { ty, len }
         })
         .collect_vec();
     let schema_bytes = unsafe {
@@ -455,7 +542,7 @@ pub fn views_to_raw_block(views: &[ColumnView]) -> Vec<u8> {
     };
     for (i, view) in views.iter().enumerate() {
         let n = view.write_raw_into(&mut bytes).unwrap();
-        if view.as_ty().is_var_type() {
+        if view.as_ty().is_var_type() || matches!(view.as_ty(), Ty::Blob | Ty::MediumBlob) {
             lengths[i] = (n - nrows * 4) as _;
         } else {
             lengths[i] = (nrows * view.as_ty().fixed_length()) as _;
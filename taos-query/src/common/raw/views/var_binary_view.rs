@@ -0,0 +1,128 @@
+use std::{ffi::c_void, fmt::Debug};
+
+use super::Offsets;
+use crate::{
+    common::{BorrowedValue, Ty},
+    prelude::InlinableWrite,
+    util::InlineBytes,
+};
+
+use bytes::Bytes;
+use itertools::Itertools;
+
+/// View of binary columns, shared by [Ty::VarBinary], [Ty::Blob] and [Ty::MediumBlob].
+///
+/// The layout is the same as [VarCharView](super::VarCharView): an offset for each row
+/// (`-1` for NULL) and length-prefixed bytes.
+#[derive(Debug, Clone)]
+pub struct VarBinaryView {
+    pub(crate) offsets: Offsets,
+    pub(crate) data: Bytes,
+}
+
+impl VarBinaryView {
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// A iterator only decide if the value at some row index is NULL or not.
+    pub fn is_null_iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len()).map(|row| unsafe { self.is_null_unchecked(row) })
+    }
+
+    /// Build a nulls vector.
+    pub fn to_nulls_vec(&self) -> Vec<bool> {
+        self.is_null_iter().collect()
+    }
+
+    /// Check if the value at `row` index is NULL or not.
+    ///
+    /// Returns null when `row` index out of bound.
+    pub fn is_null(&self, row: usize) -> bool {
+        if row < self.len() {
+            unsafe { self.is_null_unchecked(row) }
+        } else {
+            false
+        }
+    }
+
+    /// Unsafe version for [is_null](#method.is_null)
+    pub(crate) unsafe fn is_null_unchecked(&self, row: usize) -> bool {
+        *self.offsets.get_unchecked(row) < 0
+    }
+
+    pub(crate) unsafe fn get_unchecked(&self, row: usize) -> Option<&InlineBytes> {
+        let offset = self.offsets.get_unchecked(row);
+        if *offset >= 0 {
+            Some(InlineBytes::<u16>::from_ptr(
+                self.data.as_ptr().offset(*offset as isize),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Get the value at `row` as `ty`, which should be one of the binary types.
+    pub(crate) unsafe fn get_value_unchecked(&self, row: usize, ty: Ty) -> BorrowedValue {
+        match self.get_unchecked(row).map(|v| v.as_bytes()) {
+            Some(v) => match ty {
+                Ty::Blob => BorrowedValue::Blob(v),
+                Ty::MediumBlob => BorrowedValue::MediumBlob(v),
+                _ => BorrowedValue::VarBinary(v),
+            },
+            None => BorrowedValue::Null,
+        }
+    }
+
+    pub(crate) unsafe fn get_raw_value_unchecked(
+        &self,
+        row: usize,
+        ty: Ty,
+    ) -> (Ty, u32, *const c_void) {
+        match self.get_unchecked(row) {
+            Some(v) => (ty, v.len() as _, v.as_bytes().as_ptr() as _),
+            None => (ty, 0, std::ptr::null()),
+        }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = Option<&[u8]>> {
+        (0..self.len()).map(|row| unsafe { self.get_unchecked(row) }.map(|v| v.as_bytes()))
+    }
+
+    pub fn to_vec(&self) -> Vec<Option<Vec<u8>>> {
+        self.iter().map(|v| v.map(|v| v.to_vec())).collect_vec()
+    }
+
+    /// Write column data as raw bytes.
+    pub(crate) fn write_raw_into<W: std::io::Write>(&self, mut wtr: W) -> std::io::Result<usize> {
+        let offsets = self.offsets.as_bytes();
+        wtr.write_all(offsets)?;
+        wtr.write_all(&self.data)?;
+        Ok(offsets.len() + self.data.len())
+    }
+
+    pub fn from_iter<
+        B: AsRef<[u8]>,
+        T: Into<Option<B>>,
+        I: ExactSizeIterator<Item = T>,
+        V: IntoIterator<Item = T, IntoIter = I>,
+    >(
+        iter: V,
+    ) -> Self {
+        let mut offsets = Vec::new();
+        let mut data = Vec::new();
+
+        for i in iter.into_iter().map(|v| v.into()) {
+            if let Some(v) = i {
+                offsets.push(data.len() as i32);
+                data.write_inlined_bytes::<2>(v.as_ref()).unwrap();
+            } else {
+                offsets.push(-1);
+            }
+        }
+        VarBinaryView {
+            offsets: Offsets::from_offsets(offsets.into_iter()),
+            data: data.into(),
+        }
+    }
+}
@@ -55,6 +55,18 @@ impl Timestamp {
             | Timestamp::Nanoseconds(raw) => *raw,
         }
     }
+    /// Convert to timestamp in `precision`, floored to a coarser one, `None` if overflowed.
+    pub fn cast_precision(&self, precision: Precision) -> Option<Timestamp> {
+        let raw = self.as_raw_i64();
+        let from = self.precision() as u32;
+        let to = precision as u32;
+        let raw = if to >= from {
+            raw.checked_mul(1000i64.pow(to - from))?
+        } else {
+            raw.div_euclid(1000i64.pow(from - to))
+        };
+        Some(Timestamp::new(raw, precision))
+    }
     pub fn to_naive_datetime(&self) -> chrono::NaiveDateTime {
         let duration = match self {
             Timestamp::Milliseconds(raw) => chrono::Duration::milliseconds(*raw),
@@ -87,6 +99,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ts_cast_precision() {
+        use Precision::*;
+        let ts = Timestamp::new(1654570964022, Millisecond);
+        assert_eq!(
+            ts.cast_precision(Nanosecond),
+            Some(Timestamp::Nanoseconds(1654570964022000000))
+        );
+        let ts = Timestamp::new(-1_500, Microsecond);
+        assert_eq!(
+            ts.cast_precision(Millisecond),
+            Some(Timestamp::Milliseconds(-2))
+        );
+        assert_eq!(
+            Timestamp::new(i64::MAX, Millisecond).cast_precision(Microsecond),
+            None
+        );
+    }
+
     #[test]
     fn ts_debug() {
         let ts = Timestamp::new(0, Precision::Millisecond);
@@ -25,10 +25,16 @@ use serde::de::Visitor;
 /// | UInt       | 13  | INT UNSIGNED     | u32               |
 /// | UBigInt    | 14  | BIGINT UNSIGNED  | u64               |
 /// | Json       | 15  | JSON             | serde_json::Value |
+/// | VarBinary  | 16  | VARBINARY        | \[u8\]/Vec<u8>    |
+/// | Decimal    | 17  | DECIMAL          | [Decimal](super::Decimal) |
+/// | Blob       | 18  | BLOB             | \[u8\]/Vec<u8>    |
+/// | MediumBlob | 19  | MEDIUMBLOB       | \[u8\]/Vec<u8>    |
+/// | Decimal64  | 21  | DECIMAL          | [Decimal](super::Decimal) |
 ///
 /// Note:
 /// - VarChar sql name is BINARY in v2, and VARCHAR in v3.
-/// - Decimal/Blob/MediumBlob is not supported in 2.0/3.0 .
+/// - DECIMAL columns of precision up to 18 are [Ty::Decimal64] in 8 bytes, others are
+///   [Ty::Decimal] in 16 bytes.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, serde_repr::Serialize_repr)]
 #[repr(u8)]
 #[non_exhaustive]
@@ -72,17 +78,17 @@ pub enum Ty {
     Json = 15, // 15
 
     /// 16, VarBinary, `varbinary` in sql, [Vec<u8>] in Rust, which is supported since TDengine 3.0.
-    #[doc(hidden)]
     VarBinary, // 16
-    /// 17, Not supported now.
-    #[doc(hidden)]
+    /// 17, Decimal, `decimal` in sql of precision 19 to 38, a 16-byte scaled integer represented as
+    /// [Decimal](super::Decimal) in Rust.
     Decimal, // 17
-    /// 18, Not supported now.
-    #[doc(hidden)]
+    /// 18, Blob, `blob` in sql, [Vec<u8>] in Rust.
     Blob, // 18
-    /// 19, Not supported now.
-    #[doc(hidden)]
+    /// 19, MediumBlob, `mediumblob` in sql, [Vec<u8>] in Rust.
     MediumBlob, // 19
+    /// 21, Decimal64, `decimal` in sql of precision up to 18, an 8-byte scaled integer represented
+    /// as [Decimal](super::Decimal) in Rust.
+    Decimal64 = 21, // 21
 }
 
 impl Default for Ty {
@@ -224,6 +230,7 @@ impl Ty {
                 | Float
                 | Double
                 | Decimal
+                | Decimal64
         )
     }
 
@@ -244,6 +251,7 @@ impl Ty {
             UInt => 4,
             UBigInt => 8,
             Decimal => 16,
+            Decimal64 => 8,
             _ => usize::MAX,
         }
     }
@@ -272,6 +280,7 @@ impl Ty {
             Decimal => "DECIMAL",
             Blob => "BLOB",
             MediumBlob => "MEDIUMBLOB",
+            Decimal64 => "DECIMAL",
             _ => panic!("not a type"),
         }
     }
@@ -299,6 +308,7 @@ impl Ty {
             Decimal => "decimal",
             Blob => "blob",
             MediumBlob => "mediumblob",
+            Decimal64 => "decimal",
             _ => panic!("not a type"),
         }
     }
@@ -317,6 +327,7 @@ impl Ty {
         return _var_str!(
             Null Bool TinyInt SmallInt Int BigInt UTinyInt USmallInt UInt UBigInt
             Float Double VarChar NChar Timestamp Json VarBinary Decimal Blob MediumBlob
+            Decimal64
         );
     }
 
@@ -344,6 +355,7 @@ impl Ty {
             17 => Decimal,
             18 => Blob,
             19 => MediumBlob,
+            21 => Decimal64,
             _ => panic!("unknown data type"),
         }
     }
@@ -351,7 +363,7 @@ impl Ty {
 impl From<u8> for Ty {
     #[inline]
     fn from(v: u8) -> Self {
-        unsafe { std::mem::transmute(v) }
+        Self::from_u8(v)
     }
 }
 
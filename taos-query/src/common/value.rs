@@ -1,9 +1,8 @@
 use std::{borrow::Cow, fmt::Display, str::Utf8Error};
 
-use rust_decimal::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use super::{Timestamp, Ty};
+use super::{Decimal, Timestamp, Ty};
 
 #[derive(Debug, Clone)]
 pub enum BorrowedValue<'b> {
@@ -51,7 +50,7 @@ impl<'b> BorrowedValue<'b> {
             Json(_) => Ty::Json,
             NChar(_) => Ty::NChar,
             VarBinary(_) => Ty::VarBinary,
-            Decimal(_) => Ty::Decimal,
+            Decimal(v) => v.ty(),
             Blob(_) => Ty::Blob,
             MediumBlob(_) => Ty::MediumBlob,
         }
@@ -93,6 +92,8 @@ impl<'b> BorrowedValue<'b> {
                 .to_naive_datetime()
                 .format("%Y-%m-%dT%H:%M:%S%.f")
                 .to_string()),
+            VarBinary(v) | Blob(v) | MediumBlob(v) => Ok(std::str::from_utf8(v)?.to_string()),
+            Decimal(v) => Ok(format!("{v}")),
             _ => unreachable!("un supported type to string"),
         }
     }
@@ -118,10 +119,10 @@ impl<'b> BorrowedValue<'b> {
                 Value::Json(serde_json::from_slice(v).expect("json should always be deserialized"))
             }
             NChar(str) => Value::NChar(str.to_string()),
-            VarBinary(_) => todo!(),
-            Decimal(_) => todo!(),
-            Blob(_) => todo!(),
-            MediumBlob(_) => todo!(),
+            VarBinary(v) => Value::VarBinary(v.to_vec()),
+            Decimal(v) => Value::Decimal(*v),
+            Blob(v) => Value::Blob(v.to_vec()),
+            MediumBlob(v) => Value::MediumBlob(v.to_vec()),
         }
     }
 
@@ -148,10 +149,9 @@ impl<'b> BorrowedValue<'b> {
                 serde_json::from_slice(v).expect("json should always be deserialized"),
             ),
             NChar(str) => serde_json::Value::String(str.to_string()),
-            VarBinary(_) => todo!(),
-            Decimal(_) => todo!(),
-            Blob(_) => todo!(),
-            MediumBlob(_) => todo!(),
+            VarBinary(v) | Blob(v) | MediumBlob(v) => serde_json::Value::from(v.to_vec()),
+            // Decimal is represented as a string to keep the precision.
+            Decimal(v) => serde_json::Value::String(v.to_string()),
         }
     }
 
@@ -177,10 +177,10 @@ impl<'b> BorrowedValue<'b> {
                 Value::Json(serde_json::from_slice(&v).expect("json should always be deserialized"))
             }
             NChar(str) => Value::NChar(str.to_string()),
-            VarBinary(_) => todo!(),
-            Decimal(_) => todo!(),
-            Blob(_) => todo!(),
-            MediumBlob(_) => todo!(),
+            VarBinary(v) => Value::VarBinary(v.to_vec()),
+            Decimal(v) => Value::Decimal(v),
+            Blob(v) => Value::Blob(v.to_vec()),
+            MediumBlob(v) => Value::MediumBlob(v.to_vec()),
         }
     }
 }
@@ -205,16 +205,27 @@ impl<'b> Display for BorrowedValue<'b> {
             UInt(v) => f.write_fmt(format_args!("{v}")),
             UBigInt(v) => f.write_fmt(format_args!("{v}")),
             Json(v) => f.write_fmt(format_args!("{}", v.as_ref().escape_ascii())),
-            VarBinary(_) => todo!(),
-            Decimal(_) => todo!(),
-            Blob(_) => todo!(),
-            MediumBlob(_) => todo!(),
+            VarBinary(v) | Blob(v) | MediumBlob(v) => Display::fmt(&HexBytes(v), f),
+            Decimal(v) => f.write_fmt(format_args!("{v}")),
         }
     }
 }
 
 unsafe impl<'b> Send for BorrowedValue<'b> {}
 
+/// Display binary data as hex string like `\x0A0B`, as TDengine does.
+struct HexBytes<'a>(&'a [u8]);
+
+impl Display for HexBytes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("\\x")?;
+        for b in self.0 {
+            write!(f, "{b:02X}")?;
+        }
+        Ok(())
+    }
+}
+
 // #[derive(Debug, Clone)]
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum Value {
@@ -260,10 +271,8 @@ impl Display for Value {
             UInt(v) => f.write_fmt(format_args!("{v}")),
             UBigInt(v) => f.write_fmt(format_args!("{v}")),
             Json(v) => f.write_fmt(format_args!("{v}")),
-            VarBinary(_) => todo!(),
-            Decimal(_) => todo!(),
-            Blob(_) => todo!(),
-            MediumBlob(_) => todo!(),
+            VarBinary(v) | Blob(v) | MediumBlob(v) => Display::fmt(&HexBytes(v), f),
+            Decimal(v) => f.write_fmt(format_args!("{v}")),
         }
     }
 }
@@ -290,7 +299,7 @@ impl Value {
             Json(_) => Ty::Json,
             NChar(_) => Ty::NChar,
             VarBinary(_) => Ty::VarBinary,
-            Decimal(_) => Ty::Decimal,
+            Decimal(v) => v.ty(),
             Blob(_) => Ty::Blob,
             MediumBlob(_) => Ty::MediumBlob,
         }
@@ -357,11 +366,9 @@ impl Value {
             USmallInt(v) => format!("{v}"),
             UInt(v) => format!("{v}"),
             UBigInt(v) => format!("{v}"),
-            Json(v) => format!("\"{}\"", v),
-            VarBinary(_) => todo!(),
-            Decimal(_) => todo!(),
-            Blob(_) => todo!(),
-            MediumBlob(_) => todo!(),
+            Json(v) => format!("\"{}\"", v.to_string().escape_debug()),
+            VarBinary(v) | Blob(v) | MediumBlob(v) => format!("\"{}\"", HexBytes(v)),
+            Decimal(v) => format!("{v}"),
         }
     }
 
@@ -386,6 +393,8 @@ impl Value {
                 .to_naive_datetime()
                 .format("%Y-%m-%dT%H:%M:%S%.f")
                 .to_string()),
+            VarBinary(v) | Blob(v) | MediumBlob(v) => Ok(std::str::from_utf8(v)?.to_string()),
+            Decimal(v) => Ok(format!("{v}")),
             _ => unreachable!("un supported type to string"),
         }
     }
@@ -411,10 +420,9 @@ impl Value {
             Timestamp(v) => serde_json::Value::Number(serde_json::Number::from(v.as_raw_i64())),
             Json(v) => v.clone(),
             NChar(str) => serde_json::Value::String(str.to_string()),
-            VarBinary(_) => todo!(),
-            Decimal(_) => todo!(),
-            Blob(_) => todo!(),
-            MediumBlob(_) => todo!(),
+            VarBinary(v) | Blob(v) | MediumBlob(v) => serde_json::Value::from(v.to_vec()),
+            // Decimal is represented as a string to keep the precision.
+            Decimal(v) => serde_json::Value::String(v.to_string()),
         }
     }
 }
@@ -147,6 +147,7 @@ impl<'de, 'b: 'de> serde::de::Deserializer<'de> for BorrowedValue<'de> {
             },
             Timestamp(v) => visitor.visit_i64(v.as_raw_i64()),
             VarBinary(v) | Blob(v) | MediumBlob(v) => visitor.visit_borrowed_bytes(v),
+            Decimal(v) => visitor.visit_string(v.to_string()),
             _ => Err(<Self::Error as de::Error>::custom(
                 "un supported type to deserialize",
             )),
@@ -267,6 +268,7 @@ impl<'de, 'b: 'de> serde::de::Deserializer<'de> for BorrowedValue<'de> {
             },
             Timestamp(v) => visitor.visit_i64(v.as_raw_i64()),
             VarBinary(v) | Blob(v) | MediumBlob(v) => visitor.visit_borrowed_bytes(v),
+            Decimal(v) => visitor.visit_newtype_struct(v.to_string().into_deserializer()),
             _ => Err(<Self::Error as de::Error>::custom(
                 "un supported type to deserialize",
             )),
@@ -179,6 +179,7 @@ impl<'de, 'v: 'de> serde::de::Deserializer<'de> for &'v Value {
                 .map_err(<Self::Error as de::Error>::custom),
             Timestamp(v) => visitor.visit_i64(v.as_raw_i64()),
             VarBinary(v) | Blob(v) | MediumBlob(v) => visitor.visit_borrowed_bytes(v),
+            Decimal(v) => visitor.visit_string(v.to_string()),
             _ => Err(<Self::Error as de::Error>::custom(
                 "un supported type to deserialize",
             )),
@@ -219,6 +220,7 @@ impl<'de, 'v: 'de> serde::de::Deserializer<'de> for &'v Value {
                     .format("%Y-%m-%dT%H:%M:%S%.f")
                     .to_string(),
             ),
+            Decimal(v) => visitor.visit_string(v.to_string()),
             _ => Err(<Self::Error as de::Error>::custom(
                 "un supported type to deserialize",
             )),
@@ -281,6 +283,7 @@ impl<'de, 'v: 'de> serde::de::Deserializer<'de> for &'v Value {
             VarBinary(v) | Blob(v) | MediumBlob(v) => {
                 visitor.visit_newtype_struct(v.as_slice().into_deserializer())
             }
+            Decimal(v) => visitor.visit_newtype_struct(v.to_string().into_deserializer()),
             _ => Err(<Self::Error as de::Error>::custom(
                 "un supported type to deserialize",
             )),
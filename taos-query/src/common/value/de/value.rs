@@ -180,6 +180,7 @@ impl<'de, 'b: 'de> serde::de::Deserializer<'de> for Value {
             VarBinary(v) | Blob(v) | MediumBlob(v) => {
                 v.into_deserializer().deserialize_any(visitor)
             }
+            Decimal(v) => visitor.visit_string(v.to_string()),
             _ => Err(<Self::Error as de::Error>::custom(
                 "un supported type to deserialize",
             )),
@@ -223,6 +224,7 @@ impl<'de, 'b: 'de> serde::de::Deserializer<'de> for Value {
                     .format("%Y-%m-%dT%H:%M:%S%.f")
                     .to_string(),
             ),
+            Decimal(v) => visitor.visit_string(v.to_string()),
             _ => Err(<Self::Error as de::Error>::custom(
                 "un supported type to deserialize",
             )),
@@ -285,6 +287,7 @@ impl<'de, 'b: 'de> serde::de::Deserializer<'de> for Value {
             VarBinary(v) | Blob(v) | MediumBlob(v) => {
                 visitor.visit_newtype_struct(v.as_slice().into_deserializer())
             }
+            Decimal(v) => visitor.visit_newtype_struct(v.to_string().into_deserializer()),
             _ => Err(<Self::Error as de::Error>::custom(
                 "un supported type to deserialize",
             )),
@@ -438,6 +441,7 @@ mod tests {
             VarBinary(vec![0, 1,2]), Vec<u8>, vec![0, 1, 2]
             Blob(vec![0, 1,2]), Vec<u8>, vec![0, 1, 2]
             MediumBlob(vec![0, 1,2]), Vec<u8>, vec![0, 1, 2]
+            Decimal(crate::common::Decimal::new(12345, 10, 2)), rust_decimal::Decimal, rust_decimal::Decimal::new(12345, 2)
         );
     }
 
@@ -488,6 +492,8 @@ mod tests {
             Json(json!({ "name": "abc"})), json!({ "name": "abc"}).to_string()
             Json(json!(1)), json!(1).to_string()
             Json(json!(null)), json!(null).to_string()
+            Decimal(crate::common::Decimal::new(12345, 10, 2)), "123.45"
+            Decimal(crate::common::Decimal::new(i128::MAX, 38, 2)), "1701411834604692317316873037158841057.27"
         };
     }
 
@@ -0,0 +1,273 @@
+use chrono::NaiveDateTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tmq::{Assignment, VGroupId};
+
+/// Information for `information_schema.ins_consumers` record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsumerInfo {
+    consumer_id: i64,
+    consumer_group: String,
+    #[serde(default)]
+    client_id: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    topics: Option<String>,
+    #[serde(default)]
+    up_time: Option<NaiveDateTime>,
+    #[serde(default)]
+    subscribe_time: Option<NaiveDateTime>,
+    #[serde(default)]
+    rebalance_time: Option<NaiveDateTime>,
+}
+
+impl ConsumerInfo {
+    /// Consumer id generated by server.
+    pub fn id(&self) -> i64 {
+        self.consumer_id
+    }
+
+    /// Consumer group, the `group.id` of a consumer.
+    pub fn group(&self) -> &str {
+        &self.consumer_group
+    }
+
+    /// The `client.id` of a consumer.
+    pub fn client_id(&self) -> Option<&str> {
+        self.client_id.as_deref()
+    }
+
+    /// Consumer status, e.g. `ready`, `lost`, `rebalancing`.
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Topics subscribed by the consumer.
+    pub fn topics(&self) -> Vec<&str> {
+        self.topics
+            .as_deref()
+            .map(|topics| {
+                topics
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|topic| !topic.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Time of the consumer being up.
+    pub fn up_time(&self) -> Option<NaiveDateTime> {
+        self.up_time
+    }
+
+    /// Time of last subscription.
+    pub fn subscribe_time(&self) -> Option<NaiveDateTime> {
+        self.subscribe_time
+    }
+
+    /// Time of last rebalance.
+    pub fn rebalance_time(&self) -> Option<NaiveDateTime> {
+        self.rebalance_time
+    }
+}
+
+/// Information for `information_schema.ins_subscriptions` record, one for each topic vgroup of a group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subscription {
+    topic_name: String,
+    consumer_group: String,
+    vgroup_id: VGroupId,
+    #[serde(default)]
+    consumer_id: Option<i64>,
+    #[serde(default)]
+    offset: Option<String>,
+    #[serde(default)]
+    rows: Option<i64>,
+}
+
+impl Subscription {
+    /// Topic name.
+    pub fn topic(&self) -> &str {
+        &self.topic_name
+    }
+
+    /// Consumer group.
+    pub fn group(&self) -> &str {
+        &self.consumer_group
+    }
+
+    /// VGroup id.
+    pub fn vgroup_id(&self) -> VGroupId {
+        self.vgroup_id
+    }
+
+    /// Consumer that the vgroup is assigned to, `None` if not assigned.
+    pub fn consumer_id(&self) -> Option<i64> {
+        self.consumer_id.filter(|id| *id != 0)
+    }
+
+    /// Committed offset in the server representation, like `log:100`.
+    pub fn offset(&self) -> Option<&str> {
+        self.offset.as_deref()
+    }
+
+    /// Committed wal version parsed from [Subscription::offset], `None` if not committed yet.
+    pub fn committed_version(&self) -> Option<i64> {
+        self.offset
+            .as_deref()
+            .and_then(|offset| offset.rsplit(':').next())
+            .and_then(|version| version.trim().parse().ok())
+    }
+
+    /// Rows consumed, available in newer servers.
+    pub fn rows(&self) -> Option<i64> {
+        self.rows
+    }
+}
+
+/// Lag of a topic vgroup for a consumer group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VGroupLag {
+    topic: String,
+    group: String,
+    vgroup_id: VGroupId,
+    consumer_id: Option<i64>,
+    committed: Option<i64>,
+    begin: i64,
+    end: i64,
+}
+
+impl VGroupLag {
+    /// Join committed offsets of `group` in `subscriptions` with the offset ranges in `assignments`.
+    ///
+    /// Each assignment produces one record, the committed offset is `None` if the group never
+    /// committed the vgroup.
+    pub fn from_subscriptions(
+        group: &str,
+        subscriptions: &[Subscription],
+        assignments: &[(String, Vec<Assignment>)],
+    ) -> Vec<Self> {
+        assignments
+            .iter()
+            .flat_map(|(topic, assignments)| {
+                assignments.iter().map(move |assignment| {
+                    let sub = subscriptions.iter().find(|sub| {
+                        sub.group() == group
+                            && sub.topic() == topic
+                            && sub.vgroup_id() == assignment.vgroup_id()
+                    });
+                    VGroupLag {
+                        topic: topic.clone(),
+                        group: group.to_string(),
+                        vgroup_id: assignment.vgroup_id(),
+                        consumer_id: sub.and_then(Subscription::consumer_id),
+                        committed: sub.and_then(Subscription::committed_version),
+                        begin: assignment.begin(),
+                        end: assignment.end(),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Topic name.
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Consumer group.
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// VGroup id.
+    pub fn vgroup_id(&self) -> VGroupId {
+        self.vgroup_id
+    }
+
+    /// Consumer that the vgroup is assigned to, `None` if not assigned.
+    pub fn consumer_id(&self) -> Option<i64> {
+        self.consumer_id
+    }
+
+    /// Committed offset of the group, `None` if not committed yet.
+    pub fn committed(&self) -> Option<i64> {
+        self.committed
+    }
+
+    /// The last offset in the vgroup.
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Messages not committed yet, counted from the first offset if never committed.
+    pub fn lag(&self) -> i64 {
+        let committed = self.committed.unwrap_or(self.begin);
+        (self.end - committed).max(0)
+    }
+}
+
+#[test]
+fn test_vgroup_lag() {
+    let sub = |group: &str, vgroup_id, offset: &str| Subscription {
+        topic_name: "tp1".to_string(),
+        consumer_group: group.to_string(),
+        vgroup_id,
+        consumer_id: Some(10),
+        offset: Some(offset.to_string()),
+        rows: None,
+    };
+    let subscriptions = [
+        sub("g1", 2, "log:100"),
+        sub("g2", 2, "log:150"),
+        sub("g1", 3, "log:300"),
+    ];
+    let assignments = [(
+        "tp1".to_string(),
+        vec![
+            Assignment::new(2, 0, 10, 200),
+            Assignment::new(3, 0, 10, 250),
+            Assignment::new(4, 0, 10, 50),
+        ],
+    )];
+
+    let lags = VGroupLag::from_subscriptions("g1", &subscriptions, &assignments);
+    assert_eq!(lags.len(), 3);
+    assert_eq!(lags[0].vgroup_id(), 2);
+    assert_eq!(lags[0].committed(), Some(100));
+    assert_eq!(lags[0].end(), 200);
+    assert_eq!(lags[0].lag(), 100);
+    assert_eq!(lags[0].consumer_id(), Some(10));
+    // committed offset after the end offset is not a lag.
+    assert_eq!(lags[1].lag(), 0);
+    // never committed, count from the first offset.
+    assert_eq!(lags[2].committed(), None);
+    assert_eq!(lags[2].consumer_id(), None);
+    assert_eq!(lags[2].lag(), 40);
+    assert!(lags
+        .iter()
+        .all(|lag| lag.topic() == "tp1" && lag.group() == "g1"));
+}
+
+#[test]
+fn test_subscription_offset() {
+    let sub = Subscription {
+        topic_name: "tp1".to_string(),
+        consumer_group: "g1".to_string(),
+        vgroup_id: 2,
+        consumer_id: Some(0),
+        offset: Some("log:100".to_string()),
+        rows: None,
+    };
+    assert_eq!(sub.committed_version(), Some(100));
+    assert_eq!(sub.consumer_id(), None);
+
+    let sub = Subscription {
+        offset: Some("unknown".to_string()),
+        ..sub
+    };
+    assert_eq!(sub.committed_version(), None);
+}
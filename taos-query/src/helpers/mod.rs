@@ -1,7 +1,9 @@
+mod consumer;
 mod database;
 mod describe;
 mod topic;
 
+pub use consumer::*;
 pub use database::*;
 pub use describe::*;
 pub use topic::*;
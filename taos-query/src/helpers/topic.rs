@@ -1,7 +1,62 @@
+use std::fmt::{self, Display};
+
 use chrono::NaiveDateTime;
 
 use serde::{Deserialize, Serialize};
 
+/// Check the topic name could be quoted with backticks, which could not contain backticks.
+pub fn check_topic_name(name: &str) -> Result<(), taos_error::Error> {
+    if name.is_empty() || name.contains('`') {
+        return Err(taos_error::Error::from_string(format!(
+            "invalid topic name: {name:?}"
+        )));
+    }
+    Ok(())
+}
+
+/// Escape a string as a single quoted sql string literal.
+pub fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Scope of a topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TopicKind {
+    /// Column/query topic, created by `AS SELECT ...`.
+    Query,
+    /// Super table topic, created by `AS STABLE ...`.
+    Stable,
+    /// Database topic, created by `AS DATABASE ...`.
+    Database,
+}
+
+impl TopicKind {
+    /// Derive topic kind from the `type` column of `ins_topics`, e.g. `column`, `stable`, `db`.
+    pub fn from_type(ty: &str) -> Option<Self> {
+        match ty.to_lowercase().as_str() {
+            "column" | "query" => Some(Self::Query),
+            "stable" | "super table" => Some(Self::Stable),
+            "db" | "database" => Some(Self::Database),
+            _ => None,
+        }
+    }
+
+    /// Derive topic kind from the create sql, by the first keyword after `AS`.
+    pub fn from_sql(sql: &str) -> Self {
+        let mut tokens = sql.split_whitespace();
+        while let Some(token) = tokens.next() {
+            if token.eq_ignore_ascii_case("as") {
+                return match tokens.next() {
+                    Some(next) if next.eq_ignore_ascii_case("database") => Self::Database,
+                    Some(next) if next.eq_ignore_ascii_case("stable") => Self::Stable,
+                    _ => Self::Query,
+                };
+            }
+        }
+        Self::Query
+    }
+}
+
 /// Information for `show topics` record.
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Topic {
@@ -9,6 +64,10 @@ pub struct Topic {
     db_name: String,
     create_time: NaiveDateTime,
     sql: String,
+    #[serde(default)]
+    meta: Option<String>,
+    #[serde(default, rename = "type")]
+    ty: Option<String>,
 }
 
 impl Topic {
@@ -32,12 +91,264 @@ impl Topic {
         &self.sql
     }
 
+    /// Scope of the topic.
+    pub fn kind(&self) -> TopicKind {
+        self.ty
+            .as_deref()
+            .and_then(TopicKind::from_type)
+            .unwrap_or_else(|| TopicKind::from_sql(&self.sql))
+    }
+
+    /// Check if the topic is created `WITH META`.
+    pub fn with_meta(&self) -> bool {
+        match self.meta.as_deref() {
+            Some(meta) => meta.eq_ignore_ascii_case("with_meta"),
+            None => {
+                let sql = self.sql.to_lowercase();
+                let tokens: Vec<_> = sql.split_whitespace().collect();
+                tokens.windows(2).any(|w| w == ["with", "meta"])
+            }
+        }
+    }
+
     /// Check if the topic is a database-scope topic, otherwise is table-scope topic.
     pub fn is_db_topic(&self) -> bool {
-        self.sql.contains("as database")
+        self.kind() == TopicKind::Database
     }
 
     pub fn is_stable_topic(&self) -> bool {
-        self.sql.contains("as stable")
+        self.kind() == TopicKind::Stable
+    }
+}
+
+/// Typed builder for `CREATE TOPIC` sql.
+///
+/// ```rust
+/// # use taos_query::helpers::TopicBuilder;
+/// let topic = TopicBuilder::query("tp1", "db1.stb1").columns(["ts", "c1"]).filter("c1 > 0");
+/// assert_eq!(
+///     topic.to_string(),
+///     "CREATE TOPIC IF NOT EXISTS `tp1` AS SELECT ts, c1 FROM db1.stb1 WHERE c1 > 0"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicBuilder {
+    name: String,
+    if_not_exists: bool,
+    with_meta: bool,
+    scope: TopicScope,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TopicScope {
+    Query {
+        table: String,
+        columns: Vec<String>,
+        filter: Option<String>,
+    },
+    Stable {
+        stable: String,
+        filter: Option<String>,
+    },
+    Database(String),
+}
+
+impl TopicBuilder {
+    fn new(name: impl Into<String>, scope: TopicScope) -> Self {
+        Self {
+            name: name.into(),
+            if_not_exists: true,
+            with_meta: false,
+            scope,
+        }
+    }
+
+    /// Query topic from a table or super table, subscribe all columns by default.
+    pub fn query(name: impl Into<String>, table: impl Into<String>) -> Self {
+        Self::new(
+            name,
+            TopicScope::Query {
+                table: table.into(),
+                columns: Vec::new(),
+                filter: None,
+            },
+        )
+    }
+
+    /// Super table topic.
+    pub fn stable(name: impl Into<String>, stable: impl Into<String>) -> Self {
+        Self::new(
+            name,
+            TopicScope::Stable {
+                stable: stable.into(),
+                filter: None,
+            },
+        )
+    }
+
+    /// Database topic.
+    pub fn database(name: impl Into<String>, db: impl Into<String>) -> Self {
+        Self::new(name, TopicScope::Database(db.into()))
     }
+
+    /// Columns or expressions to subscribe, only for query topics.
+    pub fn columns<T: Into<String>>(mut self, columns: impl IntoIterator<Item = T>) -> Self {
+        if let TopicScope::Query { columns: c, .. } = &mut self.scope {
+            *c = columns.into_iter().map(Into::into).collect();
+        }
+        self
+    }
+
+    /// `WHERE` condition for query and super table topics.
+    pub fn filter(mut self, condition: impl Into<String>) -> Self {
+        match &mut self.scope {
+            TopicScope::Query { filter, .. } | TopicScope::Stable { filter, .. } => {
+                *filter = Some(condition.into())
+            }
+            TopicScope::Database(_) => (),
+        }
+        self
+    }
+
+    /// Subscribe meta changes with `WITH META`, only for super table and database topics.
+    ///
+    /// Query topics do not support it, [TopicBuilder::to_sql] will fail if it's set.
+    pub fn with_meta(mut self, with_meta: bool) -> Self {
+        self.with_meta = with_meta;
+        self
+    }
+
+    /// Add `IF NOT EXISTS` or not, default is true.
+    pub fn if_not_exists(mut self, if_not_exists: bool) -> Self {
+        self.if_not_exists = if_not_exists;
+        self
+    }
+
+    /// Topic name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Scope of the topic to be created.
+    pub fn kind(&self) -> TopicKind {
+        match self.scope {
+            TopicScope::Query { .. } => TopicKind::Query,
+            TopicScope::Stable { .. } => TopicKind::Stable,
+            TopicScope::Database(_) => TopicKind::Database,
+        }
+    }
+
+    /// Validate the options and build the `CREATE TOPIC` sql.
+    pub fn to_sql(&self) -> Result<String, taos_error::Error> {
+        check_topic_name(&self.name)?;
+        if self.with_meta && self.kind() == TopicKind::Query {
+            return Err(taos_error::Error::from_string(format!(
+                "query topic {} does not support WITH META",
+                self.name
+            )));
+        }
+        Ok(self.to_string())
+    }
+}
+
+impl Display for TopicBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CREATE TOPIC ")?;
+        if self.if_not_exists {
+            f.write_str("IF NOT EXISTS ")?;
+        }
+        write!(f, "`{}` ", self.name)?;
+        let with_meta = if self.with_meta { "WITH META " } else { "" };
+        match &self.scope {
+            TopicScope::Query {
+                table,
+                columns,
+                filter,
+            } => {
+                if columns.is_empty() {
+                    write!(f, "AS SELECT * FROM {table}")?;
+                } else {
+                    write!(f, "AS SELECT {} FROM {table}", columns.join(", "))?;
+                }
+                if let Some(filter) = filter {
+                    write!(f, " WHERE {filter}")?;
+                }
+            }
+            TopicScope::Stable { stable, filter } => {
+                write!(f, "{with_meta}AS STABLE {stable}")?;
+                if let Some(filter) = filter {
+                    write!(f, " WHERE {filter}")?;
+                }
+            }
+            TopicScope::Database(db) => write!(f, "{with_meta}AS DATABASE {db}")?,
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_topic_builder() {
+    let topic = TopicBuilder::query("tp1", "db1.stb1");
+    assert_eq!(topic.kind(), TopicKind::Query);
+    assert_eq!(
+        topic.to_string(),
+        "CREATE TOPIC IF NOT EXISTS `tp1` AS SELECT * FROM db1.stb1"
+    );
+
+    let topic = TopicBuilder::stable("tp2", "db1.stb1")
+        .with_meta(true)
+        .filter("t1 = 1")
+        .if_not_exists(false);
+    assert_eq!(
+        topic.to_string(),
+        "CREATE TOPIC `tp2` WITH META AS STABLE db1.stb1 WHERE t1 = 1"
+    );
+
+    let topic = TopicBuilder::database("tp3", "db1").with_meta(true);
+    assert_eq!(topic.kind(), TopicKind::Database);
+    assert_eq!(
+        topic.to_string(),
+        "CREATE TOPIC IF NOT EXISTS `tp3` WITH META AS DATABASE db1"
+    );
+    assert!(topic.to_sql().is_ok());
+}
+
+#[test]
+fn test_topic_builder_invalid() {
+    let topic = TopicBuilder::query("tp1", "db1.stb1").with_meta(true);
+    let err = topic.to_sql().unwrap_err();
+    assert_eq!(err.message(), "query topic tp1 does not support WITH META");
+
+    assert!(TopicBuilder::database("tp`1", "db1").to_sql().is_err());
+    assert!(TopicBuilder::database("", "db1").to_sql().is_err());
+}
+
+#[test]
+fn test_topic_name_escape() {
+    assert_eq!(quote_literal("tp1"), "'tp1'");
+    assert_eq!(quote_literal("tp' or '1'='1"), r"'tp\' or \'1\'=\'1'");
+    assert_eq!(quote_literal(r"tp\"), r"'tp\\'");
+    assert!(check_topic_name("tp1").is_ok());
+    assert!(check_topic_name("tp`; drop database db1").is_err());
+}
+
+#[test]
+fn test_topic_kind() {
+    assert_eq!(
+        TopicKind::from_sql("create topic tp1 as select * from stb1"),
+        TopicKind::Query
+    );
+    assert_eq!(
+        TopicKind::from_sql("CREATE TOPIC tp1 WITH META AS STABLE db1.stb1"),
+        TopicKind::Stable
+    );
+    assert_eq!(
+        TopicKind::from_sql("create topic `as database` as select ts from db1.`as database`"),
+        TopicKind::Query
+    );
+    assert_eq!(
+        TopicKind::from_sql("create topic tp1 as database db1"),
+        TopicKind::Database
+    );
+    assert_eq!(TopicKind::from_type("db"), Some(TopicKind::Database));
 }
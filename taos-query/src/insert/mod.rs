@@ -1,46 +1,241 @@
 use std::fmt::Debug;
 
+use serde::Serialize;
+
+use crate::common::itypes::{IBlob, IMediumBlob, INChar, ITimestamp, IVarBinary, IVarChar};
+use crate::common::views::ColumnView;
+use crate::common::{Field, Precision, Ty, Value};
+use crate::stmt::{values_to_view, RowsError};
+use crate::RawBlock;
+
 pub trait Insertable: Debug {
-    type Error;
+    type Error: From<RowsError>;
+
     fn insert(&self, sql: &str) -> Result<usize, Self::Error>;
 
-    fn insert_raw(&self, table: &str, raw: &[u8]) -> Result<usize, Self::Error>;
+    /// Insert columns into `table`, each view is the column named in `names` at the same position.
+    fn insert_columns(
+        &self,
+        table: &str,
+        names: &[&str],
+        columns: &[ColumnView],
+    ) -> Result<usize, Self::Error>;
+
+    /// Insert a raw block into `table` with its field names.
+    fn insert_raw(&self, table: &str, raw: &RawBlock) -> Result<usize, Self::Error> {
+        let names: Vec<&str> = raw.field_names().iter().map(String::as_str).collect();
+        self.insert_columns(table, &names, raw.columns().as_slice())
+    }
 
-    fn insert_named<'a>(&self, table: &str, fields: [&dyn IntoNamedField<'a>]);
+    /// Insert a row of named values into `table`, each value is of its default type in [IntoField].
+    ///
+    /// ```rust,ignore
+    /// taos.insert_named("tb1", &[&("ts", ITimestamp(1654570964022)), &("v", 1), &("s", "abc")])?;
+    /// ```
+    fn insert_named<'a>(
+        &self,
+        table: &str,
+        fields: &[&'a dyn IntoNamedField<'a>],
+    ) -> Result<usize, Self::Error> {
+        let names: Vec<&str> = fields.iter().map(|field| field.name()).collect();
+        let columns = fields
+            .iter()
+            .map(|field| field.to_view())
+            .collect::<Result<Vec<_>, _>>()?;
+        self.insert_columns(table, &names, &columns)
+    }
 
     // fn insert_many<T: Any>(&self, table: &str, records: &[dyn Any]) -> Result<usize, Self::Error>;
 
     // fn insert_progressive(&self, stable: &str, ) -> Inserter;
 }
 
-pub trait IntoField: Debug {}
+/// Types could be inserted as a field, with the default data type.
+pub trait IntoField: Debug {
+    /// The data type of the field when not specified.
+    fn field_ty() -> Ty
+    where
+        Self: Sized;
+}
 
-impl IntoField for i32 {}
-impl IntoField for f32 {}
-impl IntoField for f64 {}
+macro_rules! _impl_into_field {
+    ($($ty:ty => $v:ident),* $(,)?) => {
+        $(
+            impl IntoField for $ty {
+                fn field_ty() -> Ty {
+                    Ty::$v
+                }
+            }
+        )*
+    };
+}
+
+_impl_into_field! {
+    bool => Bool,
+    i8 => TinyInt,
+    i16 => SmallInt,
+    i32 => Int,
+    i64 => BigInt,
+    u8 => UTinyInt,
+    u16 => USmallInt,
+    u32 => UInt,
+    u64 => UBigInt,
+    f32 => Float,
+    f64 => Double,
+    &str => VarChar,
+    String => VarChar,
+    IVarChar => VarChar,
+    INChar => NChar,
+    serde_json::Value => Json,
+    ITimestamp => Timestamp,
+    chrono::DateTime<chrono::Utc> => Timestamp,
+    chrono::DateTime<chrono::Local> => Timestamp,
+    chrono::DateTime<chrono::FixedOffset> => Timestamp,
+    &[u8] => VarBinary,
+    Vec<u8> => VarBinary,
+    IVarBinary => VarBinary,
+    IBlob => Blob,
+    IMediumBlob => MediumBlob,
+    rust_decimal::Decimal => Decimal,
+}
+
+impl<T: IntoField> IntoField for Option<T> {
+    fn field_ty() -> Ty {
+        T::field_ty()
+    }
+}
 
 pub trait IntoNamedField<'a>: IntoField {
     fn name(&'a self) -> &'a str;
+
+    /// Single row column of the value, with its default type.
+    fn to_view(&self) -> Result<ColumnView, RowsError>;
+}
+impl<T: IntoField> IntoField for (&str, T) {
+    fn field_ty() -> Ty {
+        T::field_ty()
+    }
 }
-impl<T: IntoField> IntoField for (&str, T) {}
 
-impl<'a, T: IntoField> IntoNamedField<'a> for (&str, T) {
+impl<'a, T: IntoField + Serialize> IntoNamedField<'a> for (&str, T) {
     fn name(&'a self) -> &'a str {
         self.0
     }
+
+    fn to_view(&self) -> Result<ColumnView, RowsError> {
+        column(self.0, std::slice::from_ref(&self.1), None)
+    }
+}
+
+/// Build a column of `values`, with type `ty` or the default type of `T`.
+///
+/// Timestamps are in milliseconds, integers or RFC3339 strings are accepted with `Ty::Timestamp`.
+pub fn column<T: IntoField + Serialize>(
+    name: &str,
+    values: &[T],
+    ty: Option<Ty>,
+) -> Result<ColumnView, RowsError> {
+    let field = Field::new(name, ty.unwrap_or_else(T::field_ty), 0);
+    values_to_view(values, &field, Precision::Millisecond)
+}
+
+fn quote_ident(name: &str) -> Result<String, RowsError> {
+    if name.is_empty() || name.contains('`') {
+        return Err(RowsError::InvalidName(name.to_string()));
+    }
+    Ok(format!("`{name}`"))
+}
+
+/// Quote `db.tb` or `tb` with backticks, names with backticks are rejected.
+pub fn quote_table(table: &str) -> Result<String, RowsError> {
+    match table.split_once('.') {
+        Some((db, tb)) => Ok(format!("{}.{}", quote_ident(db)?, quote_ident(tb)?)),
+        None => quote_ident(table),
+    }
+}
+
+/// Build the stmt sql to insert `names` columns into `table`, identifiers are quoted with backticks.
+///
+/// `table` could be `db.tb` or `tb`, names with backticks are rejected as they could not be quoted.
+pub fn insert_sql(table: &str, names: &[&str]) -> Result<String, RowsError> {
+    let table = quote_table(table)?;
+    let names = names
+        .iter()
+        .map(|name| quote_ident(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!(
+        "insert into {table} ({}) values({})",
+        names.join(","),
+        vec!["?"; names.len()].join(",")
+    ))
+}
+
+/// Build the sql to create subtable `table` of `stable` with `tags` if it does not exist.
+///
+/// Table names are quoted like [insert_sql]. Timestamp tags are written as RFC3339 strings in
+/// UTC, so the server converts them to the database precision.
+pub fn create_subtable_sql(table: &str, stable: &str, tags: &[Value]) -> Result<String, RowsError> {
+    Ok(format!(
+        "create table if not exists {} using {} tags({})",
+        quote_table(table)?,
+        quote_table(stable)?,
+        tags.iter()
+            .map(|tag| match tag {
+                Value::Timestamp(ts) => format!(
+                    "\"{}\"",
+                    ts.to_naive_datetime().format("%Y-%m-%dT%H:%M:%S%.fZ")
+                ),
+                tag => tag.to_sql_value(),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    ))
+}
+
+/// Insert columns with names, used in [insert!](crate::insert!).
+#[doc(hidden)]
+pub fn insert_columns<I: Insertable>(
+    conn: &I,
+    table: &str,
+    names: &[&str],
+    columns: Vec<Result<ColumnView, RowsError>>,
+) -> Result<usize, I::Error> {
+    let columns = columns.into_iter().collect::<Result<Vec<_>, _>>()?;
+    conn.insert_columns(table, names, &columns)
 }
 
 /// Insert macros.
 ///
-/// insert! {
-///    "ts" => ["2022-10-10T10:10:10.000", "2022-10-10T10:10:11", "2022-10-10T10:10:12"],
+/// Values of each column are built into a [ColumnView] with the default type of the values,
+/// or the type after `:`, like `VarChar`, `NChar`, `Json` or `Timestamp`.
+///
+/// ```rust,ignore
+/// insert!(taos, "tb1", {
+///    "ts" => ["2022-10-10T10:10:10.000+08:00", "2022-10-10T10:10:11+08:00", "2022-10-10T10:10:12+08:00"]: Timestamp,
 ///    "n" => [0, 1, 2],
 ///    "f" => [0.1, 0.2, 0.3],
-///    "g" => ["abc", "def", "desc"]: VarChar,
-/// }
-// macro_rules! {
-//     ($conn:expr, $sql:expr, $raw: )
-// }
+///    "g" => ["abc", "def", "desc"]: NChar,
+/// })?;
+/// ```
+#[macro_export]
+macro_rules! insert {
+    ($conn:expr, $table:expr, {
+        $($name:literal => [$($value:expr),* $(,)?] $(: $ty:ident)?),* $(,)?
+    }) => {
+        $crate::insert::insert_columns(
+            &$conn,
+            $table,
+            &[$($name),*],
+            vec![$(
+                $crate::insert::column(
+                    $name,
+                    &[$($value),*],
+                    None$(.or(Some($crate::common::Ty::$ty)))?,
+                )
+            ),*],
+        )
+    };
+}
 
 #[test]
 fn obj() {
@@ -49,12 +244,164 @@ fn obj() {
     let v = vec![&10 as _, &0.0 as _];
     t1(&v);
 
-    fn insert_named<'a>(_fields: &[&dyn IntoNamedField<'a>]) {}
+    fn names<'a>(fields: &[&'a dyn IntoNamedField<'a>]) -> Vec<&'a str> {
+        fields.iter().map(|field| field.name()).collect()
+    }
 
     let v = vec![&("a", 0) as _, &("b", 0.0) as _];
-    insert_named(&v);
+    assert_eq!(names(&v), ["a", "b"]);
+}
+
+#[test]
+fn test_field_ty() {
+    assert_eq!(<INChar as IntoField>::field_ty(), Ty::NChar);
+    assert_eq!(<ITimestamp as IntoField>::field_ty(), Ty::Timestamp);
+    assert_eq!(<Vec<u8> as IntoField>::field_ty(), Ty::VarBinary);
+    assert_eq!(
+        <Option<rust_decimal::Decimal> as IntoField>::field_ty(),
+        Ty::Decimal
+    );
+
+    let view = column("b", &[IVarBinary::from(vec![0u8, 255])], None).unwrap();
+    assert_eq!(view.as_ty(), Ty::VarBinary);
+    let view = column("ts", &[ITimestamp(1654570964022)], None).unwrap();
+    assert_eq!(view.as_ty(), Ty::Timestamp);
+    let view = column("d", &[rust_decimal::Decimal::new(12345, 2)], None).unwrap();
+    match view {
+        ColumnView::Decimal(view) => assert_eq!(view.get_string(0).unwrap(), "123.45"),
+        view => panic!("unexpected view: {view:?}"),
+    }
+}
+
+#[test]
+fn test_quote_table() {
+    assert_eq!(quote_table("meters").unwrap(), "`meters`");
+    assert_eq!(quote_table("db1.meters").unwrap(), "`db1`.`meters`");
+    assert!(quote_table("db1.`meters`").is_err());
+    assert!(quote_table(".meters").is_err());
+    assert!(quote_table("").is_err());
+}
+
+#[test]
+fn test_insert_sql() {
+    assert_eq!(
+        insert_sql("db1.tb1", &["ts", "v"]).unwrap(),
+        "insert into `db1`.`tb1` (`ts`,`v`) values(?,?)"
+    );
+    assert_eq!(
+        insert_sql("tb1", &["ts"]).unwrap(),
+        "insert into `tb1` (`ts`) values(?)"
+    );
+    assert!(insert_sql("tb1", &["v`) values(1); drop database db1; --"]).is_err());
+    assert!(insert_sql("tb`1", &["ts"]).is_err());
+}
+
+#[test]
+fn test_create_subtable_sql() {
+    assert_eq!(
+        create_subtable_sql(
+            "db1.tb1",
+            "stb1",
+            &[
+                Value::Int(1),
+                Value::VarChar("a\"b".to_string()),
+                Value::Null
+            ]
+        )
+        .unwrap(),
+        r#"create table if not exists `db1`.`tb1` using `stb1` tags(1,"a\"b",NULL)"#
+    );
+    assert_eq!(
+        create_subtable_sql(
+            "tb1",
+            "stb1",
+            &[
+                Value::Json(serde_json::json!({"a": "b"})),
+                Value::Timestamp(crate::common::Timestamp::new(
+                    1654570964022450088,
+                    Precision::Nanosecond
+                )),
+                Value::Timestamp(crate::common::Timestamp::new(
+                    1654570964022,
+                    Precision::Millisecond
+                )),
+            ]
+        )
+        .unwrap(),
+        r#"create table if not exists `tb1` using `stb1` tags("{\"a\":\"b\"}","2022-06-07T03:02:44.022450088Z","2022-06-07T03:02:44.022Z")"#
+    );
+    assert!(create_subtable_sql("tb1", "stb`1", &[]).is_err());
+}
 
-    for e in v {
-        dbg!(e.name());
+#[test]
+fn test_insert_macro() {
+    #[derive(Debug)]
+    struct Columns(std::cell::RefCell<Vec<(String, Ty, usize)>>);
+
+    impl Insertable for Columns {
+        type Error = RowsError;
+
+        fn insert(&self, _: &str) -> Result<usize, Self::Error> {
+            Ok(0)
+        }
+
+        fn insert_columns(
+            &self,
+            table: &str,
+            names: &[&str],
+            columns: &[ColumnView],
+        ) -> Result<usize, Self::Error> {
+            assert_eq!(table, "tb1");
+            self.0.borrow_mut().extend(
+                names
+                    .iter()
+                    .zip(columns)
+                    .map(|(name, view)| (name.to_string(), view.as_ty(), view.len())),
+            );
+            Ok(columns[0].len())
+        }
     }
+
+    let conn = Columns(Default::default());
+    let rows = insert!(conn, "tb1", {
+        "ts" => ["2022-10-10T10:10:10.000+08:00", "2022-10-10T10:10:11+08:00"]: Timestamp,
+        "n" => [0, 1],
+        "f" => [Some(0.1), None],
+        "g" => ["abc", "def"]: NChar,
+    })
+    .unwrap();
+    assert_eq!(rows, 2);
+    assert_eq!(
+        conn.0.into_inner(),
+        vec![
+            ("ts".to_string(), Ty::Timestamp, 2),
+            ("n".to_string(), Ty::Int, 2),
+            ("f".to_string(), Ty::Double, 2),
+            ("g".to_string(), Ty::NChar, 2),
+        ]
+    );
+
+    let err = insert!(conn, "tb1", { "n" => ["a"]: Int }).unwrap_err();
+    assert!(err.to_string().contains("column `n`"));
+
+    let conn = Columns(Default::default());
+    let rows = conn
+        .insert_named(
+            "tb1",
+            &[
+                &("ts", ITimestamp(1654570964022)),
+                &("n", 1i64),
+                &("g", INChar::from("abc".to_string())),
+            ],
+        )
+        .unwrap();
+    assert_eq!(rows, 1);
+    assert_eq!(
+        conn.0.into_inner(),
+        vec![
+            ("ts".to_string(), Ty::Timestamp, 1),
+            ("n".to_string(), Ty::BigInt, 1),
+            ("g".to_string(), Ty::NChar, 1),
+        ]
+    );
 }
@@ -14,10 +14,15 @@ use std::{
 pub use mdsn::{Address, Dsn, DsnError, IntoDsn};
 pub use serde::de::value::Error as DeError;
 
+#[cfg(feature = "arrow")]
+pub use arrow;
+#[cfg(feature = "parquet")]
+pub use parquet;
+
 pub mod common;
 mod de;
 pub mod helpers;
-mod insert;
+pub mod insert;
 
 mod iter;
 pub mod util;
@@ -62,14 +67,15 @@ pub fn block_in_place_or_global<F: std::future::Future>(fut: F) -> F::Output {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CodecOpts {
     Raw,
     Parquet,
 }
 
-pub trait BlockCodec {
-    fn encode(&self, _codec: CodecOpts) -> Vec<u8>;
-    fn decode(from: &[u8], _codec: CodecOpts) -> Self;
+pub trait BlockCodec: Sized {
+    fn encode(&self, _codec: CodecOpts) -> Result<Vec<u8>, taos_error::Error>;
+    fn decode(from: &[u8], _codec: CodecOpts) -> Result<Self, taos_error::Error>;
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -237,7 +243,8 @@ mod tests {
                     &[1],
                     1,
                     Precision::Millisecond,
-                )))
+                )
+                .unwrap()))
             } else {
                 None
             }
@@ -275,13 +282,16 @@ mod tests {
             }
             unsafe { B.fetch_sub(1, std::sync::atomic::Ordering::SeqCst) };
 
-            Ok(Some(RawBlock::parse_from_raw_block_v2(
-                [1].as_slice(),
-                &[Field::new("a", Ty::TinyInt, 1)],
-                &[1],
-                1,
-                Precision::Millisecond,
-            )))
+            Ok(Some(
+                RawBlock::parse_from_raw_block_v2(
+                    [1].as_slice(),
+                    &[Field::new("a", Ty::TinyInt, 1)],
+                    &[1],
+                    1,
+                    Precision::Millisecond,
+                )
+                .unwrap(),
+            ))
         }
     }
 
@@ -353,6 +363,14 @@ mod tests {
         fn write_meta(&self, _: RawMeta) -> Result<(), Self::Error> {
             Ok(())
         }
+
+        fn write_raw_block(&self, _: &RawBlock) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn write_raw_block_to(&self, _: &str, _: &RawBlock) -> Result<(), Self::Error> {
+            Ok(())
+        }
     }
     #[test]
     fn query_deserialize() {
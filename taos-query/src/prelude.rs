@@ -187,6 +187,44 @@ pub mod sync {
 
         fn write_meta(&self, _: RawMeta) -> Result<(), Self::Error>;
 
+        /// Write a raw block into the table named in the block, like blocks of tmq data messages.
+        ///
+        /// It fails when the block has no table name, use [Queryable::write_raw_block_to] for such blocks.
+        fn write_raw_block(&self, block: &RawBlock) -> Result<(), Self::Error> {
+            let table = block.table_name().ok_or_else(|| {
+                <Self::ResultSet as Fetchable>::Error::from(RawError::from_string(
+                    "raw block has no table name",
+                ))
+            })?;
+            self.write_raw_block_to(table, block)
+        }
+
+        /// Write a raw block into `table`, the table name in the block is ignored.
+        ///
+        /// Connections without raw block support return an error.
+        fn write_raw_block_to(&self, _table: &str, _block: &RawBlock) -> Result<(), Self::Error> {
+            Err(<Self::ResultSet as Fetchable>::Error::from(RawError::new(
+                Code::Failed,
+                "write_raw_block_to is not supported",
+            ))
+            .into())
+        }
+
+        /// Write a raw block into subtable `table`, it is created from `stable` with `tags` if it does not exist.
+        fn write_raw_block_with_tags(
+            &self,
+            table: &str,
+            stable: &str,
+            tags: &[Value],
+            block: &RawBlock,
+        ) -> Result<(), Self::Error> {
+            let sql = crate::insert::create_subtable_sql(table, stable, tags)
+                .map_err(|err| RawError::from_string(err.to_string()))
+                .map_err(<Self::ResultSet as Fetchable>::Error::from)?;
+            self.exec(sql)?;
+            self.write_raw_block_to(table, block)
+        }
+
         fn exec_many<T: AsRef<str>, I: IntoIterator<Item = T>>(
             &self,
             input: I,
@@ -235,6 +273,36 @@ pub mod sync {
             Ok(())
         }
 
+        /// Create topic with a typed [TopicBuilder].
+        fn create_topic_with(&self, topic: &TopicBuilder) -> Result<(), Self::Error> {
+            let sql = topic
+                .to_sql()
+                .map_err(<Self::ResultSet as Fetchable>::Error::from)?;
+            self.exec(sql)?;
+            Ok(())
+        }
+
+        /// Drop a topic if exists, `force` to drop it even if there're consumers subscribing it.
+        fn drop_topic(&self, name: impl AsRef<str>, force: bool) -> Result<(), Self::Error> {
+            let name = name.as_ref();
+            check_topic_name(name).map_err(<Self::ResultSet as Fetchable>::Error::from)?;
+            let force = if force { "FORCE " } else { "" };
+            self.exec(format!("DROP TOPIC IF EXISTS {force}`{name}`"))?;
+            Ok(())
+        }
+
+        /// Topic information from `information_schema.ins_topics`.
+        ///
+        /// ## Compatibility
+        ///
+        /// This is a 3.x-only API.
+        fn describe_topic(&self, name: impl AsRef<str>) -> Result<Option<Topic>, Self::Error> {
+            let name = quote_literal(name.as_ref());
+            self.query_one(format!(
+                "select * from information_schema.ins_topics where topic_name = {name}"
+            ))
+        }
+
         fn databases(&self) -> Result<Vec<ShowDatabase>, Self::Error> {
             self.query("show databases")?
                 .deserialize()
@@ -254,6 +322,46 @@ pub mod sync {
                 .map_err(Into::into)
         }
 
+        /// Consumers information from `information_schema.ins_consumers`.
+        ///
+        /// ## Compatibility
+        ///
+        /// This is a 3.x-only API.
+        fn consumers(&self) -> Result<Vec<ConsumerInfo>, Self::Error> {
+            self.query("select * from information_schema.ins_consumers")?
+                .deserialize()
+                .try_collect()
+                .map_err(Into::into)
+        }
+
+        /// Subscriptions of each topic vgroup from `information_schema.ins_subscriptions`.
+        ///
+        /// ## Compatibility
+        ///
+        /// This is a 3.x-only API.
+        fn subscriptions(&self) -> Result<Vec<Subscription>, Self::Error> {
+            self.query("select * from information_schema.ins_subscriptions")?
+                .deserialize()
+                .try_collect()
+                .map_err(Into::into)
+        }
+
+        /// Committed offset, end offset and lag of each topic vgroup for consumer `group`.
+        ///
+        /// End offsets are taken from `assignments`, see [crate::prelude::AsyncQueryable::consumer_lags].
+        fn consumer_lags(
+            &self,
+            group: &str,
+            assignments: &[(String, Vec<crate::tmq::Assignment>)],
+        ) -> Result<Vec<VGroupLag>, Self::Error> {
+            let subscriptions = self.subscriptions()?;
+            Ok(VGroupLag::from_subscriptions(
+                group,
+                &subscriptions,
+                assignments,
+            ))
+        }
+
         fn describe(&self, table: &str) -> Result<Describe, Self::Error> {
             Ok(Describe(
                 self.query(format!("describe `{table}`"))?
@@ -273,6 +381,7 @@ mod r#async {
 
     use crate::common::*;
     use crate::helpers::*;
+    pub use crate::stmt::AsyncBindable;
     pub use crate::stmt::Bindable;
 
     pub use super::_priv::*;
@@ -474,7 +583,59 @@ mod r#async {
 
         async fn write_raw_meta(&self, _: RawMeta) -> Result<(), Self::Error>;
 
-        async fn write_raw_block(&self, block: &RawBlock) -> Result<(), Self::Error>;
+        /// Write a raw block into the table named in the block, like blocks of tmq data messages.
+        ///
+        /// It fails when the block has no table name, use [AsyncQueryable::write_raw_block_to] for such blocks.
+        async fn write_raw_block(&self, block: &RawBlock) -> Result<(), Self::Error> {
+            let table = block.table_name().ok_or_else(|| {
+                <Self::AsyncResultSet as AsyncFetchable>::Error::from(RawError::from_string(
+                    "raw block has no table name",
+                ))
+            })?;
+            self.write_raw_block_to(table, block).await
+        }
+
+        /// Write a raw block into `table`, the table name in the block is ignored.
+        ///
+        /// Connections without raw block support return an error.
+        async fn write_raw_block_to(
+            &self,
+            _table: &str,
+            _block: &RawBlock,
+        ) -> Result<(), Self::Error> {
+            Err(
+                <Self::AsyncResultSet as AsyncFetchable>::Error::from(RawError::new(
+                    Code::Failed,
+                    "write_raw_block_to is not supported",
+                ))
+                .into(),
+            )
+        }
+
+        /// Write a raw block into subtable `table`, it is created from `stable` with `tags` if it does not exist.
+        async fn write_raw_block_with_tags(
+            &self,
+            table: &str,
+            stable: &str,
+            tags: &[Value],
+            block: &RawBlock,
+        ) -> Result<(), Self::Error> {
+            let sql = crate::insert::create_subtable_sql(table, stable, tags)
+                .map_err(|err| RawError::from_string(err.to_string()))
+                .map_err(<Self::AsyncResultSet as AsyncFetchable>::Error::from)?;
+            self.exec(sql).await?;
+            self.write_raw_block_to(table, block).await
+        }
+
+        /// Initialize a statement on the connection.
+        ///
+        /// ```rust,ignore
+        /// let mut stmt: Stmt = taos.stmt_init().await?;
+        /// stmt.prepare("insert into tb1 values(?, ?)").await?;
+        /// ```
+        async fn stmt_init<S: AsyncBindable<Self>>(&self) -> Result<S, S::Error> {
+            S::init(self).await
+        }
 
         async fn exec_many<T, I>(&self, input: I) -> Result<usize, Self::Error>
         where
@@ -546,6 +707,46 @@ mod r#async {
             Ok(())
         }
 
+        /// Create topic with a typed [TopicBuilder].
+        async fn create_topic_with(&self, topic: &TopicBuilder) -> Result<(), Self::Error> {
+            let sql = topic
+                .to_sql()
+                .map_err(<Self::AsyncResultSet as AsyncFetchable>::Error::from)?;
+            self.exec(sql).await?;
+            Ok(())
+        }
+
+        /// Drop a topic if exists, `force` to drop it even if there're consumers subscribing it.
+        async fn drop_topic<N: AsRef<str> + Send + Sync>(
+            &self,
+            name: N,
+            force: bool,
+        ) -> Result<(), Self::Error> {
+            let name = name.as_ref();
+            check_topic_name(name)
+                .map_err(<Self::AsyncResultSet as AsyncFetchable>::Error::from)?;
+            let force = if force { "FORCE " } else { "" };
+            self.exec(format!("DROP TOPIC IF EXISTS {force}`{name}`"))
+                .await?;
+            Ok(())
+        }
+
+        /// Topic information from `information_schema.ins_topics`.
+        ///
+        /// ## Compatibility
+        ///
+        /// This is a 3.x-only API.
+        async fn describe_topic<N: AsRef<str> + Send + Sync>(
+            &self,
+            name: N,
+        ) -> Result<Option<Topic>, Self::Error> {
+            let name = quote_literal(name.as_ref());
+            self.query_one(format!(
+                "select * from information_schema.ins_topics where topic_name = {name}"
+            ))
+            .await
+        }
+
         async fn databases(&self) -> Result<Vec<ShowDatabase>, Self::Error> {
             use futures::stream::TryStreamExt;
             Ok(self
@@ -571,6 +772,66 @@ mod r#async {
                 .await?)
         }
 
+        /// Consumers information from `information_schema.ins_consumers`.
+        ///
+        /// ## Compatibility
+        ///
+        /// This is a 3.x-only API.
+        async fn consumers(&self) -> Result<Vec<ConsumerInfo>, Self::Error> {
+            Ok(self
+                .query("select * from information_schema.ins_consumers")
+                .await?
+                .deserialize()
+                .try_collect()
+                .await?)
+        }
+
+        /// Subscriptions of each topic vgroup from `information_schema.ins_subscriptions`.
+        ///
+        /// ## Compatibility
+        ///
+        /// This is a 3.x-only API.
+        async fn subscriptions(&self) -> Result<Vec<Subscription>, Self::Error> {
+            Ok(self
+                .query("select * from information_schema.ins_subscriptions")
+                .await?
+                .deserialize()
+                .try_collect()
+                .await?)
+        }
+
+        /// Committed offset, end offset and lag of each topic vgroup for consumer `group`.
+        ///
+        /// The server does not expose end offsets by sql, so they are taken from `assignments`,
+        /// e.g. [AsAsyncConsumer::assignments] of any consumer subscribing the topics.
+        ///
+        /// ## Compatibility
+        ///
+        /// This is a 3.x-only API.
+        async fn consumer_lags(
+            &self,
+            group: &str,
+            assignments: &[(String, Vec<crate::tmq::Assignment>)],
+        ) -> Result<Vec<VGroupLag>, Self::Error> {
+            let subscriptions = self.subscriptions().await?;
+            Ok(VGroupLag::from_subscriptions(
+                group,
+                &subscriptions,
+                assignments,
+            ))
+        }
+
+        /// Consumer groups subscribing the topic.
+        async fn consumer_groups(&self, topic: &str) -> Result<Vec<String>, Self::Error> {
+            let subscriptions = self.subscriptions().await?;
+            Ok(subscriptions
+                .iter()
+                .filter(|sub| sub.topic() == topic)
+                .map(|sub| sub.group().to_string())
+                .unique()
+                .collect())
+        }
+
         /// Get table meta information.
         async fn describe(&self, table: &str) -> Result<Describe, Self::Error> {
             Ok(Describe(
@@ -3,8 +3,15 @@ use crate::{
     Queryable,
 };
 
+#[cfg(feature = "async")]
+use crate::AsyncQueryable;
+
 mod column;
+mod rows;
+mod stats;
 pub use column::*;
+pub use rows::*;
+pub use stats::*;
 
 pub trait Bindable<Q>
 where
@@ -31,34 +38,59 @@ where
 
     fn execute(&mut self) -> Result<usize, Self::Error>;
 
+    /// Execute with per-table binding statistics, tables are not tracked by default.
+    fn execute_bind_stats(&mut self) -> Result<StmtBindStats, Self::Error> {
+        self.execute().map(|affected_rows| StmtBindStats {
+            affected_rows,
+            tables: Vec::new(),
+        })
+    }
+
     fn affected_rows(&self) -> usize;
 
-    fn result_set(&mut self) -> Result<Q::ResultSet, Self::Error> {
-        todo!()
-    }
+    fn result_set(&mut self) -> Result<Q::ResultSet, Self::Error>;
 }
 
-// #[async_trait::async_trait]
-// pub trait AsyncBindable<Q>
-// where
-//     Q: AsyncQueryable,
-//     Self: Sized,
-// {
-//     type Error;
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+pub trait AsyncBindable<Q>
+where
+    Q: AsyncQueryable,
+    Self: Sized + Send,
+{
+    type Error: Send;
 
-//     async fn init(taos: Q) -> Result<Self, Self::Error>;
+    async fn init(taos: &Q) -> Result<Self, Self::Error>;
 
-//     async fn prepare<S: AsRef<str>>(&mut self, sql: S) -> Result<(), Self::Error>;
+    async fn prepare<S: AsRef<str> + Send>(&mut self, sql: S) -> Result<&mut Self, Self::Error>;
 
-//     async fn set_tbname<S: AsRef<str>>(&mut self, sql: S) -> Result<(), Self::Error>;
+    async fn set_tbname<S: AsRef<str> + Send>(&mut self, name: S) -> Result<&mut Self, Self::Error>;
 
-//     async fn set_tags(&mut self, tags: &[Value]) -> Result<(), Self::Error>;
+    async fn set_tags(&mut self, tags: &[Value]) -> Result<&mut Self, Self::Error>;
 
-//     async fn bind(&mut self, params: &[ColumnView]) -> Result<(), Self::Error>;
+    async fn set_tbname_tags<S: AsRef<str> + Send>(
+        &mut self,
+        name: S,
+        tags: &[Value],
+    ) -> Result<&mut Self, Self::Error> {
+        self.set_tbname(name).await?.set_tags(tags).await
+    }
 
-//     async fn add_batch(&mut self) -> Result<(), Self::Error>;
+    async fn bind(&mut self, params: &[ColumnView]) -> Result<&mut Self, Self::Error>;
 
-//     async fn execute(&mut self) -> Result<usize, Self::Error>;
+    async fn add_batch(&mut self) -> Result<&mut Self, Self::Error>;
 
-//     async fn result_set(&mut self) -> Result<Q::AsyncResultSet, Self::Error>;
-// }
+    async fn execute(&mut self) -> Result<usize, Self::Error>;
+
+    /// Execute with per-table binding statistics, tables are not tracked by default.
+    async fn execute_bind_stats(&mut self) -> Result<StmtBindStats, Self::Error> {
+        self.execute().await.map(|affected_rows| StmtBindStats {
+            affected_rows,
+            tables: Vec::new(),
+        })
+    }
+
+    fn affected_rows(&self) -> usize;
+
+    async fn result_set(&mut self) -> Result<Q::AsyncResultSet, Self::Error>;
+}
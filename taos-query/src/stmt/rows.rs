@@ -0,0 +1,690 @@
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use rust_decimal::prelude::FromPrimitive;
+
+use crate::common::views::{
+    decimal_precision_scale, ColumnView, DecimalView, JsonView, TimestampView, VarBinaryView,
+};
+use crate::common::{Decimal, Field, Precision, Ty, Value};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RowsError {
+    #[error("failed to serialize row {row}: {source}")]
+    Serialize {
+        row: usize,
+        source: serde_json::Error,
+    },
+    #[error("row {row} should be a struct, map, tuple or sequence, but got: {value}")]
+    InvalidRow { row: usize, value: JsonValue },
+    #[error("row {row} has unknown field `{name}`")]
+    UnknownField { row: usize, name: String },
+    #[error("row {row} expects {expected} values, but got {actual}")]
+    Arity {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("column `{column}` expects {ty}, but got {value} in row {row}")]
+    Mismatch {
+        column: String,
+        ty: Ty,
+        row: usize,
+        value: JsonValue,
+    },
+    #[error("column `{column}` with type {ty} is not supported for row binding")]
+    Unsupported { column: String, ty: Ty },
+    #[error("expect {expected} parameters, but got {actual}")]
+    Count { expected: usize, actual: usize },
+    #[error("invalid identifier `{0}`, which could not be quoted")]
+    InvalidName(String),
+    #[error("parameter `{name}` expects {expected}, but got {actual}")]
+    Type {
+        name: String,
+        expected: Ty,
+        actual: Ty,
+    },
+}
+
+/// Check column views against the column fields of a prepared statement.
+pub fn check_views(views: &[ColumnView], fields: &[Field]) -> Result<(), RowsError> {
+    check_types(views.iter().map(ColumnView::as_ty), fields)
+}
+
+/// Check tag values against the tag fields of a prepared statement, `NULL` is accepted for any type.
+pub fn check_values(values: &[Value], fields: &[Field]) -> Result<(), RowsError> {
+    let types = values.iter().enumerate().map(|(i, value)| {
+        match (value, fields.get(i).map(Field::ty)) {
+            // Decimals are rescaled to the precision of the field.
+            (Value::Decimal(_), Some(ty @ (Ty::Decimal | Ty::Decimal64))) => ty,
+            (value, _) => value.ty(),
+        }
+    });
+    check_types(types, fields)
+}
+
+/// Convert tag values to single row column views with types of the tag fields, to bind tags in raw block.
+///
+/// Timestamps are converted to `precision` of the table, other values are kept exact,
+/// and `NULL` is typed by the field.
+pub fn tags_to_views(
+    tags: &[Value],
+    fields: &[Field],
+    precision: Precision,
+) -> Result<Vec<ColumnView>, RowsError> {
+    check_values(tags, fields)?;
+    tags.iter()
+        .zip(fields)
+        .map(|(tag, field)| {
+            let view = match (tag, field.ty()) {
+                (Value::Timestamp(ts), _) => {
+                    let ts = ts
+                        .cast_precision(precision)
+                        .ok_or_else(|| RowsError::Mismatch {
+                            column: field.name().to_string(),
+                            ty: field.ty(),
+                            row: 0,
+                            value: JsonValue::from(ts.as_raw_i64()),
+                        })?;
+                    ColumnView::Timestamp(TimestampView::from_timestamp(vec![ts]))
+                }
+                (Value::Null, Ty::Timestamp) => ColumnView::Timestamp(match precision {
+                    Precision::Millisecond => TimestampView::from_millis(vec![None::<i64>]),
+                    Precision::Microsecond => TimestampView::from_micros(vec![None::<i64>]),
+                    Precision::Nanosecond => TimestampView::from_nanos(vec![None::<i64>]),
+                }),
+                (Value::Bool(v), _) => ColumnView::from_bools(vec![*v]),
+                (Value::Null, Ty::Bool) => ColumnView::from_bools(vec![None::<bool>]),
+                (Value::TinyInt(v), _) => ColumnView::from_tiny_ints(vec![*v]),
+                (Value::Null, Ty::TinyInt) => ColumnView::from_tiny_ints(vec![None::<i8>]),
+                (Value::SmallInt(v), _) => ColumnView::from_small_ints(vec![*v]),
+                (Value::Null, Ty::SmallInt) => ColumnView::from_small_ints(vec![None::<i16>]),
+                (Value::Int(v), _) => ColumnView::from_ints(vec![*v]),
+                (Value::Null, Ty::Int) => ColumnView::from_ints(vec![None::<i32>]),
+                (Value::BigInt(v), _) => ColumnView::from_big_ints(vec![*v]),
+                (Value::Null, Ty::BigInt) => ColumnView::from_big_ints(vec![None::<i64>]),
+                (Value::UTinyInt(v), _) => ColumnView::from_unsigned_tiny_ints(vec![*v]),
+                (Value::Null, Ty::UTinyInt) => {
+                    ColumnView::from_unsigned_tiny_ints(vec![None::<u8>])
+                }
+                (Value::USmallInt(v), _) => ColumnView::from_unsigned_small_ints(vec![*v]),
+                (Value::Null, Ty::USmallInt) => {
+                    ColumnView::from_unsigned_small_ints(vec![None::<u16>])
+                }
+                (Value::UInt(v), _) => ColumnView::from_unsigned_ints(vec![*v]),
+                (Value::Null, Ty::UInt) => ColumnView::from_unsigned_ints(vec![None::<u32>]),
+                (Value::UBigInt(v), _) => ColumnView::from_unsigned_big_ints(vec![*v]),
+                (Value::Null, Ty::UBigInt) => ColumnView::from_unsigned_big_ints(vec![None::<u64>]),
+                (Value::Float(v), _) => ColumnView::from_floats(vec![*v]),
+                (Value::Null, Ty::Float) => ColumnView::from_floats(vec![None::<f32>]),
+                (Value::Double(v), _) => ColumnView::from_doubles(vec![*v]),
+                (Value::Null, Ty::Double) => ColumnView::from_doubles(vec![None::<f64>]),
+                (Value::VarChar(v), _) => ColumnView::from_varchar(vec![v.as_str()]),
+                (Value::Null, Ty::VarChar) => ColumnView::from_varchar(vec![None::<&str>]),
+                (Value::NChar(v), _) => ColumnView::from_nchar(vec![v.as_str()]),
+                (Value::Null, Ty::NChar) => ColumnView::from_nchar(vec![None::<&str>]),
+                (Value::Json(v), _) => {
+                    ColumnView::Json(JsonView::from_iter(std::iter::once(Some(v.to_string()))))
+                }
+                (Value::Null, Ty::Json) => {
+                    ColumnView::Json(JsonView::from_iter(std::iter::once(None::<String>)))
+                }
+                (Value::VarBinary(v), _) => ColumnView::from_var_binary(vec![v.as_slice()]),
+                (Value::Null, Ty::VarBinary) => ColumnView::from_var_binary(vec![None::<&[u8]>]),
+                (Value::Blob(v), _) => ColumnView::from_blobs(vec![v.as_slice()]),
+                (Value::Null, Ty::Blob) => ColumnView::from_blobs(vec![None::<&[u8]>]),
+                (Value::MediumBlob(v), _) => {
+                    ColumnView::MediumBlob(VarBinaryView::from_iter(vec![v.as_slice()]))
+                }
+                (Value::Null, Ty::MediumBlob) => {
+                    ColumnView::MediumBlob(VarBinaryView::from_iter(vec![None::<&[u8]>]))
+                }
+                (Value::Decimal(v), _) => {
+                    // Without precision of the field, keep the precision and scale of the value.
+                    let (precision, scale) = match decimal_precision_scale(field.bytes()) {
+                        (0, _) => (v.precision(), v.scale()),
+                        precision_scale => precision_scale,
+                    };
+                    let value = v
+                        .cast(precision, scale)
+                        .ok_or_else(|| RowsError::Mismatch {
+                            column: field.name().to_string(),
+                            ty: field.ty(),
+                            row: 0,
+                            value: JsonValue::String(v.to_string()),
+                        })?;
+                    ColumnView::Decimal(DecimalView::from_scaled(
+                        [Some(value.as_raw_i128())],
+                        precision,
+                        scale,
+                    ))
+                }
+                (Value::Null, Ty::Decimal | Ty::Decimal64) => {
+                    let (precision, scale) = decimal_precision_scale(field.bytes());
+                    ColumnView::Decimal(DecimalView::from_scaled([None], precision, scale))
+                }
+                (_, ty) => {
+                    return Err(RowsError::Unsupported {
+                        column: field.name().to_string(),
+                        ty,
+                    })
+                }
+            };
+            Ok(view)
+        })
+        .collect()
+}
+
+fn check_types(
+    types: impl ExactSizeIterator<Item = Ty>,
+    fields: &[Field],
+) -> Result<(), RowsError> {
+    if types.len() != fields.len() {
+        return Err(RowsError::Count {
+            expected: fields.len(),
+            actual: types.len(),
+        });
+    }
+    match types
+        .zip(fields)
+        .find(|(ty, field)| *ty != Ty::Null && *ty != field.ty())
+    {
+        Some((actual, field)) => Err(RowsError::Type {
+            name: field.name().to_string(),
+            expected: field.ty(),
+            actual,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Transpose serializable rows into column views with types of `fields`, the write-side
+/// mirror of [Fetchable::deserialize](crate::Fetchable::deserialize).
+///
+/// Struct or map rows are matched to fields by name, tuple or sequence rows by position.
+/// Keys not in `fields` and tuples of other lengths are errors, missing keys and `None`
+/// values are bound as NULL. Timestamps could be integers in `precision` or RFC3339 strings,
+/// as `chrono::DateTime` is serialized, out of range timestamps are errors.
+///
+/// ```rust
+/// # use taos_query::common::{Field, Precision, Ty};
+/// # use taos_query::stmt::rows_to_views;
+/// #[derive(serde::Serialize)]
+/// struct Row {
+///     ts: i64,
+///     v: Option<i32>,
+/// }
+/// let fields = [Field::new("ts", Ty::Timestamp, 8), Field::new("v", Ty::Int, 4)];
+/// let rows = [Row { ts: 0, v: Some(1) }, Row { ts: 1, v: None }];
+/// let views = rows_to_views(&rows, &fields, Precision::Millisecond).unwrap();
+/// assert_eq!(views[1].len(), 2);
+/// ```
+pub fn rows_to_views<T: Serialize>(
+    rows: &[T],
+    fields: &[Field],
+    precision: Precision,
+) -> Result<Vec<ColumnView>, RowsError> {
+    let matrix = rows
+        .iter()
+        .enumerate()
+        .map(|(row, value)| {
+            match serde_json::to_value(value)
+                .map_err(|source| RowsError::Serialize { row, source })?
+            {
+                JsonValue::Object(mut map) => {
+                    let values = fields
+                        .iter()
+                        .map(|field| map.remove(field.name()).unwrap_or(JsonValue::Null))
+                        .collect();
+                    match map.into_iter().next() {
+                        Some((name, _)) => Err(RowsError::UnknownField { row, name }),
+                        None => Ok(values),
+                    }
+                }
+                JsonValue::Array(values) if values.len() != fields.len() => Err(RowsError::Arity {
+                    row,
+                    expected: fields.len(),
+                    actual: values.len(),
+                }),
+                JsonValue::Array(values) => Ok(values),
+                value => Err(RowsError::InvalidRow { row, value }),
+            }
+        })
+        .collect::<Result<Vec<Vec<JsonValue>>, _>>()?;
+
+    fields
+        .iter()
+        .enumerate()
+        .map(|(col, field)| column_from_json(&matrix, col, field, precision))
+        .collect()
+}
+
+/// Build a column view of `values` with type of `field`, values are converted as [rows_to_views].
+pub fn values_to_view<T: Serialize>(
+    values: &[T],
+    field: &Field,
+    precision: Precision,
+) -> Result<ColumnView, RowsError> {
+    let matrix = values
+        .iter()
+        .enumerate()
+        .map(|(row, value)| {
+            serde_json::to_value(value)
+                .map(|value| vec![value])
+                .map_err(|source| RowsError::Serialize { row, source })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    column_from_json(&matrix, 0, field, precision)
+}
+
+/// Binary values could be byte arrays (as `Vec<u8>` is serialized), hex strings like `\\x0A0B`
+/// or plain strings.
+fn json_to_bytes(value: &JsonValue) -> Option<Vec<u8>> {
+    match value {
+        JsonValue::String(s) => match s.strip_prefix("\\x") {
+            Some(hex) if hex.is_ascii() && hex.len() % 2 == 0 => (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+                .collect(),
+            Some(_) => None,
+            None => Some(s.as_bytes().to_vec()),
+        },
+        JsonValue::Array(values) => values
+            .iter()
+            .map(|v| v.as_u64().and_then(|v| u8::try_from(v).ok()))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Decimals could be exact strings (as `Decimal` is displayed) or numbers.
+fn json_to_decimal(value: &JsonValue) -> Option<Decimal> {
+    match value {
+        JsonValue::String(s) => s.parse().ok(),
+        JsonValue::Number(n) => n.to_string().parse().ok().or_else(|| {
+            n.as_f64()
+                .and_then(rust_decimal::Decimal::from_f64)
+                .map(Decimal::from)
+        }),
+        _ => None,
+    }
+}
+
+/// Collect the column `col` of the matrix with conversion `f`, which returns `None` for mismatched types.
+fn collect<V>(
+    matrix: &[Vec<JsonValue>],
+    col: usize,
+    field: &Field,
+    f: impl Fn(&JsonValue) -> Option<V>,
+) -> Result<Vec<Option<V>>, RowsError> {
+    matrix
+        .iter()
+        .enumerate()
+        .map(|(row, values)| match values.get(col) {
+            None | Some(JsonValue::Null) => Ok(None),
+            Some(value) => f(value).map(Some).ok_or_else(|| RowsError::Mismatch {
+                column: field.name().to_string(),
+                ty: field.ty(),
+                row,
+                value: value.clone(),
+            }),
+        })
+        .collect()
+}
+
+fn column_from_json(
+    matrix: &[Vec<JsonValue>],
+    col: usize,
+    field: &Field,
+    precision: Precision,
+) -> Result<ColumnView, RowsError> {
+    macro_rules! _int {
+        ($as:ident, $ty:ty, $from:ident) => {
+            ColumnView::$from(collect(matrix, col, field, |v| {
+                v.$as().and_then(|v| <$ty>::try_from(v).ok())
+            })?)
+        };
+    }
+    let view = match field.ty() {
+        Ty::Bool => ColumnView::from_bools(collect(matrix, col, field, JsonValue::as_bool)?),
+        Ty::TinyInt => _int!(as_i64, i8, from_tiny_ints),
+        Ty::SmallInt => _int!(as_i64, i16, from_small_ints),
+        Ty::Int => _int!(as_i64, i32, from_ints),
+        Ty::BigInt => _int!(as_i64, i64, from_big_ints),
+        Ty::UTinyInt => _int!(as_u64, u8, from_unsigned_tiny_ints),
+        Ty::USmallInt => _int!(as_u64, u16, from_unsigned_small_ints),
+        Ty::UInt => _int!(as_u64, u32, from_unsigned_ints),
+        Ty::UBigInt => _int!(as_u64, u64, from_unsigned_big_ints),
+        Ty::Float => ColumnView::from_floats(collect(matrix, col, field, |v| {
+            v.as_f64().map(|v| v as f32)
+        })?),
+        Ty::Double => ColumnView::from_doubles(collect(matrix, col, field, JsonValue::as_f64)?),
+        Ty::Timestamp => {
+            let values = collect(matrix, col, field, |v| match v {
+                JsonValue::String(s) => {
+                    chrono::DateTime::parse_from_rfc3339(s).ok().and_then(|dt| {
+                        let (scale, subsec) = match precision {
+                            Precision::Millisecond => (1_000, dt.timestamp_subsec_millis()),
+                            Precision::Microsecond => (1_000_000, dt.timestamp_subsec_micros()),
+                            Precision::Nanosecond => (1_000_000_000, dt.timestamp_subsec_nanos()),
+                        };
+                        // i64 nanoseconds only covers years 1677 to 2262.
+                        dt.timestamp()
+                            .checked_mul(scale)
+                            .and_then(|ts| ts.checked_add(subsec as i64))
+                    })
+                }
+                v => v.as_i64(),
+            })?;
+            ColumnView::Timestamp(match precision {
+                Precision::Millisecond => TimestampView::from_millis(values),
+                Precision::Microsecond => TimestampView::from_micros(values),
+                Precision::Nanosecond => TimestampView::from_nanos(values),
+            })
+        }
+        Ty::VarChar => ColumnView::from_varchar(collect(matrix, col, field, |v| {
+            v.as_str().map(ToString::to_string)
+        })?),
+        Ty::NChar => ColumnView::from_nchar(collect(matrix, col, field, |v| {
+            v.as_str().map(ToString::to_string)
+        })?),
+        Ty::Json => {
+            let values = collect(matrix, col, field, |v| match v {
+                JsonValue::String(s) => Some(s.clone()),
+                v => Some(v.to_string()),
+            })?;
+            ColumnView::Json(JsonView::from_iter(values.into_iter()))
+        }
+        Ty::VarBinary => ColumnView::from_var_binary(collect(matrix, col, field, json_to_bytes)?),
+        Ty::Blob => ColumnView::from_blobs(collect(matrix, col, field, json_to_bytes)?),
+        Ty::MediumBlob => ColumnView::MediumBlob(VarBinaryView::from_iter(collect(
+            matrix,
+            col,
+            field,
+            json_to_bytes,
+        )?)),
+        // Without precision of the field, keep the scale of the values.
+        Ty::Decimal | Ty::Decimal64 if decimal_precision_scale(field.bytes()).0 == 0 => {
+            ColumnView::Decimal(DecimalView::from_iter(collect(
+                matrix,
+                col,
+                field,
+                json_to_decimal,
+            )?))
+        }
+        Ty::Decimal | Ty::Decimal64 => {
+            // Decimals are bound as scaled integers of the field scale.
+            let (precision, scale) = decimal_precision_scale(field.bytes());
+            let values = collect(matrix, col, field, |v| {
+                json_to_decimal(v)
+                    .and_then(|v| v.cast(precision, scale))
+                    .map(|v| v.as_raw_i128())
+            })?;
+            ColumnView::Decimal(DecimalView::from_scaled(values, precision, scale))
+        }
+        ty => {
+            return Err(RowsError::Unsupported {
+                column: field.name().to_string(),
+                ty,
+            })
+        }
+    };
+    Ok(view)
+}
+
+#[test]
+fn test_check_params() {
+    let fields = [
+        Field::new("ts", Ty::Timestamp, 8),
+        Field::new("v", Ty::Int, 4),
+    ];
+    let views = [
+        ColumnView::from_millis_timestamp(vec![0]),
+        ColumnView::from_ints(vec![1]),
+    ];
+    assert!(check_views(&views, &fields).is_ok());
+    assert_eq!(
+        check_views(&views[..1], &fields).unwrap_err().to_string(),
+        "expect 2 parameters, but got 1"
+    );
+
+    let views = [
+        ColumnView::from_millis_timestamp(vec![0]),
+        ColumnView::from_big_ints(vec![1]),
+    ];
+    assert_eq!(
+        check_views(&views, &fields).unwrap_err().to_string(),
+        "parameter `v` expects INT, but got BIGINT"
+    );
+
+    let tags = [
+        Field::new("t1", Ty::Int, 4),
+        Field::new("t2", Ty::VarChar, 8),
+    ];
+    assert!(check_values(&[Value::Int(1), Value::Null], &tags).is_ok());
+    assert!(check_values(&[Value::Int(1), Value::Int(2)], &tags).is_err());
+
+    // Decimal tags are rescaled to the field, but views must be of the field type.
+    let tags = [Field::new(
+        "d",
+        Ty::Decimal64,
+        crate::common::views::decimal_bytes(10, 2),
+    )];
+    let value = Value::Decimal(Decimal::new(15, 38, 1));
+    assert!(check_values(&[value.clone()], &tags).is_ok());
+    let views = tags_to_views(&[value], &tags, Precision::Millisecond).unwrap();
+    assert!(check_views(&views, &tags).is_ok());
+    match &views[0] {
+        ColumnView::Decimal(view) => assert_eq!(view.get_string(0).as_deref(), Some("1.50")),
+        view => panic!("unexpected view: {view:?}"),
+    }
+    let value = rust_decimal::Decimal::new(15, 1);
+    let views = [ColumnView::from_decimals(vec![value])];
+    assert!(check_views(&views, &tags).is_err());
+}
+
+#[test]
+fn test_tags_to_views() {
+    use crate::common::Timestamp;
+
+    let fields = [
+        Field::new("t1", Ty::Timestamp, 8),
+        Field::new("t2", Ty::NChar, 16),
+        Field::new("t3", Ty::Int, 4),
+    ];
+    let tags = [
+        Value::Timestamp(Timestamp::new(1654570964022450088, Precision::Nanosecond)),
+        Value::NChar("涛思数据".to_string()),
+        Value::Null,
+    ];
+    let views = tags_to_views(&tags, &fields, Precision::Nanosecond).unwrap();
+    match &views[0] {
+        ColumnView::Timestamp(view) => {
+            assert_eq!(view.precision(), Precision::Nanosecond);
+            assert_eq!(view.as_raw_slice(), &[1654570964022450088]);
+        }
+        view => panic!("unexpected view: {view:?}"),
+    }
+    match &views[1] {
+        ColumnView::NChar(view) => assert_eq!(view.to_vec(), vec![Some("涛思数据")]),
+        view => panic!("unexpected view: {view:?}"),
+    }
+    match &views[2] {
+        ColumnView::Int(view) => assert_eq!(view.to_vec(), vec![None]),
+        view => panic!("unexpected view: {view:?}"),
+    }
+}
+
+#[test]
+fn test_tags_to_views_precision() {
+    use crate::common::Timestamp;
+
+    let fields = [
+        Field::new("t1", Ty::Timestamp, 8),
+        Field::new("t2", Ty::Timestamp, 8),
+    ];
+    // Millisecond tags on a nanosecond table.
+    let tags = [
+        Value::Timestamp(Timestamp::new(1654570964022, Precision::Millisecond)),
+        Value::Null,
+    ];
+    let views = tags_to_views(&tags, &fields, Precision::Nanosecond).unwrap();
+    for view in &views {
+        match view {
+            ColumnView::Timestamp(view) => assert_eq!(view.precision(), Precision::Nanosecond),
+            view => panic!("unexpected view: {view:?}"),
+        }
+    }
+    match &views[0] {
+        ColumnView::Timestamp(view) => {
+            assert_eq!(view.as_raw_slice(), &[1654570964022000000])
+        }
+        view => panic!("unexpected view: {view:?}"),
+    }
+
+    let tags = [
+        Value::Timestamp(Timestamp::new(1654570964022450088, Precision::Nanosecond)),
+        Value::Timestamp(Timestamp::new(i64::MAX, Precision::Millisecond)),
+    ];
+    let views = tags_to_views(&tags[..1], &fields[..1], Precision::Microsecond).unwrap();
+    match &views[0] {
+        ColumnView::Timestamp(view) => assert_eq!(view.as_raw_slice(), &[1654570964022450]),
+        view => panic!("unexpected view: {view:?}"),
+    }
+    assert!(tags_to_views(&tags, &fields, Precision::Nanosecond).is_err());
+}
+
+#[test]
+fn test_rows_to_views() {
+    #[derive(Serialize)]
+    struct Row<'a> {
+        ts: &'a str,
+        name: Option<&'a str>,
+        v: i64,
+    }
+    let fields = [
+        Field::new("ts", Ty::Timestamp, 8),
+        Field::new("v", Ty::SmallInt, 2),
+        Field::new("name", Ty::VarChar, 10),
+    ];
+    let rows = [
+        Row {
+            ts: "2022-06-07T11:02:44.022+08:00",
+            name: Some("a"),
+            v: 1,
+        },
+        Row {
+            ts: "2022-06-07T11:02:45.022+08:00",
+            name: None,
+            v: 2,
+        },
+    ];
+    let views = rows_to_views(&rows, &fields, Precision::Millisecond).unwrap();
+    assert_eq!(views.len(), 3);
+    match &views[0] {
+        ColumnView::Timestamp(view) => {
+            assert_eq!(view.as_raw_slice(), &[1654570964022, 1654570965022])
+        }
+        view => panic!("unexpected view: {view:?}"),
+    }
+    assert!(matches!(&views[1], ColumnView::SmallInt(_)));
+    match &views[2] {
+        ColumnView::VarChar(view) => assert_eq!(view.to_vec(), vec![Some("a".to_string()), None]),
+        view => panic!("unexpected view: {view:?}"),
+    }
+
+    // tuple rows are matched by position.
+    let views = rows_to_views(&[(0i64, 1u8, "b")], &fields, Precision::Millisecond).unwrap();
+    assert_eq!(views[2].len(), 1);
+
+    let err = rows_to_views(&[(0i64, 100000, "c")], &fields, Precision::Millisecond).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "column `v` expects SMALLINT, but got 100000 in row 0"
+    );
+
+    // misspelled keys and tuples of other lengths are rejected.
+    let err = rows_to_views(
+        &[serde_json::json!({"ts": 0, "v": 1, "nmae": "a"})],
+        &fields,
+        Precision::Millisecond,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "row 0 has unknown field `nmae`");
+    let err = rows_to_views(&[(0i64, 1u8)], &fields, Precision::Millisecond).unwrap_err();
+    assert_eq!(err.to_string(), "row 0 expects 3 values, but got 2");
+    let err = rows_to_views(&[(0i64, 1u8, "b", 1)], &fields, Precision::Millisecond).unwrap_err();
+    assert_eq!(err.to_string(), "row 0 expects 3 values, but got 4");
+}
+
+#[test]
+fn test_timestamp_rows_to_views() {
+    let fields = [Field::new("ts", Ty::Timestamp, 8)];
+    let rows = [["2022-06-07T11:02:44.022450088+08:00"]];
+    for (precision, ts) in [
+        (Precision::Millisecond, 1654570964022),
+        (Precision::Microsecond, 1654570964022450),
+        (Precision::Nanosecond, 1654570964022450088),
+    ] {
+        let views = rows_to_views(&rows, &fields, precision).unwrap();
+        match &views[0] {
+            ColumnView::Timestamp(view) => assert_eq!(view.as_raw_slice(), &[ts]),
+            view => panic!("unexpected view: {view:?}"),
+        }
+    }
+
+    // out of the range of i64 nanoseconds.
+    let rows = [["2300-01-01T00:00:00Z"]];
+    assert!(rows_to_views(&rows, &fields, Precision::Microsecond).is_ok());
+    let err = rows_to_views(&rows, &fields, Precision::Nanosecond).unwrap_err();
+    assert!(matches!(err, RowsError::Mismatch { row: 0, .. }), "{err}");
+    let rows = [["1600-01-01T00:00:00Z"]];
+    assert!(rows_to_views(&rows, &fields, Precision::Nanosecond).is_err());
+}
+
+#[test]
+fn test_binary_rows_to_views() {
+    use crate::common::views::decimal_bytes;
+
+    let fields = [
+        Field::new("b", Ty::VarBinary, 8),
+        Field::new("d", Ty::Decimal64, decimal_bytes(10, 2)),
+    ];
+    let rows = [
+        serde_json::json!([[0, 1, 255], "123.45"]),
+        serde_json::json!(["\\x00FF", 1.5]),
+        serde_json::json!(["abc", null]),
+    ];
+    let views = rows_to_views(&rows, &fields, Precision::Millisecond).unwrap();
+    match &views[0] {
+        ColumnView::VarBinary(view) => assert_eq!(
+            view.to_vec(),
+            vec![
+                Some(vec![0, 1, 255]),
+                Some(vec![0, 255]),
+                Some(b"abc".to_vec())
+            ]
+        ),
+        view => panic!("unexpected view: {view:?}"),
+    }
+    match &views[1] {
+        ColumnView::Decimal(view) => {
+            assert_eq!((view.precision(), view.scale()), (10, 2));
+            assert_eq!(view.ty(), Ty::Decimal64);
+            assert_eq!(
+                view.to_string_vec(),
+                vec![Some("123.45".to_string()), Some("1.50".to_string()), None]
+            );
+            assert_eq!(view.get_scaled(1), Some(150));
+        }
+        view => panic!("unexpected view: {view:?}"),
+    }
+
+    // beyond the precision of the field.
+    let rows = [serde_json::json!([null, "123456789.5"])];
+    let err = rows_to_views(&rows, &fields, Precision::Millisecond).unwrap_err();
+    assert!(matches!(err, RowsError::Mismatch { row: 0, .. }), "{err}");
+
+    let rows = [serde_json::json!(["\\xZZ", null])];
+    assert!(rows_to_views(&rows, &fields, Precision::Millisecond).is_err());
+}
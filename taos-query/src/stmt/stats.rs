@@ -0,0 +1,46 @@
+/// Client side binding statistics of a table in a stmt execution.
+///
+/// The server only reports affected rows of the whole execution, so these are counted
+/// while binding: rows bound are not necessarily the rows inserted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StmtTableBindStats {
+    /// Table name set by `set_tbname`, empty if the table is not a placeholder.
+    pub table: String,
+    /// Batches added.
+    pub batches: usize,
+    /// Rows bound in added batches.
+    pub bound_rows: usize,
+    /// The first binding error of the table, its batches after the error are not added.
+    pub error: Option<String>,
+}
+
+impl StmtTableBindStats {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Binding statistics of a stmt execution, see [super::Bindable::execute_bind_stats].
+///
+/// Tables failed in binding are skipped while others are executed. An execution error is
+/// not attributed to tables, as the server reports one error for the whole execution.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StmtBindStats {
+    /// Rows affected by the execution, as reported by the server.
+    pub affected_rows: usize,
+    /// Tables in the order of first binding.
+    pub tables: Vec<StmtTableBindStats>,
+}
+
+impl StmtBindStats {
+    /// Tables failed in binding.
+    pub fn failed_tables(&self) -> impl Iterator<Item = &StmtTableBindStats> {
+        self.tables.iter().filter(|table| !table.is_ok())
+    }
+
+    /// Check if some tables are failed in binding while others are bound.
+    pub fn is_partial(&self) -> bool {
+        let failed = self.failed_tables().count();
+        failed > 0 && failed < self.tables.len()
+    }
+}
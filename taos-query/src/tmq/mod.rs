@@ -2,6 +2,7 @@ use std::{fmt::Debug, pin::Pin, str::FromStr, time::Duration};
 
 use futures::Stream;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     common::{JsonMeta, RawData, RawMeta},
@@ -151,6 +152,87 @@ pub trait IsAsyncData {
 
 pub type VGroupId = i32;
 
+/// A vgroup of a topic, it's the minimal unit to be assigned to a consumer, like the kafka topic partition.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TopicVGroup {
+    pub topic: String,
+    pub vgroup_id: VGroupId,
+}
+
+impl TopicVGroup {
+    pub fn new(topic: impl Into<String>, vgroup_id: VGroupId) -> Self {
+        Self {
+            topic: topic.into(),
+            vgroup_id,
+        }
+    }
+}
+
+/// Vgroup assignment of a topic for current consumer, with the offset range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Assignment {
+    vgroup_id: VGroupId,
+    #[serde(rename = "offset")]
+    current_offset: i64,
+    begin: i64,
+    end: i64,
+}
+
+impl Assignment {
+    pub const fn new(vgroup_id: VGroupId, current_offset: i64, begin: i64, end: i64) -> Self {
+        Self {
+            vgroup_id,
+            current_offset,
+            begin,
+            end,
+        }
+    }
+
+    /// VGroup id of the assignment.
+    pub const fn vgroup_id(&self) -> VGroupId {
+        self.vgroup_id
+    }
+
+    /// Current consumed offset in the vgroup.
+    pub const fn current_offset(&self) -> i64 {
+        self.current_offset
+    }
+
+    /// The first offset in the vgroup.
+    pub const fn begin(&self) -> i64 {
+        self.begin
+    }
+
+    /// The last offset in the vgroup.
+    pub const fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Messages not consumed yet in the vgroup.
+    pub const fn lag(&self) -> i64 {
+        if self.end > self.current_offset {
+            self.end - self.current_offset
+        } else {
+            0
+        }
+    }
+}
+
+/// Callbacks for vgroup assignment changes, similar to kafka's rebalance listener.
+///
+/// Consumers call the listener in `recv_timeout` before the next message is returned, when
+/// polling finds the assignment changed. The change has already taken effect by then, so
+/// revoked vgroups may be owned by another consumer and committing their offsets could fail.
+/// Commit offsets as messages are processed, and use `on_revoked` to drop local state only.
+#[async_trait::async_trait]
+pub trait RebalanceListener<C: Sync>: Send + Sync {
+    /// Vgroups that will not be consumed by `consumer` any more.
+    async fn on_revoked(&self, _consumer: &C, _vgroups: &[TopicVGroup]) {}
+
+    /// Vgroups that are newly assigned to `consumer`.
+    async fn on_assigned(&self, _consumer: &C, _vgroups: &[TopicVGroup]) {}
+}
+
 /// Extract offset information.
 pub trait IsOffset {
     /// Database name for current message
@@ -217,8 +299,15 @@ pub trait AsConsumer: Sized {
 
     fn commit(&self, offset: Self::Offset) -> Result<(), Self::Error>;
 
-    fn unsubscribe(self) {
-        drop(self)
+    /// Vgroup assignments for each subscribed topic, `None` if not supported.
+    fn assignments(&self) -> Option<Vec<(String, Vec<Assignment>)>> {
+        None
+    }
+
+    /// Unsubscribe all topics and close the consumer.
+    fn unsubscribe(self) -> Result<(), Self::Error> {
+        drop(self);
+        Ok(())
     }
 }
 
@@ -292,8 +381,15 @@ pub trait AsAsyncConsumer: Sized + Send + Sync {
 
     async fn commit(&self, offset: Self::Offset) -> Result<(), Self::Error>;
 
-    async fn unsubscribe(self) {
-        drop(self)
+    /// Vgroup assignments for each subscribed topic, `None` if not supported.
+    async fn assignments(&self) -> Option<Vec<(String, Vec<Assignment>)>> {
+        None
+    }
+
+    /// Unsubscribe all topics and close the consumer.
+    async fn unsubscribe(self) -> Result<(), Self::Error> {
+        drop(self);
+        Ok(())
     }
 }
 
@@ -332,6 +428,14 @@ where
     fn commit(&self, offset: Self::Offset) -> Result<(), Self::Error> {
         crate::block_in_place_or_global(<C as AsAsyncConsumer>::commit(self, offset))
     }
+
+    fn assignments(&self) -> Option<Vec<(String, Vec<Assignment>)>> {
+        crate::block_in_place_or_global(<C as AsAsyncConsumer>::assignments(self))
+    }
+
+    fn unsubscribe(self) -> Result<(), Self::Error> {
+        crate::block_in_place_or_global(<C as AsAsyncConsumer>::unsubscribe(self))
+    }
 }
 
 // #[async_trait::async_trait]
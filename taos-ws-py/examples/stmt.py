@@ -0,0 +1,25 @@
+import taosws
+
+conn = taosws.connect("ws://localhost:6041")
+conn.execute("drop database if exists py_stmt")
+conn.execute("create database py_stmt")
+conn.execute("create table py_stmt.stb (ts timestamp, v int) tags(t1 int)")
+
+stmt = conn.statement()
+stmt.prepare("insert into ? using py_stmt.stb tags(?) values(?, ?)")
+for name, tags, rows in [
+    ("py_stmt.tb1", [1], [(1654570964022, 1), (1654570964023, 2)]),
+    ("py_stmt.tb2", ["not an int"], [(1654570964022, 3)]),
+]:
+    stmt.set_tbname(name)
+    try:
+        stmt.set_tags(tags)
+        stmt.bind_rows(rows)
+        stmt.add_batch()
+    except taosws.StatementError as err:
+        print("skip table", name, err)
+
+affected_rows, tables = stmt.execute_bind_stats()
+print("affected rows:", affected_rows)
+for (name, batches, rows, error) in tables:
+    print(name, batches, rows, error)
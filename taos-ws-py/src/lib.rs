@@ -1,14 +1,18 @@
-use pyo3::types::{PyDict, PyTuple};
+use pyo3::types::{PyBool, PyBytes, PyDict, PyList, PyTuple};
 use pyo3::PyIterProtocol;
 use pyo3::{create_exception, exceptions::PyException};
 use pyo3::{prelude::*, PyObjectProtocol};
+use serde_json::Value as JsonValue;
 use taos_query::prelude::sync::*;
-use taos_query::{common::RawBlock as Block, prelude::BorrowedValue, Fetchable};
-use taos_ws::{ResultSet, Taos, TaosBuilder};
+use taos_query::{
+    block_in_place_or_global, common::RawBlock as Block, prelude::BorrowedValue, Fetchable,
+};
+use taos_ws::{ResultSet, Stmt, StmtBindStats, Taos, TaosBuilder};
 
 create_exception!(taosws, ConnectionError, PyException);
 create_exception!(taosws, QueryError, PyException);
 create_exception!(taosws, FetchError, PyException);
+create_exception!(taosws, StatementError, PyException);
 
 #[pyclass]
 struct TaosConnection {
@@ -27,6 +31,11 @@ struct TaosCursor {
     _result: Option<ResultSet>,
 }
 
+#[pyclass]
+struct TaosStmt {
+    _inner: Stmt,
+}
+
 #[pyclass]
 struct TaosField {
     _inner: Field,
@@ -145,6 +154,135 @@ impl TaosConnection {
             _result: None,
         })
     }
+
+    fn statement(&self) -> PyResult<TaosStmt> {
+        let taos = self
+            .builder
+            .build()
+            .map_err(|err| ConnectionError::new_err(err.to_string()))?;
+        Ok(TaosStmt {
+            _inner: Stmt::init(&taos).map_err(|err| StatementError::new_err(err.to_string()))?,
+        })
+    }
+}
+
+#[pymethods]
+impl TaosStmt {
+    fn prepare(&mut self, sql: &str) -> PyResult<()> {
+        self._inner
+            .prepare(sql)
+            .map_err(|err| StatementError::new_err(err.to_string()))?;
+        Ok(())
+    }
+
+    fn set_tbname(&mut self, name: &str) -> PyResult<()> {
+        self._inner
+            .set_tbname(name)
+            .map_err(|err| StatementError::new_err(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Set tags with a list of values in the order of tag placeholders.
+    fn set_tags(&mut self, tags: &PyAny) -> PyResult<()> {
+        let tags = py_to_json(tags)?;
+        block_in_place_or_global(self._inner.set_tags_row(&tags))
+            .map_err(|err| StatementError::new_err(err.to_string()))
+    }
+
+    /// Bind a list of rows, each row is a tuple or list in the order of column placeholders.
+    fn bind_rows(&mut self, rows: &PyList) -> PyResult<()> {
+        let rows = rows.iter().map(py_to_json).collect::<PyResult<Vec<_>>>()?;
+        block_in_place_or_global(self._inner.bind_rows(&rows))
+            .map_err(|err| StatementError::new_err(err.to_string()))
+    }
+
+    fn add_batch(&mut self) -> PyResult<()> {
+        self._inner
+            .add_batch()
+            .map_err(|err| StatementError::new_err(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Execute the batches, tables failed in binding are skipped, see `bind_tables`.
+    fn execute(&mut self) -> PyResult<usize> {
+        self._inner
+            .execute()
+            .map_err(|err| StatementError::new_err(err.to_string()))
+    }
+
+    /// Execute the batches, returns `(affected_rows, tables)` with tables as `bind_tables`.
+    ///
+    /// An execution error is raised for the whole batch, the server does not tell which
+    /// tables are failed.
+    fn execute_bind_stats(
+        &mut self,
+    ) -> PyResult<(usize, Vec<(String, usize, usize, Option<String>)>)> {
+        let stats = self
+            ._inner
+            .execute_bind_stats()
+            .map_err(|err| StatementError::new_err(err.to_string()))?;
+        Ok((stats.affected_rows, bind_tables(&stats)))
+    }
+
+    #[getter]
+    fn affected_rows(&self) -> usize {
+        self._inner.affected_rows()
+    }
+
+    /// Tables bound in last execution, as tuples of `(name, batches, bound_rows, bind_error)`.
+    ///
+    /// They are counted while binding, the server only reports `affected_rows` of the execution.
+    fn bind_tables(&self) -> Vec<(String, usize, usize, Option<String>)> {
+        bind_tables(self._inner.bind_stats())
+    }
+}
+
+fn bind_tables(stats: &StmtBindStats) -> Vec<(String, usize, usize, Option<String>)> {
+    stats
+        .tables
+        .iter()
+        .map(|table| {
+            (
+                table.table.clone(),
+                table.batches,
+                table.bound_rows,
+                table.error.clone(),
+            )
+        })
+        .collect()
+}
+
+fn py_to_json(value: &PyAny) -> PyResult<JsonValue> {
+    if value.is_none() {
+        Ok(JsonValue::Null)
+    } else if let Ok(v) = value.downcast::<PyBool>() {
+        Ok(JsonValue::Bool(v.is_true()))
+    } else if let Ok(v) = value.extract::<i64>() {
+        Ok(v.into())
+    } else if let Ok(v) = value.extract::<u64>() {
+        Ok(v.into())
+    } else if let Ok(v) = value.extract::<f64>() {
+        Ok(v.into())
+    } else if let Ok(v) = value.extract::<String>() {
+        Ok(v.into())
+    } else if let Ok(v) = value.downcast::<PyBytes>() {
+        Ok(v.as_bytes().into())
+    } else if let Ok(v) = value.downcast::<PyList>() {
+        v.iter()
+            .map(py_to_json)
+            .collect::<PyResult<_>>()
+            .map(JsonValue::Array)
+    } else if let Ok(v) = value.downcast::<PyTuple>() {
+        v.iter()
+            .map(py_to_json)
+            .collect::<PyResult<_>>()
+            .map(JsonValue::Array)
+    } else {
+        Err(StatementError::new_err(format!(
+            "unsupported value: {}",
+            value
+        )))
+    }
 }
 
 #[pymethods]
@@ -247,7 +385,10 @@ fn convert_raw_block_to_python_tuple(ret: &mut Vec<PyObject>, block: &Option<Blo
                         BorrowedValue::VarChar(s) => s.into_py(py),
                         BorrowedValue::NChar(v) => v.as_ref().into_py(py),
                         BorrowedValue::Json(j) => std::str::from_utf8(&j).unwrap().into_py(py),
-                        _ => Option::<()>::None.into_py(py),
+                        BorrowedValue::VarBinary(v)
+                        | BorrowedValue::Blob(v)
+                        | BorrowedValue::MediumBlob(v) => PyBytes::new(py, v).into_py(py),
+                        BorrowedValue::Decimal(v) => v.to_string().into_py(py),
                     };
                     vec.push(value);
                 }
@@ -294,7 +435,10 @@ impl PyIterProtocol for TaosResult {
                         BorrowedValue::VarChar(s) => s.into_py(py),
                         BorrowedValue::NChar(v) => v.as_ref().into_py(py),
                         BorrowedValue::Json(j) => std::str::from_utf8(&j).unwrap().into_py(py),
-                        _ => Option::<()>::None.into_py(py),
+                        BorrowedValue::VarBinary(v)
+                        | BorrowedValue::Blob(v)
+                        | BorrowedValue::MediumBlob(v) => PyBytes::new(py, v).into_py(py),
+                        BorrowedValue::Decimal(v) => v.to_string().into_py(py),
                     };
                     vec.push(value);
                 }
@@ -338,10 +482,12 @@ fn connect(dsn: &str) -> PyResult<TaosConnection> {
 fn taosws(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<TaosConnection>()?;
     m.add_class::<TaosCursor>()?;
+    m.add_class::<TaosStmt>()?;
     m.add_function(wrap_pyfunction!(connect, m)?)?;
     m.add("ConnectionError", py.get_type::<ConnectionError>())?;
     m.add("QueryError", py.get_type::<QueryError>())?;
     m.add("FetchError", py.get_type::<FetchError>())?;
+    m.add("StatementError", py.get_type::<StatementError>())?;
     m.add("apilevel", 1)?;
     m.add("threadsafety", 1)?;
     m.add("paramstyle", "pyformat")?;
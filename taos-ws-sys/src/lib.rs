@@ -10,8 +10,8 @@ use std::{
 use taos_error::Code;
 
 use taos_query::{
+    common::{ColumnView, Precision, Ty},
     common::{Field, RawBlock as Block, Timestamp},
-    common::{Precision, Ty},
     Fetchable,
 };
 use taos_ws::sync::*;
@@ -680,6 +680,11 @@ pub unsafe extern "C" fn ws_result_precision(rs: *const WS_RES) -> i32 {
 ///
 /// For type which is var-data (varchar/nchar/json), the `*len` is the bytes length, others is fixed size of that type.
 ///
+/// DECIMAL value is a little-endian two's complement integer `v` meaning `v / 10^scale`, 16 bytes
+/// for type DECIMAL (17) and 8 bytes for DECIMAL64 (21). The field `bytes` of [WS_FIELD] packs
+/// the byte width in the low byte, the precision in the third byte and the scale in the high byte.
+/// Use [ws_get_decimal_in_block] to get it as a string.
+///
 /// ## Example
 ///
 /// ```c
@@ -714,6 +719,43 @@ pub unsafe extern "C" fn ws_get_value_in_block(
     }
 }
 
+/// Write the DECIMAL value at `(row, col)` of current block into `dest` as a null-terminated
+/// string, like `123.45`.
+///
+/// Returns the string length, `0` if the value is NULL, or `-1` if it's not a DECIMAL value or
+/// `len` is not enough for the string and the terminating null.
+#[no_mangle]
+pub unsafe extern "C" fn ws_get_decimal_in_block(
+    rs: *mut WS_RES,
+    row: i32,
+    col: i32,
+    dest: *mut c_char,
+    len: u32,
+) -> i32 {
+    let Some(rs) = (rs as *mut WsMaybeError<WsResultSet>).as_mut() else {
+        return -1;
+    };
+    let view = rs
+        .block
+        .as_ref()
+        .and_then(|block| block.columns().nth(col as usize));
+    let value = match view {
+        Some(ColumnView::Decimal(view)) if (row as usize) < view.len() => {
+            view.get_string(row as usize)
+        }
+        _ => return -1,
+    };
+    match value {
+        Some(value) if value.len() < len as usize => {
+            std::ptr::copy_nonoverlapping(value.as_ptr(), dest as *mut u8, value.len());
+            *dest.add(value.len()) = 0;
+            value.len() as _
+        }
+        Some(_) => -1,
+        None => 0,
+    }
+}
+
 /// Convert timestamp to C string.
 ///
 /// This function use a thread-local variable to print, it may works in most cases but not always be thread-safe,
@@ -926,10 +968,16 @@ mod tests {
                             ))
                             .unwrap()
                         ),
-                        Ty::VarBinary => todo!(),
-                        Ty::Decimal => todo!(),
-                        Ty::Blob => todo!(),
-                        Ty::MediumBlob => todo!(),
+                        Ty::VarBinary | Ty::Blob | Ty::MediumBlob => println!(
+                            "{:x?}",
+                            std::slice::from_raw_parts(v as *const u8, len as usize)
+                        ),
+                        Ty::Decimal | Ty::Decimal64 => {
+                            let mut buf = [0 as c_char; 64];
+                            let len = ws_get_decimal_in_block(rs, row, col, buf.as_mut_ptr(), 64);
+                            assert!(len > 0);
+                            println!("{}", CStr::from_ptr(buf.as_ptr()).to_str().unwrap())
+                        }
                         _ => todo!(),
                     }
                 }
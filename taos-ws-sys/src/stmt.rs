@@ -3,8 +3,7 @@ use std::fmt::Debug;
 use std::os::raw::*;
 
 use taos_query::block_in_place_or_global;
-use taos_query::common::Value;
-use taos_query::prelude::Itertools;
+use taos_query::common::{Decimal, Value};
 use taos_query::stmt::Bindable;
 use taos_ws::Stmt;
 
@@ -91,10 +90,18 @@ pub unsafe extern "C" fn ws_stmt_set_tbname_tags(
     match (stmt as *mut WsMaybeError<Stmt>).as_mut() {
         Some(stmt) => {
             let name = CStr::from_ptr(name).to_str().unwrap();
-            let tags = std::slice::from_raw_parts(bind, len as usize)
+            let tags = match std::slice::from_raw_parts(bind, len as usize)
                 .iter()
                 .map(|bind| bind.to_tag_value())
-                .collect_vec();
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(tags) => tags,
+                Err(e) => {
+                    let errno = e.code;
+                    stmt.error = Some(e);
+                    return errno.into();
+                }
+            };
 
             if let Err(e) = stmt.set_tbname_tags(name, &tags) {
                 let errno = e.errno();
@@ -108,12 +115,16 @@ pub unsafe extern "C" fn ws_stmt_set_tbname_tags(
     }
 }
 
-/// Currently only insert sql is supported.
+/// Check if the prepared sql is an insert statement.
 #[no_mangle]
 pub unsafe extern "C" fn ws_stmt_is_insert(stmt: *mut WS_STMT, insert: *mut c_int) -> c_int {
-    let _ = stmt;
-    *insert = 1;
-    0
+    match (stmt as *mut WsMaybeError<Stmt>).as_mut() {
+        Some(stmt) => {
+            *insert = stmt.is_insert() as c_int;
+            0
+        }
+        _ => Code::Failed.into(),
+    }
 }
 
 #[derive(Debug)]
@@ -135,6 +146,15 @@ pub struct TaosMultiBind {
 #[allow(non_camel_case_types)]
 pub type WS_MULTI_BIND = TaosMultiBind;
 
+/// Parse a decimal bound as a string.
+fn parse_decimal(bytes: &[u8]) -> Result<Decimal, WsError> {
+    let value = std::str::from_utf8(bytes)
+        .map_err(|_| WsError::new(Code::Failed, "decimal should be bound as an utf-8 string"))?;
+    value
+        .parse()
+        .map_err(|e| WsError::new(Code::Failed, &format!("invalid decimal {value:?}: {e}")))
+}
+
 impl TaosMultiBind {
     pub fn new(ty: Ty) -> Self {
         Self {
@@ -150,11 +170,12 @@ impl TaosMultiBind {
     pub fn first_to_json(&self) -> serde_json::Value {
         self.to_json().as_array().unwrap().first().unwrap().clone()
     }
-    pub fn to_tag_value(&self) -> Value {
+    /// Tag value of the first row, decimals should be bound as strings.
+    pub fn to_tag_value(&self) -> Result<Value, WsError> {
         if !self.is_null.is_null() && unsafe { self.is_null.read() != 0 } {
-            return Value::Null;
+            return Ok(Value::Null);
         }
-        match Ty::from(self.buffer_type) {
+        Ok(match Ty::from(self.buffer_type) {
             Ty::Null => unsafe { Value::Null },
             Ty::Bool => unsafe { Value::Bool(*(self.buffer as *const bool)) },
             Ty::TinyInt => unsafe { Value::TinyInt(*(self.buffer as *const i8)) },
@@ -194,8 +215,47 @@ impl TaosMultiBind {
                 // let v = std::str::from_utf8_unchecked(slice);
                 Value::Json(serde_json::from_slice(slice).unwrap())
             },
+            Ty::VarBinary | Ty::Blob | Ty::MediumBlob => unsafe {
+                assert!(!self.length.is_null());
+                assert!(!self.buffer.is_null());
+                let slice: &[u8] =
+                    std::slice::from_raw_parts(self.buffer as _, self.length.read() as usize);
+                match Ty::from(self.buffer_type) {
+                    Ty::Blob => Value::Blob(slice.to_vec()),
+                    Ty::MediumBlob => Value::MediumBlob(slice.to_vec()),
+                    _ => Value::VarBinary(slice.to_vec()),
+                }
+            },
+            Ty::Decimal | Ty::Decimal64 => unsafe {
+                assert!(!self.length.is_null());
+                assert!(!self.buffer.is_null());
+                let slice =
+                    std::slice::from_raw_parts(self.buffer as _, self.length.read() as usize);
+                Value::Decimal(parse_decimal(slice)?)
+            },
             _ => todo!(),
+        })
+    }
+
+    /// Check that non-null decimals are bound as valid decimal strings.
+    pub fn check_decimals(&self) -> Result<(), WsError> {
+        if !matches!(Ty::from(self.buffer_type), Ty::Decimal | Ty::Decimal64)
+            || self.buffer.is_null()
+        {
+            return Ok(());
+        }
+        for i in 0..self.num as usize {
+            unsafe {
+                if !self.is_null.is_null() && *(self.is_null as *const bool).add(i) {
+                    continue;
+                }
+                let ptr =
+                    (self.buffer as *const u8).offset(self.buffer_length as isize * i as isize);
+                let len = *self.length.offset(i as isize) as usize;
+                parse_decimal(std::slice::from_raw_parts(ptr, len))?;
+            }
         }
+        Ok(())
     }
 
     pub fn to_json(&self) -> serde_json::Value {
@@ -239,6 +299,28 @@ impl TaosMultiBind {
             }};
         }
 
+        macro_rules! _impl_var {
+            ($f:expr) => {{
+                let f = $f;
+                let value = |i: usize| {
+                    let ptr =
+                        (self.buffer as *const u8).offset(self.buffer_length as isize * i as isize);
+                    let len = *self.length.offset(i as isize) as usize;
+                    f(std::slice::from_raw_parts(ptr, len))
+                };
+                if self.is_null.is_null() {
+                    json!((0..len).map(value).collect::<Vec<_>>())
+                } else {
+                    let nulls = std::slice::from_raw_parts(self.is_null as *const bool, len);
+                    let column = (0..len)
+                        .zip(nulls)
+                        .map(|(i, is_null)| if *is_null { None } else { Some(value(i)) })
+                        .collect::<Vec<_>>();
+                    json!(column)
+                }
+            }};
+        }
+
         unsafe {
             match Ty::from(self.buffer_type) {
                 Ty::Null => _nulls!(),
@@ -349,10 +431,14 @@ impl TaosMultiBind {
                         .collect::<Vec<_>>();
                     json!(column)
                 }
-                Ty::VarBinary => todo!(),
-                Ty::Decimal => todo!(),
-                Ty::Blob => todo!(),
-                Ty::MediumBlob => todo!(),
+                Ty::VarBinary | Ty::Blob | Ty::MediumBlob => {
+                    _impl_var!(|bytes: &[u8]| bytes.to_vec())
+                }
+                // Decimals are bound as strings to keep the precision, see
+                // [TaosMultiBind::check_decimals].
+                Ty::Decimal | Ty::Decimal64 => {
+                    _impl_var!(|bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned())
+                }
                 _ => todo!(),
             }
         }
@@ -478,10 +564,18 @@ pub unsafe extern "C" fn ws_stmt_set_tags(
 ) -> c_int {
     match (stmt as *mut WsMaybeError<Stmt>).as_mut() {
         Some(stmt) => {
-            let columns = std::slice::from_raw_parts(bind, len as usize)
+            let columns = match std::slice::from_raw_parts(bind, len as usize)
                 .iter()
                 .map(|bind| bind.to_tag_value())
-                .collect_vec();
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(columns) => columns,
+                Err(e) => {
+                    let errno = e.code;
+                    stmt.error = Some(e);
+                    return errno.into();
+                }
+            };
 
             if let Err(e) = stmt.set_tags(&columns) {
                 let errno = e.errno();
@@ -503,10 +597,13 @@ pub unsafe extern "C" fn ws_stmt_bind_param_batch(
 ) -> c_int {
     match (stmt as *mut WsMaybeError<Stmt>).as_mut() {
         Some(stmt) => {
-            let columns = std::slice::from_raw_parts(bind, len as usize)
-                .iter()
-                .map(|bind| bind.to_json())
-                .collect();
+            let binds = std::slice::from_raw_parts(bind, len as usize);
+            if let Err(e) = binds.iter().try_for_each(TaosMultiBind::check_decimals) {
+                let errno = e.code;
+                stmt.error = Some(e);
+                return errno.into();
+            }
+            let columns = binds.iter().map(|bind| bind.to_json()).collect();
 
             if let Err(e) = block_in_place_or_global(stmt.stmt_bind(columns)) {
                 let errno = e.errno();
@@ -555,6 +652,34 @@ pub unsafe extern "C" fn ws_stmt_execute(stmt: *mut WS_STMT, affected_rows: *mut
     }
 }
 
+/// Execute the bind batch like `ws_stmt_execute`, and get number of tables failed in binding
+/// in `failed_tables` pointer, which are skipped in the execution.
+///
+/// Details of each table are got by `ws_stmt_bind_table_*` functions. An execution error is
+/// returned for the whole batch, as the server does not tell which tables are failed.
+#[no_mangle]
+pub unsafe extern "C" fn ws_stmt_execute_bind_stats(
+    stmt: *mut WS_STMT,
+    affected_rows: *mut i32,
+    failed_tables: *mut i32,
+) -> c_int {
+    match (stmt as *mut WsMaybeError<Stmt>).as_mut() {
+        Some(stmt) => match stmt.execute_bind_stats() {
+            Ok(stats) => {
+                *affected_rows = stats.affected_rows as _;
+                *failed_tables = stats.failed_tables().count() as _;
+                0
+            }
+            Err(e) => {
+                let errno = e.errno();
+                stmt.error = Some(WsError::new(errno, &e.to_string()));
+                errno.into()
+            }
+        },
+        _ => 0,
+    }
+}
+
 /// Get inserted rows in current statement.
 #[no_mangle]
 pub unsafe extern "C" fn ws_stmt_affected_rows(stmt: *mut WS_STMT) -> c_int {
@@ -564,6 +689,98 @@ pub unsafe extern "C" fn ws_stmt_affected_rows(stmt: *mut WS_STMT) -> c_int {
     }
 }
 
+/// Copy `s` into `buf` of `len` bytes with a trailing nul, returns the length of `s`.
+///
+/// If the returned length is not less than `len`, the copy is truncated at a char boundary,
+/// call again with a buffer of the length plus one.
+unsafe fn copy_to_buf(s: &str, buf: *mut c_char, len: c_int) -> c_int {
+    if !buf.is_null() && len > 0 {
+        let mut n = s.len().min(len as usize - 1);
+        while !s.is_char_boundary(n) {
+            n -= 1;
+        }
+        std::ptr::copy_nonoverlapping(s.as_ptr(), buf as *mut u8, n);
+        *buf.add(n) = 0;
+    }
+    s.len() as _
+}
+
+/// Get number of tables bound in last execution.
+///
+/// Per-table binding stats are counted on the client, they are kept until next execution,
+/// even if the execution failed.
+#[no_mangle]
+pub unsafe extern "C" fn ws_stmt_bind_tables(stmt: *mut WS_STMT) -> c_int {
+    match (stmt as *mut WsMaybeError<Stmt>).as_mut() {
+        Some(stmt) => stmt.bind_stats().tables.len() as _,
+        _ => 0,
+    }
+}
+
+/// Copy name of the `index`-th table in last execution into `buf`, returns the length of name.
+///
+/// The name is truncated if the length is not less than `len`. Returns -1 if `index` is out
+/// of range.
+#[no_mangle]
+pub unsafe extern "C" fn ws_stmt_bind_table_name(
+    stmt: *mut WS_STMT,
+    index: c_int,
+    buf: *mut c_char,
+    len: c_int,
+) -> c_int {
+    match (stmt as *mut WsMaybeError<Stmt>)
+        .as_mut()
+        .and_then(|stmt| stmt.bind_stats().tables.get(index as usize))
+    {
+        Some(table) => copy_to_buf(&table.table, buf, len),
+        _ => -1,
+    }
+}
+
+/// Get batches added of the `index`-th table in last execution, -1 if `index` is out of range.
+#[no_mangle]
+pub unsafe extern "C" fn ws_stmt_bind_table_batches(stmt: *mut WS_STMT, index: c_int) -> c_int {
+    match (stmt as *mut WsMaybeError<Stmt>)
+        .as_mut()
+        .and_then(|stmt| stmt.bind_stats().tables.get(index as usize))
+    {
+        Some(table) => table.batches as _,
+        _ => -1,
+    }
+}
+
+/// Get rows bound of the `index`-th table in last execution, -1 if `index` is out of range.
+#[no_mangle]
+pub unsafe extern "C" fn ws_stmt_bind_table_rows(stmt: *mut WS_STMT, index: c_int) -> c_int {
+    match (stmt as *mut WsMaybeError<Stmt>)
+        .as_mut()
+        .and_then(|stmt| stmt.bind_stats().tables.get(index as usize))
+    {
+        Some(table) => table.bound_rows as _,
+        _ => -1,
+    }
+}
+
+/// Copy binding error of the `index`-th table in last execution into `buf`.
+///
+/// Returns the length of error string, 0 if the table is bound, -1 if `index` is out of range.
+/// The error is truncated if the length is not less than `len`.
+#[no_mangle]
+pub unsafe extern "C" fn ws_stmt_bind_table_errstr(
+    stmt: *mut WS_STMT,
+    index: c_int,
+    buf: *mut c_char,
+    len: c_int,
+) -> c_int {
+    match (stmt as *mut WsMaybeError<Stmt>)
+        .as_mut()
+        .and_then(|stmt| stmt.bind_stats().tables.get(index as usize))
+    {
+        Some(table) => copy_to_buf(table.error.as_deref().unwrap_or_default(), buf, len),
+        _ => -1,
+    }
+}
+
 /// Equivalent to ws_errstr
 #[no_mangle]
 pub unsafe extern "C" fn ws_stmt_errstr(stmt: *mut WS_STMT) -> *const c_char {
@@ -580,6 +797,19 @@ pub unsafe extern "C" fn ws_stmt_close(stmt: *mut WS_STMT) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_copy_to_buf() {
+        let mut buf = [1 as c_char; 8];
+        unsafe {
+            assert_eq!(copy_to_buf("abc", buf.as_mut_ptr(), 8), 3);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "abc");
+            // "涛思" is 6 bytes, the second char does not fit in 5 bytes with nul.
+            assert_eq!(copy_to_buf("涛思", buf.as_mut_ptr(), 5), 6);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "涛");
+            assert_eq!(copy_to_buf("abc", std::ptr::null_mut(), 0), 3);
+        }
+    }
+
     #[test]
     fn stmt_common() {
         use crate::*;
@@ -775,8 +1005,14 @@ mod tests {
 
                 ws_stmt_add_batch(stmt);
                 let mut rows = 0;
-                ws_stmt_execute(stmt, &mut rows);
+                let mut failed = -1;
+                ws_stmt_execute_bind_stats(stmt, &mut rows, &mut failed);
                 assert_eq!(rows, 1);
+                assert_eq!(failed, 0);
+                assert_eq!(ws_stmt_bind_tables(stmt), 1);
+                assert_eq!(ws_stmt_bind_table_batches(stmt, 0), 1);
+                assert_eq!(ws_stmt_bind_table_rows(stmt, 0), 1);
+                assert_eq!(ws_stmt_bind_table_batches(stmt, 1), -1);
 
                 let sql = format!("select * from st where tbname = '{tbname}'\0");
                 let rs = ws_query(taos, sql.as_bytes().as_ptr() as _);
@@ -893,3 +1129,35 @@ fn json_test() {
     let s = json!(vec![Option::<u8>::None]);
     assert_eq!(dbg!(serde_json::to_string(&s).unwrap()), "[null]");
 }
+
+#[test]
+fn decimal_bind_test() {
+    let mut bind = TaosMultiBind::from_binary_vec(&[Some("123.45"), None]);
+    bind.buffer_type = Ty::Decimal as _;
+    assert!(bind.check_decimals().is_ok());
+    assert!(matches!(
+        bind.to_tag_value(),
+        Ok(Value::Decimal(v)) if v == Decimal::new(12345, 38, 2)
+    ));
+    assert_eq!(bind.to_json(), serde_json::json!(["123.45", null]));
+
+    // Digits beyond the range of rust_decimal are kept.
+    let max = "9".repeat(36) + ".99";
+    let mut bind = TaosMultiBind::from_binary_vec(&[Some(max.as_str())]);
+    bind.buffer_type = Ty::Decimal64 as _;
+    assert!(bind.check_decimals().is_ok());
+    assert!(matches!(
+        bind.to_tag_value(),
+        Ok(Value::Decimal(v)) if v.to_string() == max
+    ));
+
+    let mut bind = TaosMultiBind::from_binary_vec(&[Some("1.2.3")]);
+    bind.buffer_type = Ty::Decimal as _;
+    assert!(bind.check_decimals().is_err());
+    assert!(bind.to_tag_value().is_err());
+
+    let mut bind = TaosMultiBind::from_binary_vec(&[Some([0xffu8].as_slice())]);
+    bind.buffer_type = Ty::Decimal as _;
+    assert!(bind.check_decimals().is_err());
+    assert!(bind.to_tag_value().is_err());
+}
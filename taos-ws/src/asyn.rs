@@ -4,7 +4,6 @@ use futures::{FutureExt, SinkExt, StreamExt};
 use scc::HashMap;
 // use std::sync::Mutex;
 use taos_query::common::{Field, Precision, RawBlock, RawMeta, Ty};
-use taos_query::util::InlinableWrite;
 use taos_query::{
     block_in_place_or_global, AsyncFetchable, AsyncQueryable, DeError, DsnError, IntoDsn,
 };
@@ -19,14 +18,13 @@ use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use crate::{infra::*, TaosBuilder};
 
 use std::fmt::Debug;
-use std::io::Write;
 use std::result::Result as StdResult;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Duration;
 
 type WsFetchResult = std::result::Result<WsFetchData, taos_error::Error>;
-type FetchSender = std::sync::mpsc::SyncSender<WsFetchResult>;
+pub(crate) type FetchSender = std::sync::mpsc::SyncSender<WsFetchResult>;
 type FetchReceiver = std::sync::mpsc::Receiver<WsFetchResult>;
 
 type WsSender = tokio::sync::mpsc::Sender<Message>;
@@ -53,6 +51,8 @@ pub struct ResultSet {
     affected_rows: usize,
     precision: Precision,
     summary: (usize, usize),
+    /// Result of a stmt query is freed with the stmt, not by itself.
+    owned_by_stmt: bool,
 }
 
 unsafe impl Sync for ResultSet {}
@@ -77,6 +77,9 @@ impl Drop for ResultSet {
     fn drop(&mut self) {
         if self.receiver.is_some() {
             self.fetches.remove(&self.args.id);
+            if self.owned_by_stmt {
+                return;
+            }
             let args = self.args;
             let ws = self.ws.clone();
             block_in_place_or_global(async move {
@@ -120,6 +123,8 @@ pub enum Error {
     WsError(#[from] WsError),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    RowsError(#[from] taos_query::stmt::RowsError),
 }
 
 impl Error {
@@ -390,19 +395,11 @@ impl WsTaos {
 
     pub async fn write_meta(&self, raw: RawMeta) -> Result<()> {
         let req_id = self.req_id();
-        let message_id = req_id;
-        let raw_meta_message = 3; // magic number from taosAdapter.
-
-        let mut meta = Vec::new();
-        meta.write_u64_le(req_id)?;
-        meta.write_u64_le(message_id)?;
-        meta.write_u64_le(raw_meta_message as u64)?;
-        meta.write(&raw.as_bytes())?;
+        let meta = raw_meta_message(req_id, &raw)?;
 
         log::debug!(
-            "write meta with req_id: {}, message_id: {}, raw data: {:?}",
+            "write meta with req_id: {}, raw data: {:?}",
             req_id,
-            message_id,
             Bytes::copy_from_slice(&meta)
         );
 
@@ -427,22 +424,17 @@ impl WsTaos {
         Ok(())
     }
     async fn s_write_raw_block(&self, raw: &RawBlock) -> Result<()> {
-        let req_id = self.req_id();
-        let message_id = req_id;
-        let raw_block_message = 4; // action number from `taosAdapter/controller/rest/const.go:L56`.
+        self.write_raw_block_to(raw_block_table_name(raw)?, raw).await
+    }
 
-        let mut meta = Vec::new();
-        meta.write_u64_le(req_id)?;
-        meta.write_u64_le(message_id)?;
-        meta.write_u64_le(raw_block_message as u64)?;
-        meta.write_u32_le(raw.nrows() as u32)?;
-        meta.write_inlined_str::<2>(raw.table_name().unwrap())?;
-        meta.write(&raw.as_raw_bytes())?;
+    /// Write raw block into `table`.
+    pub async fn write_raw_block_to(&self, table: &str, raw: &RawBlock) -> Result<()> {
+        let req_id = self.req_id();
+        let meta = raw_block_message(req_id, table, raw)?;
 
         log::debug!(
-            "write meta with req_id: {}, message_id: {}, raw data: {:?}",
+            "write raw block with req_id: {}, raw data: {:?}",
             req_id,
-            message_id,
             Bytes::copy_from_slice(&meta)
         );
 
@@ -517,6 +509,7 @@ impl WsTaos {
                     id: resp.id,
                 },
                 summary: (0, 0),
+                owned_by_stmt: false,
             })
         } else {
             Ok(ResultSet {
@@ -533,6 +526,7 @@ impl WsTaos {
                 fields_count: 0,
                 precision: resp.precision,
                 summary: (0, 0),
+                owned_by_stmt: false,
             })
         }
     }
@@ -558,6 +552,32 @@ impl WsTaos {
 }
 
 impl ResultSet {
+    /// Result set of a stmt query, fetched in the stmt connection.
+    pub(crate) fn from_stmt(
+        ws: WsSender,
+        fetches: Arc<HashMap<ResId, FetchSender>>,
+        timeout: Duration,
+        args: WsResArgs,
+        fields: Vec<Field>,
+        precision: Precision,
+    ) -> Self {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(2);
+        let _ = fetches.insert(args.id, sender);
+        Self {
+            ws,
+            timeout,
+            fetches,
+            receiver: Some(receiver),
+            args,
+            fields_count: fields.len(),
+            fields: Some(fields),
+            affected_rows: 0,
+            precision,
+            summary: (0, 0),
+            owned_by_stmt: true,
+        }
+    }
+
     async fn fetch(&mut self) -> Result<Option<RawBlock>> {
         let fetch = WsSend::Fetch(self.args);
         {
@@ -614,7 +634,7 @@ impl ResultSet {
                     dbg!(fetch_resp.lengths.as_ref().unwrap()),
                     fetch_resp.rows,
                     self.precision,
-                );
+                )?;
 
                 for row in 0..raw.nrows() {
                     for col in 0..raw.ncols() {
@@ -711,6 +731,10 @@ impl AsyncQueryable for WsTaos {
     async fn write_raw_block(&self, block: &RawBlock) -> StdResult<(), Self::Error> {
         self.s_write_raw_block(block).await
     }
+
+    async fn write_raw_block_to(&self, table: &str, block: &RawBlock) -> StdResult<(), Self::Error> {
+        WsTaos::write_raw_block_to(self, table, block).await
+    }
 }
 
 // Websocket tests should always use `multi_thread`
@@ -806,7 +830,7 @@ async fn ws_write_raw_block() -> anyhow::Result<()> {
         &[8, 1],
         1,
         Precision::Millisecond,
-    );
+    )?;
     raw.with_table_name("tb1");
     dbg!(&raw);
 
@@ -0,0 +1,462 @@
+//! Bulk insert rows into subtables of a super table with stmt.
+//!
+//! A [BulkInserter] buffers `(table, tags, row)` items per subtable and writes them in one
+//! stmt execution when a threshold is reached, subtables are created automatically with the tags.
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use taos_query::common::{ColumnView, Field, Precision, Value};
+use taos_query::insert::quote_table;
+use taos_query::stmt::{rows_to_views, tags_to_views, RowsError};
+use taos_query::AsyncQueryable;
+
+use crate::asyn::Error;
+use crate::{Stmt, Taos, TaosBuilder};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Errors of [BulkInserter].
+#[derive(Debug, thiserror::Error)]
+pub enum BulkError {
+    #[error(transparent)]
+    Ws(#[from] Error),
+    #[error("subtable `{table}` is pushed with tags {actual:?}, but buffered with {expected:?}")]
+    TagsMismatch {
+        table: String,
+        expected: Vec<Value>,
+        actual: Vec<Value>,
+    },
+    /// Writing failed, the rows not written are returned to retry.
+    #[error("failed to write {} subtables: {source}", .unwritten.len())]
+    Flush {
+        source: Error,
+        unwritten: Vec<TableRows>,
+    },
+}
+
+/// Report of a flush.
+#[derive(Debug, Default)]
+pub struct FlushReport {
+    /// Subtables written.
+    pub tables: usize,
+    /// Rows bound in the flush.
+    pub rows: usize,
+    /// Rows affected by the execution.
+    pub affected_rows: usize,
+    /// Time cost of the flush.
+    pub elapsed: Duration,
+    /// Subtables with invalid tags or rows, their rows are discarded.
+    pub errors: Vec<(String, Error)>,
+}
+
+impl FlushReport {
+    /// Check if all buffered subtables are written.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Buffered rows of a subtable.
+#[derive(Debug, Clone)]
+pub struct TableRows {
+    /// Subtable name.
+    pub table: String,
+    /// Tags to create the subtable.
+    pub tags: Vec<Value>,
+    /// Rows in the order of pushing, serialized as JSON values until flushing.
+    pub rows: Vec<JsonValue>,
+}
+
+/// Tag and column fields of the super table, with its timestamp precision.
+#[derive(Debug, Clone)]
+struct Fields {
+    tags: Vec<Field>,
+    columns: Vec<Field>,
+    precision: Precision,
+}
+
+impl TableRows {
+    fn to_views(
+        &self,
+        fields: &Fields,
+    ) -> std::result::Result<(Vec<ColumnView>, Vec<ColumnView>), RowsError> {
+        Ok((
+            tags_to_views(&self.tags, &fields.tags, fields.precision)?,
+            rows_to_views(&self.rows, &fields.columns, fields.precision)?,
+        ))
+    }
+}
+
+/// Buffered writer of a super table with a stmt, flushed by rows, bytes or delay.
+///
+/// All tags and rows are converted with the field types of the super table before sending,
+/// subtables with invalid data are reported in [FlushReport::errors] and never bound.
+/// Failed writes return the buffered rows in [BulkError::Flush], and the stmt is renewed in
+/// the next flush, with a new connection if the previous one is lost.
+///
+/// Rows are buffered as `serde_json::Value` and transposed to column views in flush, which costs
+/// an allocation for each value. For high-frequency writes of columnar data, bind column views
+/// with [Stmt::stmt_bind_block] directly.
+pub struct BulkInserter {
+    stmt: Stmt,
+    /// Set after a failed write, the stmt must be renewed before writing again.
+    broken: bool,
+    /// To connect again with the database of the stmt.
+    dsn: TaosBuilder,
+    sql: String,
+    fields: Option<Fields>,
+    buffers: Vec<TableRows>,
+    indexes: HashMap<String, usize>,
+    max_rows: usize,
+    max_bytes: usize,
+    max_delay: Duration,
+    rows: usize,
+    bytes: usize,
+    last_flush: Instant,
+}
+
+impl BulkInserter {
+    /// Insert into subtables of super table `stable`, which could be `db.stb` or `stb` in current database.
+    pub async fn new(taos: &Taos, stable: &str) -> Result<Self> {
+        let stable = quote_table(stable).map_err(Error::from)?;
+        let describe = taos.describe(&stable).await?;
+        let tags = describe.tag_names().count();
+        let cols = describe.names().count() - tags;
+        let placeholders = |n: usize| vec!["?"; n].join(",");
+        let sql = format!(
+            "insert into ? using {stable} tags({}) values({})",
+            placeholders(tags),
+            placeholders(cols)
+        );
+        let mut stmt: Stmt = taos.stmt_init().await?;
+        stmt.stmt_prepare(&sql).await?;
+        let mut dsn = taos.dsn.clone();
+        dsn.database = match &*taos.stmt_client.lock().await {
+            Some((database, _)) => database.clone(),
+            None => None,
+        };
+        Ok(Self {
+            stmt,
+            broken: false,
+            dsn,
+            sql,
+            fields: None,
+            buffers: Vec::new(),
+            indexes: HashMap::new(),
+            max_rows: 10000,
+            max_bytes: 4 * 1024 * 1024,
+            max_delay: Duration::from_secs(1),
+            rows: 0,
+            bytes: 0,
+            last_flush: Instant::now(),
+        })
+    }
+
+    /// Flush when buffered rows reach `max_rows`, default is 10000.
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows;
+        self
+    }
+
+    /// Flush when buffered rows reach `max_bytes` approximately, default is 4MB.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Flush when `max_delay` passed since last flush, default is 1s.
+    ///
+    /// There's no background timer, it's checked in [BulkInserter::push] and
+    /// [BulkInserter::flush_if_due], so idle writers should call the latter periodically.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Buffered rows.
+    pub fn buffered_rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Push a row of subtable `table`, returns the report if a flush is triggered.
+    ///
+    /// `tags` are used to create the subtable when it's first seen in a flush, and must be the
+    /// same for the rows of a subtable in the buffer. Rows are struct, map or tuple in the
+    /// order of columns as [Stmt::bind_rows].
+    ///
+    /// ```rust,ignore
+    /// #[derive(Serialize)]
+    /// struct Meter {
+    ///     ts: i64,
+    ///     current: f32,
+    /// }
+    /// let mut inserter = BulkInserter::new(&taos, "meters").await?.with_max_rows(1000);
+    /// inserter.push("d0", vec![Value::Int(1)], &Meter { ts: 0, current: 0.1 }).await?;
+    /// let report = inserter.flush().await?;
+    /// ```
+    pub async fn push<T: Serialize>(
+        &mut self,
+        table: &str,
+        tags: Vec<Value>,
+        row: &T,
+    ) -> std::result::Result<Option<FlushReport>, BulkError> {
+        let row = serde_json::to_value(row)
+            .map_err(|source| RowsError::Serialize {
+                row: self.rows,
+                source,
+            })
+            .map_err(Error::from)?;
+        let size = json_size(&row);
+        match self.indexes.get(table) {
+            Some(index) => {
+                let buffer = &mut self.buffers[*index];
+                if buffer.tags != tags {
+                    return Err(BulkError::TagsMismatch {
+                        table: table.to_string(),
+                        expected: buffer.tags.clone(),
+                        actual: tags,
+                    });
+                }
+                buffer.rows.push(row);
+            }
+            None => {
+                self.indexes.insert(table.to_string(), self.buffers.len());
+                self.buffers.push(TableRows {
+                    table: table.to_string(),
+                    tags,
+                    rows: vec![row],
+                });
+            }
+        }
+        self.bytes += size;
+        self.rows += 1;
+        if self.rows >= self.max_rows || self.bytes >= self.max_bytes {
+            self.flush().await.map(Some)
+        } else {
+            self.flush_if_due().await
+        }
+    }
+
+    /// Flush if `max_delay` passed since last flush and there are buffered rows.
+    pub async fn flush_if_due(&mut self) -> std::result::Result<Option<FlushReport>, BulkError> {
+        if self.rows > 0 && self.last_flush.elapsed() >= self.max_delay {
+            self.flush().await.map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Write all buffered rows in one stmt execution, the buffer is cleared in any case.
+    ///
+    /// Subtables with invalid tags or rows are reported in [FlushReport::errors]. If writing
+    /// or renewing the stmt of a previous failure failed, the other subtables are returned in
+    /// [BulkError::Flush] to retry, e.g. by pushing them again.
+    pub async fn flush(&mut self) -> std::result::Result<FlushReport, BulkError> {
+        let start = Instant::now();
+        let buffers = std::mem::take(&mut self.buffers);
+        self.indexes.clear();
+        self.rows = 0;
+        self.bytes = 0;
+        self.last_flush = start;
+
+        let mut report = FlushReport::default();
+        let first = match buffers.first() {
+            Some(buffer) => buffer.table.clone(),
+            None => return Ok(report),
+        };
+        if self.broken {
+            if let Err(source) = self.renew().await {
+                return Err(BulkError::Flush {
+                    source,
+                    unwritten: buffers,
+                });
+            }
+        }
+        let fields = match self.fields(&first).await {
+            Ok(fields) => fields,
+            Err(source) => return Err(self.fail(source, buffers)),
+        };
+
+        // Convert all subtables before sending, so the stmt is never left partly bound by invalid data.
+        let mut tables = Vec::with_capacity(buffers.len());
+        for buffer in buffers {
+            match buffer.to_views(&fields) {
+                Ok(views) => tables.push((buffer, views)),
+                Err(err) => report.errors.push((buffer.table, err.into())),
+            }
+        }
+        if tables.is_empty() {
+            report.elapsed = start.elapsed();
+            return Ok(report);
+        }
+        match self.write(&tables).await {
+            Ok(affected_rows) => report.affected_rows = affected_rows,
+            Err(source) => {
+                let buffers = tables.into_iter().map(|(buffer, _)| buffer).collect();
+                return Err(self.fail(source, buffers));
+            }
+        }
+        report.tables = tables.len();
+        report.rows = tables.iter().map(|(buffer, _)| buffer.rows.len()).sum();
+        report.elapsed = start.elapsed();
+        Ok(report)
+    }
+
+    /// Fields of the super table, it requires a subtable name to get fields from stmt.
+    async fn fields(&mut self, table: &str) -> Result<Fields> {
+        if let Some(fields) = &self.fields {
+            return Ok(fields.clone());
+        }
+        self.stmt.stmt_set_tbname(table).await?;
+        let (tags, _) = self.stmt.fields_with_precision(true).await?;
+        let (columns, precision) = self.stmt.fields_with_precision(false).await?;
+        let fields = Fields {
+            tags,
+            columns,
+            precision,
+        };
+        self.fields = Some(fields.clone());
+        Ok(fields)
+    }
+
+    async fn write(
+        &mut self,
+        tables: &[(TableRows, (Vec<ColumnView>, Vec<ColumnView>))],
+    ) -> Result<usize> {
+        for (buffer, (tags, columns)) in tables {
+            self.stmt.stmt_set_tbname(&buffer.table).await?;
+            self.stmt.stmt_set_tags_block(tags).await?;
+            self.stmt.stmt_bind_block(columns).await?;
+            self.stmt.stmt_add_batch().await?;
+        }
+        self.stmt.stmt_exec().await
+    }
+
+    /// Mark the stmt broken after a failed write, which may hold a partly bound subtable.
+    fn fail(&mut self, source: Error, unwritten: Vec<TableRows>) -> BulkError {
+        self.broken = true;
+        BulkError::Flush { source, unwritten }
+    }
+
+    /// Renew the broken stmt, on a new connection if the previous one is lost.
+    async fn renew(&mut self) -> Result<()> {
+        if !self.stmt.is_alive() {
+            self.stmt = Stmt::from_wsinfo(&self.dsn).await?;
+        }
+        self.stmt.stmt_init().await?;
+        self.stmt.stmt_prepare(&self.sql).await?;
+        self.broken = false;
+        Ok(())
+    }
+}
+
+/// Approximate size of a row in raw block.
+fn json_size(value: &JsonValue) -> usize {
+    match value {
+        JsonValue::Null | JsonValue::Bool(_) => 1,
+        JsonValue::Number(_) => 8,
+        JsonValue::String(s) => s.len() + 2,
+        JsonValue::Array(values) => values.iter().map(json_size).sum(),
+        JsonValue::Object(map) => map.values().map(json_size).sum(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use taos_query::common::Value;
+    use taos_query::{AsyncQueryable, TBuilder};
+
+    use super::{BulkError, BulkInserter};
+    use crate::TaosBuilder;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_bulk_inserter() -> anyhow::Result<()> {
+        #[derive(serde::Serialize)]
+        struct Meter {
+            ts: i64,
+            current: f32,
+        }
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists ws_bulk",
+            "create database ws_bulk",
+            "use ws_bulk",
+            "create table meters (ts timestamp, current float) tags(group_id int)",
+        ])
+        .await?;
+
+        let mut inserter = BulkInserter::new(&taos, "meters")
+            .await?
+            .with_max_rows(4)
+            .with_max_delay(std::time::Duration::from_secs(3600));
+        for i in 0..3 {
+            let table = format!("d{}", i % 2);
+            let meter = Meter {
+                ts: 1654570964022 + i,
+                current: i as f32,
+            };
+            let report = inserter
+                .push(&table, vec![Value::Int((i % 2) as _)], &meter)
+                .await?;
+            assert!(report.is_none());
+        }
+        assert_eq!(inserter.buffered_rows(), 3);
+
+        let report = inserter
+            .push("d2", vec![Value::Int(2)], &(1654570964022i64, 0.5f32))
+            .await?
+            .expect("flush by max rows");
+        assert!(report.is_ok());
+        assert_eq!(report.tables, 3);
+        assert_eq!(report.affected_rows, 4);
+        assert_eq!(inserter.buffered_rows(), 0);
+
+        // Bind errors are reported per table.
+        inserter
+            .push(
+                "d3",
+                vec![Value::VarChar("x".to_string())],
+                &(1654570964022i64, 0.5f32),
+            )
+            .await?;
+        let report = inserter.flush().await?;
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].0, "d3");
+
+        // Tags of a buffered subtable could not be changed.
+        inserter
+            .push("d4", vec![Value::Int(4)], &(1654570964022i64, 0.5f32))
+            .await?;
+        let err = inserter
+            .push("d4", vec![Value::Int(5)], &(1654570964023i64, 0.5f32))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BulkError::TagsMismatch { .. }), "{err}");
+        assert_eq!(inserter.buffered_rows(), 1);
+
+        // Rows are returned if writing failed, the super table is not a valid subtable.
+        inserter
+            .push("meters", vec![Value::Int(6)], &(1654570964022i64, 0.5f32))
+            .await?;
+        match inserter.flush().await.unwrap_err() {
+            BulkError::Flush { unwritten, .. } => {
+                let rows: usize = unwritten.iter().map(|table| table.rows.len()).sum();
+                assert_eq!(unwritten.len(), 2);
+                assert_eq!(rows, 2);
+            }
+            err => panic!("unexpected error: {err}"),
+        }
+
+        // The stmt is renewed and works as before.
+        inserter
+            .push("d5", vec![Value::Int(5)], &(1654570964022i64, 0.5f32))
+            .await?;
+        assert_eq!(inserter.flush().await?.affected_rows, 1);
+
+        taos.exec("drop database ws_bulk").await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! Poll batches of loaded messages with fetch concurrency.
+//!
+//! The adapter keeps only the current message of a consumer connection, so polls and fetches
+//! of one connection are sequential. A [BatchConsumer] opens `fetch.concurrency` connections
+//! in the same group, the server shares the vgroups of the topics among them, and polls and
+//! fetches them concurrently.
+use taos_query::tmq::{AsAsyncConsumer, IsOffset, Timeout};
+
+use super::{BatchMessage, Consumer, Offset, Result};
+
+/// Offset of a message polled by a [BatchConsumer], it should be committed by the same one.
+pub struct BatchOffset {
+    worker: usize,
+    offset: Offset,
+}
+
+impl BatchOffset {
+    /// Index of the connection that polled the message.
+    pub fn worker(&self) -> usize {
+        self.worker
+    }
+
+    /// The offset in the connection.
+    pub fn offset(&self) -> &Offset {
+        &self.offset
+    }
+}
+
+impl IsOffset for BatchOffset {
+    fn database(&self) -> &str {
+        self.offset.database()
+    }
+
+    fn topic(&self) -> &str {
+        self.offset.topic()
+    }
+
+    fn vgroup_id(&self) -> i32 {
+        self.offset.vgroup_id()
+    }
+}
+
+/// A consumer polling batches of messages from concurrent connections, see the module docs.
+///
+/// Build it by [TmqBuilder::build_batch_consumer](super::TmqBuilder::build_batch_consumer).
+pub struct BatchConsumer {
+    consumers: Vec<Consumer>,
+    batch_size: usize,
+}
+
+impl BatchConsumer {
+    /// Poll with subscribed consumers of the same group, at most `batch_size` messages a batch.
+    pub fn from_consumers(consumers: Vec<Consumer>, batch_size: usize) -> Self {
+        Self {
+            consumers,
+            batch_size: batch_size.max(1),
+        }
+    }
+
+    /// Connections of the consumer, the fetch concurrency.
+    pub fn consumers(&self) -> &[Consumer] {
+        &self.consumers
+    }
+
+    /// Max messages in a batch.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Subscribe `topics` in all connections.
+    pub async fn subscribe<T: Into<String>, I: IntoIterator<Item = T> + Send>(
+        &mut self,
+        topics: I,
+    ) -> Result<()> {
+        let topics: Vec<String> = topics.into_iter().map(Into::into).collect();
+        futures::future::try_join_all(
+            self.consumers
+                .iter_mut()
+                .map(|consumer| consumer.subscribe(topics.clone())),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Poll at most [BatchConsumer::batch_size] messages with meta or data blocks loaded.
+    ///
+    /// Connections are polled concurrently by [Consumer::poll_batch], each for its share of the
+    /// batch size. Messages of each connection are kept in the polled order.
+    pub async fn poll_batch(&self, timeout: Timeout) -> Result<Vec<(BatchOffset, BatchMessage)>> {
+        let share = self.batch_size.div_ceil(self.consumers.len().max(1));
+        let batches = futures::future::try_join_all(
+            self.consumers
+                .iter()
+                .map(|consumer| consumer.poll_batch(share, timeout)),
+        )
+        .await?;
+        Ok(batches
+            .into_iter()
+            .enumerate()
+            .flat_map(|(worker, batch)| {
+                batch
+                    .into_iter()
+                    .map(move |(offset, message)| (BatchOffset { worker, offset }, message))
+            })
+            .collect())
+    }
+
+    /// Commit the offset in the connection that polled it.
+    pub async fn commit(&self, offset: BatchOffset) -> Result<()> {
+        self.consumers[offset.worker].commit(offset.offset).await
+    }
+
+    /// Close all connections.
+    pub async fn close(self) -> Result<()> {
+        futures::future::try_join_all(self.consumers.into_iter().map(Consumer::close)).await?;
+        Ok(())
+    }
+}
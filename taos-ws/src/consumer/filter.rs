@@ -0,0 +1,124 @@
+//! Table name filter for tmq data messages.
+
+/// Filter blocks of data messages by table name, before fetching the block data.
+///
+/// Patterns are globs, `*` matches any sequence of characters and `?` matches one character.
+/// A table passes if it matches any of the included patterns (or none is set),
+/// and does not match any of the excluded patterns.
+///
+/// ```rust
+/// # use taos_ws::consumer::TableFilter;
+/// let filter = TableFilter::new().include("d*").exclude("d1?");
+/// assert!(filter.is_match("d1"));
+/// assert!(!filter.is_match("d10"));
+/// assert!(!filter.is_match("t1"));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TableFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl TableFilter {
+    /// An empty filter that accepts all tables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse from comma separated patterns, as `table.include` and `table.exclude` in dsn.
+    pub fn from_patterns(include: Option<&str>, exclude: Option<&str>) -> Self {
+        fn split(patterns: Option<&str>) -> Vec<String> {
+            patterns
+                .map(|patterns| {
+                    patterns
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|p| !p.is_empty())
+                        .map(ToString::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+        Self {
+            include: split(include),
+            exclude: split(exclude),
+        }
+    }
+
+    /// Accept tables matching the pattern.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Skip tables matching the pattern.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Check if the filter accepts all tables.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Check if the table passes the filter.
+    pub fn is_match(&self, table: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| glob_match(p, table)))
+            && !self.exclude.iter().any(|p| glob_match(p, table))
+    }
+}
+
+/// Glob matching with `*` and `?`, with backtracking on the last `*`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    p = sp + 1;
+                    t = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("*", ""));
+    assert!(glob_match("d*", "d1001"));
+    assert!(glob_match("d?0*1", "d1001"));
+    assert!(glob_match("*_meter", "sh_meter"));
+    assert!(!glob_match("*_meter", "sh_meters"));
+    assert!(!glob_match("d?", "d10"));
+    assert!(glob_match("tb1", "tb1"));
+}
+
+#[test]
+fn test_table_filter() {
+    let filter = TableFilter::from_patterns(Some("d*, t1"), Some("d2*"));
+    assert!(filter.is_match("d1"));
+    assert!(filter.is_match("t1"));
+    assert!(!filter.is_match("d21"));
+    assert!(!filter.is_match("t2"));
+
+    let filter = TableFilter::from_patterns(None, Some("tmp_*"));
+    assert!(filter.is_match("t2"));
+    assert!(!filter.is_match("tmp_1"));
+    assert!(TableFilter::from_patterns(None, Some("")).is_empty());
+}
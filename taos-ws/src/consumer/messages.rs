@@ -9,7 +9,7 @@ use serde_with::NoneAsEmptyString;
 use taos_query::common::Field;
 use taos_query::common::Precision;
 use taos_query::common::Ty;
-use taos_query::tmq::VGroupId;
+use taos_query::tmq::{Assignment, VGroupId};
 
 use crate::infra::ToMessage;
 use crate::infra::WsConnReq;
@@ -29,6 +29,12 @@ pub struct MessageArgs {
     pub(crate) message_id: MessageId,
 }
 
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct TopicArgs {
+    pub(crate) req_id: ReqId,
+    pub(crate) topic: String,
+}
+
 #[derive(Debug, Deserialize_repr, Serialize_repr, Clone, Copy)]
 #[repr(i32)]
 pub enum MessageType {
@@ -79,6 +85,10 @@ pub enum TmqSend {
     Fetch(MessageArgs),
     FetchBlock(MessageArgs),
     Commit(MessageArgs),
+    Assignment(TopicArgs),
+    Unsubscribe {
+        req_id: ReqId,
+    },
     Close,
 }
 
@@ -103,6 +113,8 @@ impl TmqSend {
             TmqSend::Fetch(args) => args.req_id,
             TmqSend::FetchBlock(args) => args.req_id,
             TmqSend::Commit(args) => args.req_id,
+            TmqSend::Assignment(args) => args.req_id,
+            TmqSend::Unsubscribe { req_id } => *req_id,
             TmqSend::Close => unreachable!(),
         }
     }
@@ -135,6 +147,8 @@ pub struct TmqFetch {
     pub fields_lengths: Option<Vec<u32>>,
     pub precision: Precision,
     pub rows: usize,
+    #[serde(default)]
+    pub timing: u64,
 }
 
 impl TmqFetch {
@@ -181,6 +195,11 @@ pub enum TmqRecvData {
     },
     Block(Vec<u32>),
     Commit,
+    Assignment {
+        #[serde(default)]
+        assignment: Vec<Assignment>,
+    },
+    Unsubscribe,
     Close,
 }
 
@@ -226,4 +245,26 @@ fn test_serde_recv_data() {
     dbg!(d.ok());
 }
 
+#[test]
+fn test_serde_recv_assignment() {
+    let json = r#"{
+        "code": 0,
+        "message": "",
+        "action": "assignment",
+        "req_id": 2,
+        "timing": 1024,
+        "assignment": [{"vgroup_id": 2, "offset": 10, "begin": 0, "end": 100}]
+    }"#;
+    let d: TmqRecv = serde_json::from_str(&json).unwrap();
+    let (req_id, data, ok) = d.ok();
+    assert_eq!(req_id, 2);
+    assert!(ok.is_ok());
+    match data {
+        TmqRecvData::Assignment { assignment } => {
+            assert_eq!(assignment, vec![Assignment::new(2, 10, 0, 100)]);
+        }
+        _ => unreachable!(),
+    }
+}
+
 impl ToMessage for TmqSend {}
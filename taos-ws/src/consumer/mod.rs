@@ -4,10 +4,10 @@ use itertools::Itertools;
 use scc::HashMap;
 
 use taos_query::block_in_place_or_global;
-use taos_query::common::{JsonMeta, RawMeta};
+use taos_query::common::{Field, JsonMeta, Precision, RawMeta};
 use taos_query::tmq::{
-    AsAsyncConsumer, AsConsumer, IsAsyncData, IsAsyncMeta, IsOffset, MessageSet, SyncOnAsync,
-    Timeout,
+    AsAsyncConsumer, AsConsumer, Assignment, IsAsyncData, IsAsyncMeta, IsOffset, MessageSet,
+    RebalanceListener, SyncOnAsync, Timeout, TopicVGroup,
 };
 use taos_query::util::InlinableRead;
 use taos_query::{AsyncFetchable, DeError, DsnError, IntoDsn, RawBlock, TBuilder};
@@ -16,22 +16,31 @@ use tokio::sync::{oneshot, watch};
 
 use tokio::time;
 use tokio_tungstenite::tungstenite::Error as WsError;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio_tungstenite::{
+    connect_async, tungstenite::protocol::Message, MaybeTlsStream, WebSocketStream,
+};
 
 use crate::infra::ToMessage;
 use crate::{infra::WsConnReq, TaosBuilder};
 use messages::*;
 
+use std::collections::BTreeSet;
 use std::fmt::Debug;
 use std::result::Result as StdResult;
-use std::sync::atomic::AtomicU64;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+mod batch;
+mod filter;
 mod messages;
 
+pub use batch::{BatchConsumer, BatchOffset};
+pub use filter::TableFilter;
+
 type WsSender = tokio::sync::mpsc::Sender<Message>;
 type WsTmqAgent = Arc<HashMap<ReqId, oneshot::Sender<StdResult<TmqRecvData, taos_error::Error>>>>;
+type WsTmqReceiver = oneshot::Receiver<StdResult<TmqRecvData, taos_error::Error>>;
 
 #[derive(Debug, Clone)]
 struct WsTmqSender {
@@ -49,26 +58,36 @@ impl WsTmqSender {
     async fn send_recv(&self, msg: TmqSend) -> Result<TmqRecvData> {
         self.send_recv_timeout(msg, self.timeout).await
     }
-    async fn send_recv_timeout(&self, msg: TmqSend, timeout: Duration) -> Result<TmqRecvData> {
+    /// Send a request, returns the receiver of its response.
+    async fn send(&self, msg: TmqSend) -> Result<WsTmqReceiver> {
         let send_timeout = Duration::from_millis(500);
+        let req_id = msg.req_id();
+        let (tx, rx) = oneshot::channel();
+
+        self.queries.insert(req_id, tx).unwrap();
+
+        if let Err(err) = self.sender.send_timeout(msg.to_msg(), send_timeout).await {
+            self.queries.remove(&req_id);
+            return Err(err.into());
+        }
+        Ok(rx)
+    }
+    async fn send_recv_timeout(&self, msg: TmqSend, timeout: Duration) -> Result<TmqRecvData> {
         if let TmqSend::Close = msg {
             log::debug!("send close message");
             self.sender.send(Message::Close(None)).await?;
             return Ok(TmqRecvData::Close);
         }
         let req_id = msg.req_id();
-        let (tx, rx) = oneshot::channel();
-
-        self.queries.insert(req_id, tx).unwrap();
-
-        self.sender.send_timeout(msg.to_msg(), send_timeout).await?;
+        let rx = self.send(msg).await?;
 
         let sleep = tokio::time::sleep(timeout);
         tokio::pin!(sleep);
         let data = tokio::select! {
             _ = &mut sleep, if !sleep.is_elapsed() => {
-               log::debug!("poll timed out");
-               Err(Error::QueryTimeout("poll".to_string()))?
+               log::debug!("request {req_id} timed out");
+               self.queries.remove(&req_id);
+               Err(Error::QueryTimeout(format!("request {req_id}")))?
             }
             message = rx => {
                 message??
@@ -81,6 +100,12 @@ impl WsTmqSender {
 pub struct TmqBuilder {
     info: TaosBuilder,
     conf: TmqInit,
+    reconnect_retries: u32,
+    reconnect_backoff: Duration,
+    resubscribe_timeout: Duration,
+    table_filter: TableFilter,
+    fetch_concurrency: usize,
+    batch_size: usize,
 }
 
 impl TBuilder for TmqBuilder {
@@ -89,7 +114,19 @@ impl TBuilder for TmqBuilder {
     type Error = Error;
 
     fn available_params() -> &'static [&'static str] {
-        &["token", "timeout", "group.id", "client.id"]
+        &[
+            "token",
+            "timeout",
+            "group.id",
+            "client.id",
+            "reconnect.retries",
+            "reconnect.backoff.ms",
+            "resubscribe.timeout.ms",
+            "table.include",
+            "table.exclude",
+            "fetch.concurrency",
+            "batch.size",
+        ]
     }
 
     fn from_dsn<D: IntoDsn>(dsn: D) -> StdResult<Self, Self::Error> {
@@ -116,29 +153,46 @@ impl TBuilder for TmqBuilder {
 struct WsMessageBase {
     sender: WsTmqSender,
     message_id: MessageId,
+    filter: Arc<TableFilter>,
 }
 
 impl WsMessageBase {
-    async fn fetch_raw_block(&self) -> Result<Option<RawBlock>> {
-        let req_id = self.sender.req_id();
+    /// Fetch metadata of next block, blocks of tables filtered out are skipped.
+    async fn fetch_meta(&self) -> Result<Option<TmqFetch>> {
+        loop {
+            let msg = TmqSend::Fetch(MessageArgs {
+                req_id: self.sender.req_id(),
+                message_id: self.message_id,
+            });
+            let data = self.sender.send_recv(msg).await?;
+            let fetch = if let TmqRecvData::Fetch(fetch) = data {
+                fetch
+            } else {
+                unreachable!()
+            };
+
+            if fetch.completed {
+                return Ok(None);
+            }
+            // Skip the block without fetching its data if the table is filtered out.
+            match fetch.table_name.as_deref() {
+                Some(name) if !self.filter.is_match(name) => {
+                    log::trace!("skip block of table {name}");
+                }
+                _ => return Ok(Some(fetch)),
+            }
+        }
+    }
 
-        let msg = TmqSend::Fetch(MessageArgs {
-            req_id,
-            message_id: self.message_id,
-        });
-        let data = self.sender.send_recv(msg).await?;
-        let fetch = if let TmqRecvData::Fetch(fetch) = data {
-            fetch
-        } else {
-            unreachable!()
+    async fn fetch_raw_block(&self) -> Result<Option<(BlockMeta, RawBlock)>> {
+        let start = Instant::now();
+        let fetch = match self.fetch_meta().await? {
+            Some(fetch) => fetch,
+            None => return Ok(None),
         };
 
-        if fetch.completed {
-            return Ok(None);
-        }
-
         let msg = TmqSend::FetchBlock(MessageArgs {
-            req_id,
+            req_id: self.sender.req_id(),
             message_id: self.message_id,
         });
         let data = self.sender.send_recv(msg).await?;
@@ -150,20 +204,22 @@ impl WsMessageBase {
                 fetch.precision,
             );
 
-            for row in 0..raw.nrows() {
-                for col in 0..raw.ncols() {
-                    log::debug!("at ({}, {})", row, col);
-                    let v = unsafe { raw.get_ref_unchecked(row, col) };
-                    println!("({}, {}): {:?}", row, col, v);
-                }
+            let fields = fetch.fields();
+            raw.with_field_names(fields.iter().map(|f| f.name()));
+            if let Some(name) = &fetch.table_name {
+                raw.with_table_name(name.as_str());
             }
-            raw.with_field_names(fetch.fields().iter().map(|f| f.name()));
-            if let Some(name) = fetch.table_name {
-                raw.with_table_name(name);
-            }
-            return Ok(Some(raw));
+            let meta = BlockMeta {
+                table_name: fetch.table_name,
+                rows: fetch.rows,
+                fields,
+                precision: fetch.precision,
+                server_timing: Duration::from_nanos(fetch.timing),
+                elapsed: start.elapsed(),
+            };
+            return Ok(Some((meta, raw)));
         }
-        todo!()
+        unreachable!()
     }
     async fn fetch_json_meta(&self) -> Result<JsonMeta> {
         let req_id = self.sender.req_id();
@@ -174,7 +230,6 @@ impl WsMessageBase {
         let data = self.sender.send_recv(msg).await?;
         if let TmqRecvData::FetchJsonMeta { data } = data {
             let json: JsonMeta = serde_json::from_value(data)?;
-            dbg!(&json);
             return Ok(json);
             // dbg!(data);
             // Ok(data)
@@ -200,6 +255,9 @@ impl WsMessageBase {
 
 pub struct Meta(WsMessageBase);
 
+/// Message polled in batch, with meta or data blocks loaded.
+pub type BatchMessage = MessageSet<RawMeta, Vec<RawBlock>>;
+
 // impl WsMetaMessage {
 //     pub async fn as_raw_meta(&self) -> Result<RawMeta> {
 //         self.0.fetch_raw_meta().await
@@ -224,11 +282,143 @@ impl IsAsyncMeta for Meta {
 
 impl SyncOnAsync for Meta {}
 
-pub struct Data(WsMessageBase);
+/// Metadata of a block in data message.
+#[derive(Debug, Clone)]
+pub struct BlockMeta {
+    table_name: Option<String>,
+    rows: usize,
+    fields: Vec<Field>,
+    precision: Precision,
+    server_timing: Duration,
+    elapsed: Duration,
+}
+
+impl BlockMeta {
+    /// Table name of the block.
+    pub fn table_name(&self) -> Option<&str> {
+        self.table_name.as_deref()
+    }
+
+    /// Number of rows in the block.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Fields of the block.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Timestamp precision of the block.
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+
+    /// Time cost in server to fetch the block.
+    pub fn server_timing(&self) -> Duration {
+        self.server_timing
+    }
+
+    /// Time cost in client to fetch the block, including the network round trips.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Summary of a table in data message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSummary {
+    table_name: Option<String>,
+    blocks: usize,
+    rows: usize,
+}
+
+impl TableSummary {
+    /// Table name.
+    pub fn table_name(&self) -> Option<&str> {
+        self.table_name.as_deref()
+    }
+
+    /// Number of blocks of the table.
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    /// Number of rows of the table.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+}
+
+pub struct Data {
+    base: WsMessageBase,
+    completed: AtomicBool,
+}
 
 impl Data {
+    fn new(base: WsMessageBase) -> Self {
+        Self {
+            base,
+            completed: AtomicBool::new(false),
+        }
+    }
+
     pub async fn fetch_block(&self) -> Result<Option<RawBlock>> {
-        self.0.fetch_raw_block().await
+        Ok(self.fetch_block_with_meta().await?.map(|(_, raw)| raw))
+    }
+
+    /// Fetch next block with its metadata.
+    pub async fn fetch_block_with_meta(&self) -> Result<Option<(BlockMeta, RawBlock)>> {
+        if self.completed.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        let block = self.base.fetch_raw_block().await?;
+        if block.is_none() {
+            self.completed.store(true, Ordering::SeqCst);
+        }
+        Ok(block)
+    }
+
+    /// Summary of tables in the remaining blocks, in the order of first appearance.
+    ///
+    /// Only the metadata of blocks is fetched, the block data is skipped in server. So the
+    /// message is consumed after this, use [Data::fetch_block_with_meta] if the data is
+    /// also required.
+    pub async fn tables(&self) -> Result<Vec<TableSummary>> {
+        let mut tables: Vec<TableSummary> = Vec::new();
+        while !self.completed.load(Ordering::SeqCst) {
+            let fetch = match self.base.fetch_meta().await? {
+                Some(fetch) => fetch,
+                None => {
+                    self.completed.store(true, Ordering::SeqCst);
+                    break;
+                }
+            };
+            match tables
+                .iter_mut()
+                .find(|table| table.table_name == fetch.table_name)
+            {
+                Some(table) => {
+                    table.blocks += 1;
+                    table.rows += fetch.rows;
+                }
+                None => tables.push(TableSummary {
+                    table_name: fetch.table_name,
+                    blocks: 1,
+                    rows: fetch.rows,
+                }),
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Fetch all the remaining blocks of the message.
+    pub async fn fetch_all_blocks(&self) -> Result<Vec<RawBlock>> {
+        let mut blocks = Vec::new();
+        while let Some(block) = self.fetch_block().await? {
+            blocks.push(block);
+        }
+        Ok(blocks)
     }
 }
 
@@ -267,26 +457,58 @@ impl WsMessageSet {
     }
 }
 
+/// Max blocking time of a single poll request in the adapter.
+///
+/// Longer timeouts are split into multiple polls, so a stalled connection is detected in time.
+const MAX_POLL_BLOCKING_TIME: Duration = Duration::from_secs(5);
+
 impl Consumer {
+    /// Poll once with `blocking_time` to wait for messages in the adapter.
+    ///
+    /// The client waits for the response in `blocking_time` plus the request timeout,
+    /// a response not received in time is an error rather than an empty poll. The timed out
+    /// poll is kept, so its late response is returned by the next poll instead of being lost.
     pub(crate) async fn poll_timeout(
         &self,
-        timeout: Duration,
+        blocking_time: Duration,
     ) -> Result<Option<(Offset, MessageSet<Meta, Data>)>> {
-        let req_id = self.sender.req_id();
-        let action = TmqSend::Poll {
-            req_id,
-            blocking_time: timeout.as_millis() as _,
+        let pending = self.pending_poll.lock().unwrap().take();
+        let (req_id, mut rx) = match pending {
+            Some(pending) => pending,
+            None => {
+                let req_id = self.sender.req_id();
+                let action = TmqSend::Poll {
+                    req_id,
+                    blocking_time: blocking_time.as_millis() as _,
+                };
+                match self.sender.send(action).await {
+                    Ok(rx) => (req_id, rx),
+                    // The connection is broken, wait for reconnecting.
+                    Err(Error::SendError(_) | Error::SendTimeoutError(_)) => {
+                        self.wait_for_connection().await?;
+                        return Ok(None);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
         };
 
-        let data = self.sender.send_recv_timeout(action, timeout).await;
-        if data.is_err() {
-            let err = data.unwrap_err();
-            match err {
-                Error::QueryTimeout(_) => return Ok(None),
-                _ => return Err(err),
+        let data = tokio::select! {
+            _ = time::sleep(blocking_time + self.timeout) => {
+                log::debug!("poll {req_id} timed out, wait for it in next poll");
+                *self.pending_poll.lock().unwrap() = Some((req_id, rx));
+                return Err(Error::QueryTimeout(format!("poll {req_id}")));
             }
-        }
-        let data = data.unwrap();
+            message = &mut rx => message,
+        };
+        let data = match data {
+            Ok(data) => data?,
+            // The request is dropped when connection broken, wait for reconnecting.
+            Err(_) => {
+                self.wait_for_connection().await?;
+                return Ok(None);
+            }
+        };
 
         match data {
             TmqRecvData::Poll(TmqPoll {
@@ -298,6 +520,8 @@ impl Consumer {
                 message_type,
             }) => {
                 if have_message {
+                    let session = self.session.load(Ordering::SeqCst);
+                    *self.uncommitted.lock().unwrap() = Some((session, message_id));
                     let offset = Offset {
                         message_id,
                         database,
@@ -307,10 +531,13 @@ impl Consumer {
                     let message = WsMessageBase {
                         sender: self.sender.clone(),
                         message_id,
+                        filter: self.table_filter.clone(),
                     };
                     match message_type {
                         MessageType::Meta => Ok(Some((offset, MessageSet::Meta(Meta(message))))),
-                        MessageType::Data => Ok(Some((offset, MessageSet::Data(Data(message))))),
+                        MessageType::Data => {
+                            Ok(Some((offset, MessageSet::Data(Data::new(message)))))
+                        }
                         _ => unreachable!(),
                     }
                 } else {
@@ -320,6 +547,195 @@ impl Consumer {
             _ => unreachable!(),
         }
     }
+
+    /// Poll at most `max_messages` messages with meta or data blocks loaded.
+    ///
+    /// It waits for the first message in `timeout`, and then polls the messages that are
+    /// already available without blocking. The adapter keeps only the current message of
+    /// a consumer, so each message is loaded before polling the next one, use a
+    /// [BatchConsumer] to poll and fetch with concurrent connections.
+    pub async fn poll_batch(
+        &self,
+        max_messages: usize,
+        timeout: Timeout,
+    ) -> Result<Vec<(Offset, BatchMessage)>> {
+        let mut messages = Vec::with_capacity(max_messages);
+        let mut timeout = timeout;
+        while messages.len() < max_messages {
+            let (offset, message) =
+                match <Self as AsAsyncConsumer>::recv_timeout(self, timeout).await? {
+                    Some(message) => message,
+                    None => break,
+                };
+            let message = match message {
+                MessageSet::Meta(meta) => MessageSet::Meta(meta.0.fetch_raw_meta().await?),
+                MessageSet::Data(data) => MessageSet::Data(data.fetch_all_blocks().await?),
+            };
+            messages.push((offset, message));
+            timeout = Timeout::None;
+        }
+        Ok(messages)
+    }
+
+    /// Wait until the connection is available, error if closed or reconnecting failed.
+    async fn wait_for_connection(&self) -> Result<()> {
+        let mut state = self.state.clone();
+        loop {
+            let current = state.borrow().clone();
+            match current {
+                ConnState::Connected => return Ok(()),
+                ConnState::Reconnecting => state.changed().await.map_err(|_| Error::ConnClosed)?,
+                ConnState::Closed => return Err(Error::ConnClosed),
+                ConnState::Failed(err) => return Err(Error::ReconnectFailed(err)),
+            }
+        }
+    }
+
+    /// Close the consumer gracefully with the default timeout, see [Consumer::close_timeout].
+    pub async fn close(self) -> Result<()> {
+        let timeout = self.timeout;
+        self.close_timeout(timeout).await
+    }
+
+    /// Unsubscribe all topics and close the connection.
+    ///
+    /// Each request waits for server acknowledgement in `timeout`. Only the offsets committed
+    /// by [commit](AsAsyncConsumer::commit) are kept, so a restarted consumer in the same group
+    /// resumes from the last committed message, unless [Consumer::set_commit_on_close] is enabled.
+    pub async fn close_timeout(self, timeout: Duration) -> Result<()> {
+        let revoked = std::mem::take(&mut *self.assigned.lock().unwrap())
+            .into_iter()
+            .collect_vec();
+        if let Some(listener) = self.rebalance_listener.clone() {
+            if !revoked.is_empty() {
+                listener.on_revoked(&self, &revoked).await;
+            }
+        }
+
+        let uncommitted = self.uncommitted.lock().unwrap().take();
+        match uncommitted {
+            Some((session, message_id))
+                if self.commit_on_close && session == self.session.load(Ordering::SeqCst) =>
+            {
+                log::debug!("commit last polled message {message_id} before close");
+                let action = TmqSend::Commit(MessageArgs {
+                    req_id: self.sender.req_id(),
+                    message_id,
+                });
+                self.sender.send_recv_timeout(action, timeout).await?;
+            }
+            Some((_, message_id)) => {
+                log::debug!("message {message_id} is not committed before close");
+            }
+            None => (),
+        }
+
+        let topics = std::mem::take(&mut *self.topics.lock().unwrap());
+        if !topics.is_empty() {
+            let action = TmqSend::Unsubscribe {
+                req_id: self.sender.req_id(),
+            };
+            self.sender.send_recv_timeout(action, timeout).await?;
+            log::debug!("unsubscribed topics: {:?}", topics);
+        }
+
+        self.sender.send_recv(TmqSend::Close).await?;
+        Ok(())
+    }
+
+    /// Commit the last polled message on close, default is false.
+    ///
+    /// The message is committed even if it's not processed yet, which makes its delivery
+    /// at-most-once. It's skipped if the connection is reconnected after the message is polled.
+    pub fn set_commit_on_close(&mut self, commit: bool) -> &mut Self {
+        self.commit_on_close = commit;
+        self
+    }
+
+    /// Register a listener to be notified when vgroups are assigned to or revoked from this consumer.
+    pub fn set_rebalance_listener<L>(&mut self, listener: L) -> &mut Self
+    where
+        L: RebalanceListener<Consumer> + 'static,
+    {
+        self.rebalance_listener = Some(Arc::new(listener));
+        self
+    }
+
+    /// Interval to check vgroup assignments from server, default is 5s.
+    pub fn set_rebalance_interval(&mut self, interval: Duration) -> &mut Self {
+        self.rebalance_interval = interval;
+        self
+    }
+
+    /// Vgroup assignment of a subscribed topic.
+    pub async fn topic_assignment(&self, topic: &str) -> Result<Vec<Assignment>> {
+        let req_id = self.sender.req_id();
+        let action = TmqSend::Assignment(TopicArgs {
+            req_id,
+            topic: topic.to_string(),
+        });
+        match self.sender.send_recv(action).await? {
+            TmqRecvData::Assignment { assignment } => Ok(assignment),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Check vgroup assignments and notify the rebalance listener with changes.
+    ///
+    /// Assignments are fetched from server when the check interval elapsed or a message comes
+    /// from an unknown vgroup. If the server does not support it, the polled vgroups are used.
+    async fn check_rebalance(&self, polled: Option<&Offset>) {
+        let listener = match self.rebalance_listener.as_ref() {
+            Some(listener) => listener.clone(),
+            None => return,
+        };
+        let polled = polled.map(|offset| TopicVGroup::new(offset.topic(), offset.vgroup_id()));
+
+        let now = Instant::now();
+        let last_checked = *self.rebalance_checked.lock().unwrap();
+        let due = last_checked
+            .map(|last| now.duration_since(last) >= self.rebalance_interval)
+            .unwrap_or(true);
+        let unknown = polled
+            .as_ref()
+            .map(|vgroup| !self.assigned.lock().unwrap().contains(vgroup))
+            .unwrap_or(false);
+        if !due && !unknown {
+            return;
+        }
+        *self.rebalance_checked.lock().unwrap() = Some(now);
+
+        let old = self.assigned.lock().unwrap().clone();
+        let topics = self.topics.lock().unwrap().clone();
+        let mut new = BTreeSet::new();
+        for topic in &topics {
+            match self.topic_assignment(topic).await {
+                Ok(assignment) => new.extend(
+                    assignment
+                        .into_iter()
+                        .map(|a| TopicVGroup::new(topic, a.vgroup_id())),
+                ),
+                Err(err) => {
+                    log::warn!("get assignment of topic {topic} failed, use polled vgroups: {err}");
+                    new.extend(old.iter().filter(|v| &v.topic == topic).cloned());
+                }
+            }
+        }
+        new.extend(polled);
+
+        let revoked = old.difference(&new).cloned().collect_vec();
+        let assigned = new.difference(&old).cloned().collect_vec();
+        *self.assigned.lock().unwrap() = new;
+
+        if !revoked.is_empty() {
+            log::debug!("vgroups revoked: {revoked:?}");
+            listener.on_revoked(self, &revoked).await;
+        }
+        if !assigned.is_empty() {
+            log::debug!("vgroups assigned: {assigned:?}");
+            listener.on_assigned(self, &assigned).await;
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -337,13 +753,15 @@ impl AsAsyncConsumer for Consumer {
         topics: I,
     ) -> Result<()> {
         let req_id = self.sender.req_id();
+        let topics = topics.into_iter().map(Into::into).collect_vec();
         let action = TmqSend::Subscribe {
             req_id,
             req: self.tmq_conf.clone(),
-            topics: topics.into_iter().map(Into::into).collect_vec(),
+            topics: topics.clone(),
             conn: self.conn.clone(),
         };
         self.sender.send_recv(action).await?;
+        *self.topics.lock().unwrap() = topics;
         Ok(())
     }
 
@@ -357,15 +775,26 @@ impl AsAsyncConsumer for Consumer {
         )>,
         Self::Error,
     > {
-        match timeout {
-            Timeout::Never => loop {
-                if let Some(msg) = self.poll_timeout(Duration::MAX).await? {
-                    return Ok(Some(msg));
-                }
-            },
-            Timeout::None => self.poll_timeout(Duration::MAX).await,
-            Timeout::Duration(timeout) => self.poll_timeout(timeout).await,
-        }
+        let deadline = match timeout {
+            Timeout::Never => None,
+            Timeout::None => Some(Instant::now()),
+            // Treat overflowed deadline as never.
+            Timeout::Duration(timeout) => Instant::now().checked_add(timeout),
+        };
+        let message = loop {
+            let blocking_time = deadline.map_or(MAX_POLL_BLOCKING_TIME, |deadline| {
+                deadline
+                    .saturating_duration_since(Instant::now())
+                    .min(MAX_POLL_BLOCKING_TIME)
+            });
+            let message = self.poll_timeout(blocking_time).await?;
+            if message.is_some() || deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+                break message;
+            }
+        };
+        self.check_rebalance(message.as_ref().map(|(offset, _)| offset))
+            .await;
+        Ok(message)
 
         // let data = self
         //     .sender
@@ -421,9 +850,33 @@ impl AsAsyncConsumer for Consumer {
         });
 
         let _ = self.sender.send_recv(action).await?;
+
+        let mut uncommitted = self.uncommitted.lock().unwrap();
+        if matches!(*uncommitted, Some((_, id)) if id <= offset.message_id) {
+            *uncommitted = None;
+        }
         Ok(())
     }
 
+    async fn unsubscribe(self) -> StdResult<(), Self::Error> {
+        self.close().await
+    }
+
+    async fn assignments(&self) -> Option<Vec<(String, Vec<Assignment>)>> {
+        let topics = self.topics.lock().unwrap().clone();
+        let mut assignments = Vec::with_capacity(topics.len());
+        for topic in &topics {
+            match self.topic_assignment(topic).await {
+                Ok(assignment) => assignments.push((topic.clone(), assignment)),
+                Err(err) => {
+                    log::warn!("get assignment of topic {topic} failed: {err}");
+                    return None;
+                }
+            }
+        }
+        Some(assignments)
+    }
+
     fn default_timeout(&self) -> Timeout {
         Timeout::from_secs(5)
     }
@@ -455,6 +908,14 @@ impl AsConsumer for Consumer {
     fn commit(&self, offset: Self::Offset) -> StdResult<(), Self::Error> {
         block_in_place_or_global(<Consumer as AsAsyncConsumer>::commit(&self, offset))
     }
+
+    fn assignments(&self) -> Option<Vec<(String, Vec<Assignment>)>> {
+        block_in_place_or_global(<Consumer as AsAsyncConsumer>::assignments(&self))
+    }
+
+    fn unsubscribe(self) -> StdResult<(), Self::Error> {
+        block_in_place_or_global(<Consumer as AsAsyncConsumer>::unsubscribe(self))
+    }
 }
 
 impl TmqBuilder {
@@ -475,14 +936,108 @@ impl TmqBuilder {
             offset_reset,
         };
 
-        Ok(Self { info, conf })
+        let reconnect_retries = match dsn.params.get("reconnect.retries") {
+            Some(v) => v.parse().map_err(|_| {
+                DsnError::InvalidParam("reconnect.retries".to_string(), v.to_string())
+            })?,
+            None => 5,
+        };
+        let reconnect_backoff = match dsn.params.get("reconnect.backoff.ms") {
+            Some(v) => Duration::from_millis(v.parse().map_err(|_| {
+                DsnError::InvalidParam("reconnect.backoff.ms".to_string(), v.to_string())
+            })?),
+            None => Duration::from_millis(500),
+        };
+        let resubscribe_timeout = match dsn.params.get("resubscribe.timeout.ms") {
+            Some(v) => Duration::from_millis(v.parse().map_err(|_| {
+                DsnError::InvalidParam("resubscribe.timeout.ms".to_string(), v.to_string())
+            })?),
+            None => Duration::from_secs(5),
+        };
+
+        let table_filter = TableFilter::from_patterns(
+            dsn.params.get("table.include").map(String::as_str),
+            dsn.params.get("table.exclude").map(String::as_str),
+        );
+        let fetch_concurrency = match dsn.params.get("fetch.concurrency") {
+            Some(v) => v.parse().map_err(|_| {
+                DsnError::InvalidParam("fetch.concurrency".to_string(), v.to_string())
+            })?,
+            None => 1,
+        };
+        let batch_size = match dsn.params.get("batch.size") {
+            Some(v) => v
+                .parse()
+                .map_err(|_| DsnError::InvalidParam("batch.size".to_string(), v.to_string()))?,
+            None => 100,
+        };
+
+        Ok(Self {
+            info,
+            conf,
+            reconnect_retries,
+            reconnect_backoff,
+            resubscribe_timeout,
+            table_filter,
+            fetch_concurrency,
+            batch_size,
+        })
+    }
+
+    /// Max retries to reconnect when the websocket connection is broken, default is 5.
+    pub fn reconnect_retries(mut self, retries: u32) -> Self {
+        self.reconnect_retries = retries;
+        self
     }
 
-    async fn build_consumer(&self) -> Result<Consumer> {
+    /// Initial backoff between reconnect retries, doubled for each retry. Default is 500ms.
+    pub fn reconnect_backoff(mut self, backoff: Duration) -> Self {
+        self.reconnect_backoff = backoff;
+        self
+    }
+
+    /// Max time to wait for the subscription to be restored after reconnected, default is 5s.
+    pub fn resubscribe_timeout(mut self, timeout: Duration) -> Self {
+        self.resubscribe_timeout = timeout;
+        self
+    }
+
+    /// Only fetch blocks of tables passing the filter, other blocks in data messages are skipped.
+    ///
+    /// It could also be set by `table.include` and `table.exclude` dsn params, with comma separated patterns.
+    pub fn table_filter(mut self, filter: TableFilter) -> Self {
+        self.table_filter = filter;
+        self
+    }
+
+    /// Connections of a [BatchConsumer] to poll and fetch concurrently, default is 1.
+    ///
+    /// It could also be set by `fetch.concurrency` dsn param.
+    pub fn fetch_concurrency(mut self, concurrency: usize) -> Self {
+        self.fetch_concurrency = concurrency;
+        self
+    }
+
+    /// Max messages in a batch of [BatchConsumer], default is 100.
+    ///
+    /// It could also be set by `batch.size` dsn param.
+    pub fn batch_size(mut self, size: usize) -> Self {
+        self.batch_size = size;
+        self
+    }
+
+    /// Build a [BatchConsumer] with `fetch_concurrency` connections in the group.
+    pub async fn build_batch_consumer(&self) -> Result<BatchConsumer> {
+        let consumers = futures::future::try_join_all(
+            (0..self.fetch_concurrency.max(1)).map(|_| self.build_consumer()),
+        )
+        .await?;
+        Ok(BatchConsumer::from_consumers(consumers, self.batch_size))
+    }
+
+    pub(crate) async fn build_consumer(&self) -> Result<Consumer> {
         let url = self.info.to_tmq_url();
-        // let (ws, _) = futures::executor::block_on(connect_async(url))?;
-        let (ws, _) = connect_async(url).await?;
-        let (mut sender, mut reader) = ws.split();
+        let (ws, _) = connect_async(url.as_str()).await?;
 
         use std::collections::hash_map::RandomState;
         let queries = Arc::new(HashMap::<ReqId, tokio::sync::oneshot::Sender<_>>::new(
@@ -490,181 +1045,320 @@ impl TmqBuilder {
             RandomState::new(),
         ));
 
-        let queries_sender = queries.clone();
-
-        let (ws, mut msg_recv) = tokio::sync::mpsc::channel::<Message>(100);
-        let ws2 = ws.clone();
+        let (sender, msg_recv) = tokio::sync::mpsc::channel::<Message>(100);
 
         // Connection watcher
-        let (tx, mut rx) = watch::channel(false);
-        let mut close_listener = rx.clone();
+        let (close_signal, close_listener) = watch::channel(false);
+        let (state_sender, state) = watch::channel(ConnState::Connected);
+
+        let req_id = Arc::new(AtomicU64::new(1));
+        let topics = Arc::new(Mutex::new(Vec::new()));
+        let session = Arc::new(AtomicU64::new(0));
+        let conn = self.info.to_conn_request();
+
+        let connection = TmqConnection {
+            url,
+            queries: queries.clone(),
+            msg_recv,
+            close_listener,
+            state: state_sender,
+            resubscribe: Resubscribe {
+                req_id: req_id.clone(),
+                conn: conn.clone(),
+                conf: self.conf.clone(),
+                topics: topics.clone(),
+            },
+            retries: self.reconnect_retries,
+            backoff: self.reconnect_backoff,
+            resubscribe_timeout: self.resubscribe_timeout,
+            session: session.clone(),
+        };
+        tokio::spawn(connection.run(ws));
 
-        tokio::spawn(async move {
-            let mut interval = time::interval(Duration::from_secs(1));
+        Ok(Consumer {
+            conn,
+            tmq_conf: self.conf.clone(),
+            sender: WsTmqSender {
+                req_id,
+                queries,
+                sender,
+                timeout: Duration::from_secs(5),
+            },
+            // fetches,
+            close_signal,
+            state,
+            timeout: Duration::from_secs(5),
+            topics,
+            assigned: Mutex::new(BTreeSet::new()),
+            rebalance_listener: None,
+            rebalance_interval: Duration::from_secs(5),
+            rebalance_checked: Mutex::new(None),
+            uncommitted: Mutex::new(None),
+            pending_poll: Mutex::new(None),
+            session,
+            commit_on_close: false,
+            table_filter: Arc::new(self.table_filter.clone()),
+        })
+    }
+}
 
-            loop {
-                tokio::select! {
-                    _ = interval.tick() => {
-                        log::trace!("Check websocket message sender alive");
-                    }
-                    Some(msg) = msg_recv.recv() => {
-                        if msg.is_close() {
-                            sender.send(msg).await.unwrap();
-                            sender.close().await.unwrap();
-                            break;
-                        }
-                        sender.send(msg).await.unwrap();
-                        log::trace!("send done");
-                    }
-                    _ = rx.changed() => {
-                        let _= sender.send(Message::Close(None)).await;
-                        sender.close().await.unwrap();
-                        log::debug!("close tmq sender");
-                        break;
-                    }
+type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// State of the websocket connection behind a consumer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConnState {
+    Connected,
+    Reconnecting,
+    Closed,
+    /// Reconnecting failed after all retries, with the last error message.
+    Failed(String),
+}
+
+/// How a websocket connection ends.
+enum ConnEnd {
+    /// Closed by the consumer.
+    Closed,
+    /// Broken by network or server.
+    Broken,
+}
+
+/// Subscription to restore after reconnected.
+struct Resubscribe {
+    req_id: Arc<AtomicU64>,
+    conn: WsConnReq,
+    conf: TmqInit,
+    topics: Arc<Mutex<Vec<String>>>,
+}
+
+impl Resubscribe {
+    fn action(&self) -> Option<TmqSend> {
+        let topics = self.topics.lock().unwrap().clone();
+        if topics.is_empty() {
+            return None;
+        }
+        Some(TmqSend::Subscribe {
+            req_id: self
+                .req_id
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst),
+            conn: self.conn.clone(),
+            req: self.conf.clone(),
+            topics,
+        })
+    }
+}
+
+/// The background task that serves websocket messages for a consumer, and reconnects when broken.
+struct TmqConnection {
+    url: String,
+    queries: WsTmqAgent,
+    msg_recv: tokio::sync::mpsc::Receiver<Message>,
+    close_listener: watch::Receiver<bool>,
+    state: watch::Sender<ConnState>,
+    resubscribe: Resubscribe,
+    retries: u32,
+    backoff: Duration,
+    resubscribe_timeout: Duration,
+    /// Increased after each reconnection.
+    session: Arc<AtomicU64>,
+}
+
+impl TmqConnection {
+    async fn run(mut self, ws: WsStream) {
+        let mut ws = ws;
+        loop {
+            if let ConnEnd::Closed = self.serve(ws).await {
+                let _ = self.state.send(ConnState::Closed);
+                break;
+            }
+
+            // Mark reconnecting before dropping the in-flight requests,
+            // so that the waiters could see it.
+            let _ = self.state.send(ConnState::Reconnecting);
+            self.queries.clear();
+
+            match self.reconnect().await {
+                Ok(Some(new)) => {
+                    log::info!("tmq connection recovered");
+                    self.session.fetch_add(1, Ordering::SeqCst);
+                    let _ = self.state.send(ConnState::Connected);
+                    ws = new;
+                }
+                Ok(None) => {
+                    let _ = self.state.send(ConnState::Closed);
+                    break;
+                }
+                Err(err) => {
+                    log::error!("tmq reconnect failed: {err}");
+                    let _ = self.state.send(ConnState::Failed(err.to_string()));
+                    break;
                 }
             }
-        });
+        }
+        log::info!("end consumer loop");
+    }
 
-        tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    Some(message) = reader.next() => {
-                        match message {
-                            Ok(message) => match message {
-                                Message::Text(text) => {
-                                    log::debug!("json response: {}", text);
-                                    let v: TmqRecv = serde_json::from_str(&text).unwrap();
-                                    let (req_id, recv, ok) = v.ok();
-                                    match &recv {
-                                        TmqRecvData::Subscribe => {
-                                            log::debug!("subscribe with: {:?}", req_id);
-
-                                            if let Some((_, sender)) = queries_sender.remove(&req_id)
-                                            {
-                                                sender.send(ok.map(|_|recv)).unwrap();
-                                            }  else {
-                                                log::warn!("subscribe message received but no receiver alive");
-                                            }
-                                        },
-                                        TmqRecvData::Poll(poll) => {
-                                            if let Some((_, sender)) = queries_sender.remove(&req_id)
-                                            {
-                                                sender.send(ok.map(|_|recv)).unwrap();
-                                            }  else {
-                                                log::warn!("poll message received but no receiver alive");
-                                            }
-                                        },
-                                        TmqRecvData::FetchJsonMeta { data }=> {
-                                            log::debug!("fetch json meta data: {:?}", data);
-                                            if let Some((_, sender)) = queries_sender.remove(&req_id)
-                                            {
-                                                sender.send(ok.map(|_|recv)).unwrap();
-                                            }  else {
-                                                log::warn!("poll message received but no receiver alive");
-                                            }
-                                        }
-                                        TmqRecvData::FetchRaw { meta: _ }=> {
-                                            if let Some((_, sender)) = queries_sender.remove(&req_id)
-                                            {
-                                                sender.send(ok.map(|_|recv)).unwrap();
-                                            }  else {
-                                                log::warn!("poll message received but no receiver alive");
-                                            }
-                                        }
-                                        TmqRecvData::Commit=> {
-                                            log::debug!("commit done: {:?}", recv);
-                                            if let Some((_, sender)) = queries_sender.remove(&req_id)
-                                            {
-                                                sender.send(ok.map(|_|recv)).unwrap();
-                                            }  else {
-                                                log::warn!("poll message received but no receiver alive");
-                                            }
-                                        }
-                                        TmqRecvData::Fetch(fetch)=> {
-                                            log::debug!("fetch done: {:?}", fetch);
-                                            if let Some((_, sender)) = queries_sender.remove(&req_id)
-                                            {
-                                                sender.send(ok.map(|_|recv)).unwrap();
-                                            }  else {
-                                                log::warn!("poll message received but no receiver alive");
-                                            }
-                                        }
-                                        _ => unreachable!("unknown tmq response"),
-                                    }
-                                }
-                                Message::Binary(data) => {
-                                    // writeUint64(message.buffer, req.ReqID)
-                                    // writeUint64(message.buffer, req.MessageID)
-                                    // writeUint64(message.buffer, TMQRawMetaMessage)
-                                    // writeUint32(message.buffer, length)
-                                    // writeUint16(message.buffer, metaType)
-                                    let mut bytes = Bytes::from(data);
-                                    let part = bytes.slice(24..);
-                                    // dbg!(&bytes);
-                                    use bytes::Buf;
-                                    let timing = bytes.get_u64_le();
-                                    let req_id = bytes.get_u64_le();
-                                    let message_id = bytes.get_u64_le();
-
-
-                                    log::debug!("[{:.2}ms] receive binary message with req_id {} message_id {}",
-                                        Duration::from_nanos(timing).as_secs_f64() / 1000.,
-                                        req_id, message_id);
-
-                                    if let Some((_, sender)) = queries_sender.remove(&req_id)
-                                    {
-                                        sender.send(Ok(TmqRecvData::Bytes(part))).unwrap();
-                                    }  else {
-                                        log::warn!("poll message received but no receiver alive");
-                                    }
+    /// Reconnect with backoff, returns `None` if the consumer is closed in the meantime.
+    async fn reconnect(&mut self) -> Result<Option<WsStream>> {
+        let mut backoff = self.backoff;
+        let mut retry = 0;
+        loop {
+            retry += 1;
+            log::warn!(
+                "tmq connection is broken, reconnect in {backoff:?} ({retry}/{})",
+                self.retries
+            );
+            tokio::select! {
+                _ = time::sleep(backoff) => {}
+                _ = self.close_listener.changed() => {
+                    return Ok(None);
+                }
+            }
+            let err = match self.try_reconnect().await {
+                Ok(ws) => return Ok(Some(ws)),
+                Err(err) => err,
+            };
+            if retry >= self.retries {
+                return Err(err);
+            }
+            log::warn!("tmq reconnect failed: {err}");
+            backoff = (backoff * 2).min(Duration::from_secs(30));
+        }
+    }
 
+    /// Connect and restore the subscription, so that consuming continues from the committed offsets.
+    async fn try_reconnect(&self) -> Result<WsStream> {
+        let (mut ws, _) = connect_async(self.url.as_str()).await?;
+        let action = match self.resubscribe.action() {
+            Some(action) => action,
+            None => return Ok(ws),
+        };
+        let req_id = action.req_id();
+        ws.send(action.to_msg()).await?;
 
+        let sleep = time::sleep(self.resubscribe_timeout);
+        tokio::pin!(sleep);
+        loop {
+            let message = tokio::select! {
+                _ = &mut sleep => {
+                    return Err(Error::QueryTimeout("resubscribe".to_string()));
+                }
+                message = ws.next() => message,
+            };
+            match message {
+                Some(Ok(Message::Text(text))) => {
+                    let recv: TmqRecv = serde_json::from_str(&text)?;
+                    let (id, _, ok) = recv.ok();
+                    if id == req_id {
+                        ok?;
+                        log::debug!("resubscribed with req_id {req_id}");
+                        return Ok(ws);
+                    }
+                }
+                Some(Ok(Message::Ping(bytes))) => ws.send(Message::Pong(bytes)).await?,
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err.into()),
+                None => return Err(Error::ConnClosed),
+            }
+        }
+    }
+
+    /// Serve messages on a websocket connection until it's closed or broken.
+    async fn serve(&mut self, ws: WsStream) -> ConnEnd {
+        let (mut sender, mut reader) = ws.split();
+        loop {
+            tokio::select! {
+                Some(msg) = self.msg_recv.recv() => {
+                    if msg.is_close() {
+                        let _ = sender.send(msg).await;
+                        let _ = sender.close().await;
+                        return ConnEnd::Closed;
+                    }
+                    if let Err(err) = sender.send(msg).await {
+                        log::error!("sending cause error: {err:?}");
+                        return ConnEnd::Broken;
+                    }
+                    log::trace!("send done");
+                }
+                message = reader.next() => {
+                    match message {
+                        Some(Ok(message)) => match message {
+                            Message::Text(text) => {
+                                log::debug!("json response: {}", text);
+                                let v: TmqRecv = match serde_json::from_str(&text) {
+                                    Ok(v) => v,
+                                    Err(err) => {
+                                        log::warn!("skip invalid json response: {err}");
+                                        continue;
+                                    }
+                                };
+                                let (req_id, recv, ok) = v.ok();
+                                if let Some((_, sender)) = self.queries.remove(&req_id) {
+                                    let _ = sender.send(ok.map(|_| recv));
+                                } else {
+                                    log::warn!("{recv:?} received but no receiver alive");
                                 }
-                                Message::Close(_) => {
-                                    log::warn!("websocket connection is closed (unexpected?)");
-                                    break;
-                                }
-                                Message::Ping(bytes) => {
-                                    ws2.send(Message::Pong(bytes)).await.unwrap();
-                                }
-                                Message::Pong(_) => {
-                                    // do nothing
-                                    log::warn!("received (unexpected) pong message, do nothing");
+                            }
+                            Message::Binary(data) => {
+                                // writeUint64(message.buffer, req.ReqID)
+                                // writeUint64(message.buffer, req.MessageID)
+                                // writeUint64(message.buffer, TMQRawMetaMessage)
+                                // writeUint32(message.buffer, length)
+                                // writeUint16(message.buffer, metaType)
+                                let mut bytes = Bytes::from(data);
+                                let part = bytes.slice(24..);
+                                use bytes::Buf;
+                                let timing = bytes.get_u64_le();
+                                let req_id = bytes.get_u64_le();
+                                let message_id = bytes.get_u64_le();
+
+                                log::debug!("[{:.2}ms] receive binary message with req_id {} message_id {}",
+                                    Duration::from_nanos(timing).as_secs_f64() / 1000.,
+                                    req_id, message_id);
+
+                                if let Some((_, sender)) = self.queries.remove(&req_id) {
+                                    let _ = sender.send(Ok(TmqRecvData::Bytes(part)));
+                                } else {
+                                    log::warn!("binary message received but no receiver alive");
                                 }
-                                Message::Frame(frame) => {
-                                    // do nothing
-                                    log::warn!("received (unexpected) frame message, do nothing");
-                                    log::debug!("* frame data: {frame:?}");
+                            }
+                            Message::Close(_) => {
+                                log::warn!("websocket connection is closed by server");
+                                return ConnEnd::Broken;
+                            }
+                            Message::Ping(bytes) => {
+                                if let Err(err) = sender.send(Message::Pong(bytes)).await {
+                                    log::error!("sending pong cause error: {err:?}");
+                                    return ConnEnd::Broken;
                                 }
-                            },
-                            Err(err) => {
-                                log::error!("receiving cause error: {err:?}");
-                                break;
                             }
+                            Message::Pong(_) => {
+                                // do nothing
+                                log::warn!("received (unexpected) pong message, do nothing");
+                            }
+                            Message::Frame(frame) => {
+                                // do nothing
+                                log::warn!("received (unexpected) frame message, do nothing");
+                                log::debug!("* frame data: {frame:?}");
+                            }
+                        },
+                        Some(Err(err)) => {
+                            log::error!("receiving cause error: {err:?}");
+                            return ConnEnd::Broken;
+                        }
+                        None => {
+                            log::error!("websocket stream ended unexpectedly");
+                            return ConnEnd::Broken;
                         }
                     }
-                    _ = close_listener.changed() => {
-                        log::info!("close reader task");
-                        break
-                    }
+                }
+                _ = self.close_listener.changed() => {
+                    let _ = sender.send(Message::Close(None)).await;
+                    let _ = sender.close().await;
+                    log::debug!("close tmq connection");
+                    return ConnEnd::Closed;
                 }
             }
-            log::info!("end consumer loop");
-        });
-        Ok(Consumer {
-            conn: self.info.to_conn_request(),
-            tmq_conf: self.conf.clone(),
-            sender: WsTmqSender {
-                req_id: Arc::new(AtomicU64::new(1)),
-                queries,
-                sender: ws,
-                timeout: Duration::from_secs(5),
-            },
-            // fetches,
-            close_signal: tx,
-            timeout: Duration::from_secs(5),
-        })
+        }
     }
 }
 
@@ -673,7 +1367,20 @@ pub struct Consumer {
     tmq_conf: TmqInit,
     sender: WsTmqSender,
     close_signal: watch::Sender<bool>,
+    state: watch::Receiver<ConnState>,
     timeout: Duration,
+    topics: Arc<Mutex<Vec<String>>>,
+    assigned: Mutex<BTreeSet<TopicVGroup>>,
+    rebalance_listener: Option<Arc<dyn RebalanceListener<Consumer>>>,
+    rebalance_interval: Duration,
+    rebalance_checked: Mutex<Option<Instant>>,
+    /// Last polled message with the connection session it's polled in.
+    uncommitted: Mutex<Option<(u64, MessageId)>>,
+    /// Poll request timed out but not responded yet.
+    pending_poll: Mutex<Option<(ReqId, WsTmqReceiver)>>,
+    session: Arc<AtomicU64>,
+    commit_on_close: bool,
+    table_filter: Arc<TableFilter>,
 }
 
 impl Drop for Consumer {
@@ -725,6 +1432,10 @@ pub enum Error {
     TaosError(#[from] taos_error::Error),
     #[error("Receive timeout in {0}")]
     QueryTimeout(String),
+    #[error("tmq connection is closed")]
+    ConnClosed,
+    #[error("tmq reconnect failed: {0}")]
+    ReconnectFailed(String),
 }
 
 unsafe impl Send for Error {}
@@ -751,7 +1462,600 @@ type Result<T> = std::result::Result<T, Error>;
 mod tests {
     use std::time::Duration;
 
-    use super::{TaosBuilder, TmqBuilder};
+    use super::{
+        ConnState, Consumer, Error, ReqId, Resubscribe, TableFilter, TaosBuilder, TmqBuilder,
+        TmqConnection, TmqInit, TmqRecv, TmqRecvData, WsConnReq, WsTmqAgent, WsTmqSender,
+    };
+    use futures::{SinkExt, StreamExt};
+    use scc::HashMap;
+    use std::collections::BTreeSet;
+    use std::result::Result as StdResult;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::watch;
+    use tokio_tungstenite::tungstenite::protocol::Message;
+
+    /// Fake adapter behind a [mock_consumer], requests are served step by step in tests.
+    struct Adapter {
+        requests: tokio::sync::mpsc::Receiver<Message>,
+        queries: WsTmqAgent,
+    }
+
+    impl Adapter {
+        /// Receive next request as json, `None` for websocket close.
+        async fn request(&mut self) -> Option<serde_json::Value> {
+            match self.requests.recv().await.expect("consumer is dropped") {
+                Message::Text(text) => Some(serde_json::from_str(&text).unwrap()),
+                _ => None,
+            }
+        }
+
+        /// Receive next request of `action`, returns its args.
+        async fn expect(&mut self, action: &str) -> serde_json::Value {
+            let request = self.request().await.expect("expect a json request");
+            assert_eq!(request["action"], action, "unexpected request: {request}");
+            request["args"].clone()
+        }
+
+        fn respond(&self, req_id: ReqId, data: StdResult<TmqRecvData, taos_error::Error>) {
+            let (_, sender) = self.queries.remove(&req_id).expect("no receiver alive");
+            let _ = sender.send(data);
+        }
+
+        /// Reply to the request with `args` by a json response like the adapter does.
+        fn reply(&self, args: &serde_json::Value, action: &str, data: serde_json::Value) {
+            self.reply_code(args, action, 0, data);
+        }
+
+        fn reply_code(
+            &self,
+            args: &serde_json::Value,
+            action: &str,
+            code: i32,
+            data: serde_json::Value,
+        ) {
+            let mut response = serde_json::json!({
+                "code": code,
+                "message": if code == 0 { "" } else { "mock error" },
+                "action": action,
+                "req_id": args["req_id"],
+            });
+            if let serde_json::Value::Object(data) = data {
+                response.as_object_mut().unwrap().extend(data);
+            }
+            let (req_id, data, ok) = serde_json::from_value::<TmqRecv>(response).unwrap().ok();
+            self.respond(req_id, ok.map(|_| data));
+        }
+    }
+
+    /// A consumer subscribed to topic `t1`, connected to a fake adapter instead of websocket.
+    fn mock_consumer() -> (Consumer, Adapter) {
+        use std::collections::hash_map::RandomState;
+        let queries: WsTmqAgent = Arc::new(HashMap::new(100, RandomState::new()));
+        let (sender, requests) = tokio::sync::mpsc::channel(100);
+        let (close_signal, _) = watch::channel(false);
+        let (_, state) = watch::channel(ConnState::Connected);
+        let consumer = Consumer {
+            conn: WsConnReq::new("root", "taosdata"),
+            tmq_conf: TmqInit::default(),
+            sender: WsTmqSender {
+                req_id: Arc::new(AtomicU64::new(1)),
+                sender,
+                queries: queries.clone(),
+                timeout: Duration::from_secs(1),
+            },
+            close_signal,
+            state,
+            timeout: Duration::from_secs(1),
+            topics: Arc::new(Mutex::new(vec!["t1".to_string()])),
+            assigned: Mutex::new(BTreeSet::new()),
+            rebalance_listener: None,
+            rebalance_interval: Duration::from_secs(5),
+            rebalance_checked: Mutex::new(None),
+            uncommitted: Mutex::new(None),
+            pending_poll: Mutex::new(None),
+            session: Arc::new(AtomicU64::new(0)),
+            commit_on_close: false,
+            table_filter: Arc::new(TableFilter::new()),
+        };
+        (consumer, Adapter { requests, queries })
+    }
+
+    /// Serve close requests, returns the committed message id if any.
+    async fn serve_close(mut adapter: Adapter, unsubscribe_code: i32) -> Option<u64> {
+        let mut committed = None;
+        let mut args = adapter.request().await.expect("expect a json request");
+        if args["action"] == "commit" {
+            committed = args["args"]["message_id"].as_u64();
+            adapter.reply(&args["args"], "commit", serde_json::json!({}));
+            args = adapter.request().await.expect("expect a json request");
+        }
+        assert_eq!(args["action"], "unsubscribe");
+        adapter.reply_code(
+            &args["args"],
+            "unsubscribe",
+            unsubscribe_code,
+            serde_json::json!({}),
+        );
+        if unsubscribe_code == 0 {
+            assert!(adapter.request().await.is_none(), "expect websocket close");
+        }
+        committed
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_close_without_commit() -> anyhow::Result<()> {
+        let (consumer, adapter) = mock_consumer();
+        *consumer.uncommitted.lock().unwrap() = Some((0, 7));
+        let adapter = tokio::spawn(serve_close(adapter, 0));
+        consumer.close().await?;
+        assert_eq!(adapter.await?, None);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_close_with_commit() -> anyhow::Result<()> {
+        let (mut consumer, adapter) = mock_consumer();
+        consumer.set_commit_on_close(true);
+        *consumer.uncommitted.lock().unwrap() = Some((0, 7));
+        let adapter = tokio::spawn(serve_close(adapter, 0));
+        consumer.close().await?;
+        assert_eq!(adapter.await?, Some(7));
+
+        // Message polled before reconnection is not committed.
+        let (mut consumer, adapter) = mock_consumer();
+        consumer.set_commit_on_close(true);
+        *consumer.uncommitted.lock().unwrap() = Some((0, 8));
+        consumer.session.fetch_add(1, Ordering::SeqCst);
+        let adapter = tokio::spawn(serve_close(adapter, 0));
+        consumer.close().await?;
+        assert_eq!(adapter.await?, None);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unsubscribe_error() -> anyhow::Result<()> {
+        use taos_query::tmq::AsAsyncConsumer;
+        let (consumer, adapter) = mock_consumer();
+        let adapter = tokio::spawn(serve_close(adapter, 0x2603));
+        let err = consumer.unsubscribe().await.unwrap_err();
+        assert_eq!(err.errstr(), "mock error");
+        adapter.await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_poll_timeout() -> anyhow::Result<()> {
+        let (mut consumer, mut adapter) = mock_consumer();
+        consumer.timeout = Duration::from_millis(100);
+
+        // The adapter does not respond in time.
+        let err = consumer
+            .poll_timeout(Duration::ZERO)
+            .await
+            .err()
+            .expect("poll should be timed out");
+        assert!(matches!(err, Error::QueryTimeout(_)), "{err}");
+        let args = adapter.expect("poll").await;
+
+        // The late response is returned by the next poll, without polling again.
+        adapter.reply(
+            &args,
+            "poll",
+            serde_json::json!({
+                "have_message": true,
+                "message_id": 3,
+                "database": "db1",
+                "topic": "t1",
+                "vgroup_id": 2,
+                "message_type": 1,
+            }),
+        );
+        let (offset, _) = consumer
+            .poll_timeout(Duration::ZERO)
+            .await?
+            .expect("the late message");
+        assert_eq!(offset.message_id, 3);
+        assert!(adapter.requests.try_recv().is_err(), "no more poll is sent");
+
+        // Polls normally after that.
+        let poll = tokio::spawn(async move {
+            let args = adapter.expect("poll").await;
+            adapter.reply(&args, "poll", serde_json::json!({ "have_message": false }));
+        });
+        assert!(consumer.poll_timeout(Duration::ZERO).await?.is_none());
+        poll.await?;
+        Ok(())
+    }
+
+    /// Poll response of message `message_id`, `message_type` 1 for data and 2 for meta.
+    fn polled(message_id: u64, message_type: u8) -> serde_json::Value {
+        serde_json::json!({
+            "have_message": true,
+            "message_id": message_id,
+            "database": "db1",
+            "topic": "t1",
+            "vgroup_id": 2,
+            "message_type": message_type,
+        })
+    }
+
+    /// Fetch response of a block with `ts` and `v` columns.
+    fn fetched(table: &str, rows: usize) -> serde_json::Value {
+        serde_json::json!({
+            "completed": false,
+            "table_name": table,
+            "fields_count": 2,
+            "fields_names": ["ts", "v"],
+            "fields_types": [9, 4],
+            "fields_lengths": [8, 4],
+            "precision": 0,
+            "rows": rows,
+            "timing": 100,
+        })
+    }
+
+    /// Serve fetches of data message `message_id`, one block of `rows` for each table.
+    async fn serve_fetch(adapter: &mut Adapter, message_id: u64, tables: &[(&str, usize)]) {
+        use taos_query::common::views::views_to_raw_block;
+        use taos_query::common::ColumnView;
+
+        for (table, rows) in tables {
+            let args = adapter.expect("fetch").await;
+            assert_eq!(args["message_id"], message_id);
+            adapter.reply(&args, "fetch", fetched(table, *rows));
+            let args = adapter.expect("fetch_block").await;
+            assert_eq!(args["message_id"], message_id);
+            let views = vec![
+                ColumnView::from_millis_timestamp((0..*rows as i64).map(Some).collect()),
+                ColumnView::from_ints((0..*rows as i32).map(Some).collect()),
+            ];
+            let req_id = args["req_id"].as_u64().unwrap();
+            adapter.respond(
+                req_id,
+                Ok(TmqRecvData::Bytes(views_to_raw_block(&views).into())),
+            );
+        }
+        let args = adapter.expect("fetch").await;
+        assert_eq!(args["message_id"], message_id);
+        adapter.reply(&args, "fetch", serde_json::json!({ "completed": true }));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_poll_batch() -> anyhow::Result<()> {
+        use taos_query::tmq::{MessageSet, Timeout};
+
+        let (consumer, mut adapter) = mock_consumer();
+        let adapter = tokio::spawn(async move {
+            // Each message is loaded before the next poll.
+            let args = adapter.expect("poll").await;
+            adapter.reply(&args, "poll", polled(1, 1));
+            serve_fetch(&mut adapter, 1, &[("tb1", 2), ("tb2", 1)]).await;
+
+            let args = adapter.expect("poll").await;
+            adapter.reply(&args, "poll", polled(2, 2));
+            let args = adapter.expect("fetch_raw").await;
+            assert_eq!(args["message_id"], 2);
+            let mut meta = 3u64.to_le_bytes().to_vec();
+            meta.extend_from_slice(b"meta");
+            adapter.respond(
+                args["req_id"].as_u64().unwrap(),
+                Ok(TmqRecvData::Bytes(meta.into())),
+            );
+
+            let args = adapter.expect("poll").await;
+            adapter.reply(&args, "poll", polled(3, 1));
+            serve_fetch(&mut adapter, 3, &[("tb3", 3)]).await;
+            adapter
+        });
+
+        let messages = consumer.poll_batch(3, Timeout::from_millis(1000)).await?;
+        assert_eq!(messages.len(), 3);
+        let ids: Vec<_> = messages
+            .iter()
+            .map(|(offset, _)| offset.message_id)
+            .collect();
+        assert_eq!(ids, [1, 2, 3]);
+        match &messages[0].1 {
+            MessageSet::Data(blocks) => {
+                let tables: Vec<_> = blocks.iter().map(|b| b.table_name()).collect();
+                assert_eq!(tables, [Some("tb1"), Some("tb2")]);
+                assert_eq!(blocks[0].nrows(), 2);
+            }
+            _ => panic!("expect data message"),
+        }
+        assert!(matches!(messages[1].1, MessageSet::Meta(_)));
+        match &messages[2].1 {
+            MessageSet::Data(blocks) => {
+                assert_eq!(blocks.len(), 1);
+                assert_eq!(blocks[0].nrows(), 3);
+            }
+            _ => panic!("expect data message"),
+        }
+
+        let mut adapter = adapter.await?;
+        assert!(adapter.requests.try_recv().is_err(), "no more poll is sent");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_batch_consumer() -> anyhow::Result<()> {
+        use taos_query::tmq::Timeout;
+
+        let (c1, a1) = mock_consumer();
+        let (c2, a2) = mock_consumer();
+        let serve = |mut adapter: Adapter, message_id: u64| async move {
+            let args = adapter.expect("poll").await;
+            adapter.reply(&args, "poll", polled(message_id, 1));
+            serve_fetch(&mut adapter, message_id, &[("tb1", 1)]).await;
+            let args = adapter.expect("poll").await;
+            adapter.reply(&args, "poll", serde_json::json!({ "have_message": false }));
+        };
+        let a1 = tokio::spawn(serve(a1, 5));
+        let a2 = tokio::spawn(serve(a2, 6));
+
+        let consumer = BatchConsumer::from_consumers(vec![c1, c2], 4);
+        let batch = consumer.poll_batch(Timeout::from_millis(1000)).await?;
+        let ids: Vec<_> = batch
+            .iter()
+            .map(|(offset, _)| (offset.worker(), offset.offset().message_id))
+            .collect();
+        assert_eq!(ids, [(0, 5), (1, 6)]);
+        a1.await?;
+        a2.await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_data_tables() -> anyhow::Result<()> {
+        use taos_query::tmq::{AsAsyncConsumer, MessageSet, Timeout};
+
+        let (mut consumer, mut adapter) = mock_consumer();
+        consumer.table_filter = Arc::new(TableFilter::new().exclude("tb3"));
+        let adapter = tokio::spawn(async move {
+            let args = adapter.expect("poll").await;
+            adapter.reply(&args, "poll", polled(1, 1));
+            // Only fetch requests, no block data is fetched.
+            for (table, rows) in [("tb1", 2), ("tb2", 1), ("tb3", 4), ("tb1", 3)] {
+                let args = adapter.expect("fetch").await;
+                adapter.reply(&args, "fetch", fetched(table, rows));
+            }
+            let args = adapter.expect("fetch").await;
+            adapter.reply(&args, "fetch", serde_json::json!({ "completed": true }));
+            adapter
+        });
+
+        let (_, message) = consumer
+            .recv_timeout(Timeout::from_millis(1000))
+            .await?
+            .expect("a message");
+        let data = match message {
+            MessageSet::Data(data) => data,
+            _ => panic!("expect data message"),
+        };
+        let tables = data.tables().await?;
+        let summary: Vec<_> = tables
+            .iter()
+            .map(|table| (table.table_name(), table.blocks(), table.rows()))
+            .collect();
+        assert_eq!(summary, [(Some("tb1"), 2, 5), (Some("tb2"), 1, 1)]);
+        // The message is consumed.
+        assert!(data.fetch_block().await?.is_none());
+
+        let mut adapter = adapter.await?;
+        assert!(
+            adapter.requests.try_recv().is_err(),
+            "no fetch_block is sent"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_block_with_meta() -> anyhow::Result<()> {
+        use taos_query::tmq::{AsAsyncConsumer, MessageSet, Timeout};
+
+        let (consumer, mut adapter) = mock_consumer();
+        let adapter = tokio::spawn(async move {
+            let args = adapter.expect("poll").await;
+            adapter.reply(&args, "poll", polled(1, 1));
+            serve_fetch(&mut adapter, 1, &[("tb1", 2), ("tb2", 3)]).await;
+        });
+
+        let (_, message) = consumer
+            .recv_timeout(Timeout::from_millis(1000))
+            .await?
+            .expect("a message");
+        let data = match message {
+            MessageSet::Data(data) => data,
+            _ => panic!("expect data message"),
+        };
+        let mut blocks = Vec::new();
+        while let Some((meta, block)) = data.fetch_block_with_meta().await? {
+            assert_eq!(meta.table_name(), block.table_name());
+            assert_eq!(meta.rows(), block.nrows());
+            assert_eq!(meta.fields().len(), 2);
+            assert_eq!(meta.fields()[1].name(), "v");
+            assert_eq!(meta.precision(), taos_query::common::Precision::Millisecond);
+            assert_eq!(meta.server_timing(), Duration::from_nanos(100));
+            blocks.push((meta.table_name().map(ToString::to_string), meta.rows()));
+        }
+        assert_eq!(
+            blocks,
+            [(Some("tb1".to_string()), 2), (Some("tb2".to_string()), 3)]
+        );
+        // No more requests after completed.
+        assert!(data.fetch_block_with_meta().await?.is_none());
+        adapter.await?;
+        Ok(())
+    }
+
+    type ServerStream = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+
+    /// Next json request received by a fake websocket server, `None` if closed.
+    async fn next_request(ws: &mut ServerStream) -> Option<serde_json::Value> {
+        loop {
+            match ws.next().await? {
+                Ok(Message::Text(text)) => return Some(serde_json::from_str(&text).unwrap()),
+                Ok(Message::Close(_)) | Err(_) => return None,
+                Ok(_) => {}
+            }
+        }
+    }
+
+    /// Websocket response to `request` as the adapter does.
+    fn ws_response(request: &serde_json::Value, data: serde_json::Value) -> Message {
+        let mut response = serde_json::json!({
+            "code": 0,
+            "message": "",
+            "action": request["action"],
+            "req_id": request["args"]["req_id"],
+        });
+        if let serde_json::Value::Object(data) = data {
+            response.as_object_mut().unwrap().extend(data);
+        }
+        Message::Text(response.to_string())
+    }
+
+    /// A connection task to `url` for topic `t1` in group `g1`, with its close signal.
+    fn test_connection(url: String, retries: u32) -> (TmqConnection, watch::Sender<bool>) {
+        use std::collections::hash_map::RandomState;
+        let (_, msg_recv) = tokio::sync::mpsc::channel(1);
+        let (close_signal, close_listener) = watch::channel(false);
+        let (state, _) = watch::channel(ConnState::Connected);
+        let conn = TmqConnection {
+            url,
+            queries: Arc::new(HashMap::new(100, RandomState::new())),
+            msg_recv,
+            close_listener,
+            state,
+            resubscribe: Resubscribe {
+                req_id: Arc::new(AtomicU64::new(1)),
+                conn: WsConnReq::new("root", "taosdata"),
+                conf: TmqInit {
+                    group_id: "g1".to_string(),
+                    client_id: None,
+                    offset_reset: None,
+                },
+                topics: Arc::new(Mutex::new(vec!["t1".to_string()])),
+            },
+            retries,
+            backoff: Duration::from_millis(10),
+            resubscribe_timeout: Duration::from_millis(200),
+            session: Arc::new(AtomicU64::new(0)),
+        };
+        (conn, close_signal)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reconnect_failed() -> anyhow::Result<()> {
+        // Nothing listens on the address after the listener is dropped.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}/rest/tmq", listener.local_addr()?);
+        drop(listener);
+
+        let (mut conn, _close) = test_connection(url, 2);
+        match conn.reconnect().await {
+            Err(Error::WsError(_)) => {}
+            Err(err) => panic!("unexpected error: {err}"),
+            Ok(_) => panic!("should not be reconnected"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_resubscribe() -> anyhow::Result<()> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}/rest/tmq", listener.local_addr()?);
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let request = next_request(&mut ws).await.unwrap();
+            assert_eq!(request["action"], "subscribe");
+            assert_eq!(request["args"]["group_id"], "g1");
+            assert_eq!(request["args"]["topics"], serde_json::json!(["t1"]));
+            ws.send(ws_response(&request, serde_json::json!({})))
+                .await
+                .unwrap();
+
+            // Never reply to the next subscription.
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws2 = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _ = next_request(&mut ws2).await;
+            (ws, ws2)
+        });
+
+        let (conn, _close) = test_connection(url, 1);
+        conn.try_reconnect().await?;
+        match conn.try_reconnect().await {
+            Err(Error::QueryTimeout(_)) => {}
+            Err(err) => panic!("unexpected error: {err}"),
+            Ok(_) => panic!("resubscribe should be timed out"),
+        }
+        server.await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_consumer_reconnect() -> anyhow::Result<()> {
+        use taos_query::tmq::{AsAsyncConsumer, IsOffset, MessageSet, Timeout};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let server = tokio::spawn(async move {
+            // The first connection is broken after subscribed.
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let request = next_request(&mut ws).await.unwrap();
+            assert_eq!(request["action"], "subscribe");
+            ws.send(ws_response(&request, serde_json::json!({})))
+                .await
+                .unwrap();
+            drop(ws);
+
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let mut subscribed = false;
+            while let Some(request) = next_request(&mut ws).await {
+                let data = match request["action"].as_str().unwrap() {
+                    "subscribe" => {
+                        subscribed = true;
+                        serde_json::json!({})
+                    }
+                    "poll" => {
+                        assert!(subscribed, "poll before resubscribed");
+                        serde_json::json!({
+                            "have_message": true,
+                            "message_id": 1,
+                            "database": "db1",
+                            "topic": "t1",
+                            "vgroup_id": 2,
+                            "message_type": 1,
+                        })
+                    }
+                    action => panic!("unexpected action {action}"),
+                };
+                ws.send(ws_response(&request, data)).await.unwrap();
+            }
+        });
+
+        let mut consumer =
+            TmqBuilder::new(format!("taos://{addr}?group.id=g1&reconnect.backoff.ms=10"))?
+                .build_consumer()
+                .await?;
+        consumer.subscribe(["t1"]).await?;
+        let (offset, message) = consumer
+            .recv_timeout(Timeout::from_secs(5))
+            .await?
+            .expect("a message after reconnected");
+        assert_eq!(offset.topic(), "t1");
+        assert_eq!(offset.vgroup_id(), 2);
+        assert!(matches!(message, MessageSet::Data(_)));
+        assert_eq!(consumer.session.load(Ordering::SeqCst), 1);
+
+        drop(consumer);
+        server.await?;
+        Ok(())
+    }
 
     #[tokio::test]
     async fn test_ws_tmq_meta() -> anyhow::Result<()> {
@@ -881,8 +2185,9 @@ mod tests {
                     }
                     MessageSet::Data(data) => {
                         // data message may have more than one data block for various tables.
-                        while let Some(data) = data.fetch_block().await? {
-                            dbg!(data.table_name());
+                        while let Some((meta, data)) = data.fetch_block_with_meta().await? {
+                            assert_eq!(meta.table_name(), data.table_name());
+                            assert_eq!(meta.rows(), data.nrows());
                             dbg!(data);
                         }
                     }
@@ -890,7 +2195,7 @@ mod tests {
                 consumer.commit(offset).await?;
             }
         }
-        consumer.unsubscribe().await;
+        consumer.unsubscribe().await?;
 
         tokio::time::sleep(Duration::from_secs(2)).await;
 
@@ -1038,7 +2343,7 @@ mod tests {
             }
             consumer.commit(offset)?;
         }
-        consumer.unsubscribe();
+        consumer.unsubscribe()?;
 
         std::thread::sleep(Duration::from_secs(2));
 
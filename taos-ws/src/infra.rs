@@ -1,9 +1,13 @@
 use std::time::Duration;
 
+use std::io::Write;
+
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::NoneAsEmptyString;
-use taos_query::common::{Precision, Ty};
+use taos_error::Code;
+use taos_query::common::{Precision, RawBlock, RawMeta, Ty};
+use taos_query::util::InlinableWrite;
 
 pub type ReqId = u64;
 
@@ -57,6 +61,51 @@ pub enum WsSend {
     Fetch(WsResArgs),
     FetchBlock(WsResArgs),
     Close(WsResArgs),
+    /// Binary message, like raw meta or raw block.
+    #[serde(skip)]
+    Binary(Vec<u8>),
+}
+
+/// Binary message to write raw meta.
+pub(crate) fn raw_meta_message(req_id: ReqId, raw: &RawMeta) -> std::io::Result<Vec<u8>> {
+    let message_id = req_id;
+    let raw_meta_message = 3; // magic number from taosAdapter.
+
+    let mut meta = Vec::new();
+    meta.write_u64_le(req_id)?;
+    meta.write_u64_le(message_id)?;
+    meta.write_u64_le(raw_meta_message as u64)?;
+    meta.write_all(&raw.as_bytes())?;
+    Ok(meta)
+}
+
+/// Binary message to write raw block into `table`.
+pub(crate) fn raw_block_message(
+    req_id: ReqId,
+    table: &str,
+    raw: &RawBlock,
+) -> std::io::Result<Vec<u8>> {
+    let message_id = req_id;
+    let raw_block_message = 4; // action number from `taosAdapter/controller/rest/const.go:L56`.
+
+    let mut meta = Vec::new();
+    meta.write_u64_le(req_id)?;
+    meta.write_u64_le(message_id)?;
+    meta.write_u64_le(raw_block_message as u64)?;
+    meta.write_u32_le(raw.nrows() as u32)?;
+    meta.write_inlined_str::<2>(table)?;
+    meta.write_all(raw.as_raw_bytes())?;
+    Ok(meta)
+}
+
+/// Table name of raw block, required to write it without an explicit table.
+pub(crate) fn raw_block_table_name(raw: &RawBlock) -> Result<&str, taos_error::Error> {
+    raw.table_name().ok_or_else(|| {
+        taos_error::Error::new(
+            Code::Failed,
+            "raw block has no table name, write it with an explicit table",
+        )
+    })
 }
 
 unsafe impl Send for WsSend {}
@@ -1,5 +1,7 @@
 #![recursion_limit = "256"]
 use std::fmt::{Debug, Display};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use infra::WsConnReq;
 use once_cell::sync::OnceCell;
@@ -16,13 +18,18 @@ mod infra;
 pub mod asyn;
 
 mod stmt;
-pub use stmt::Stmt;
+pub use stmt::{Stmt, StmtBindStats, StmtTableBindStats};
+use stmt::StmtClient;
 
 pub mod sync;
 
 // pub mod tmq;
 pub mod consumer;
 
+pub mod replicator;
+
+pub mod bulk;
+
 #[derive(Debug, Clone)]
 pub enum WsAuth {
     Token(String),
@@ -82,6 +89,8 @@ impl TBuilder for TaosBuilder {
         Ok(Taos {
             dsn: self.clone(),
             async_client: OnceCell::new(),
+            stmt_client: Default::default(),
+            database_changed: AtomicBool::new(false),
         })
     }
 }
@@ -171,6 +180,55 @@ impl TaosBuilder {
 pub struct Taos {
     dsn: TaosBuilder,
     async_client: OnceCell<WsTaos>,
+    /// Stmt connection shared by all statements of the connection, with its database.
+    stmt_client: tokio::sync::Mutex<Option<(Option<String>, Arc<StmtClient>)>>,
+    /// Set when a `use` sql is executed, the stmt connection database is checked then.
+    database_changed: AtomicBool,
+}
+
+impl Taos {
+    /// The shared stmt connection, connect at first use or when the previous one is broken.
+    ///
+    /// The stmt connection uses the current database of the query connection, so it's
+    /// reconnected when the current database is changed by `use` sql. The current database
+    /// is queried only when connecting or after `use` sql.
+    pub(crate) async fn stmt_client(&self) -> Result<Arc<StmtClient>, asyn::Error> {
+        let mut client = self.stmt_client.lock().await;
+        let changed = self.database_changed.swap(false, Ordering::SeqCst);
+        if let Some((_, client)) = client
+            .as_ref()
+            .filter(|(_, client)| !changed && client.is_alive())
+        {
+            return Ok(client.clone());
+        }
+        let database: Option<String> =
+            match <Self as AsyncQueryable>::query_one(self, "select database()").await {
+                Ok(database) => database,
+                Err(err) => {
+                    self.database_changed.store(changed, Ordering::SeqCst);
+                    return Err(err);
+                }
+            };
+        if let Some((_, client)) = client
+            .as_ref()
+            .filter(|(db, client)| db == &database && client.is_alive())
+        {
+            return Ok(client.clone());
+        }
+        let mut dsn = self.dsn.clone();
+        dsn.database = database.clone();
+        let stmt_client = StmtClient::from_wsinfo(&dsn).await?;
+        *client = Some((database, stmt_client.clone()));
+        Ok(stmt_client)
+    }
+}
+
+/// Check if the sql is `USE db`, which changes the current database.
+fn is_use_sql(sql: &str) -> bool {
+    let sql = sql.trim_start();
+    sql.get(..3)
+        .map_or(false, |word| word.eq_ignore_ascii_case("use"))
+        && sql[3..].starts_with(|c: char| c.is_whitespace() || c == '`')
 }
 
 unsafe impl Send for Taos {}
@@ -188,6 +246,9 @@ impl taos_query::AsyncQueryable for Taos {
         &self,
         sql: T,
     ) -> Result<Self::AsyncResultSet, Self::Error> {
+        if is_use_sql(sql.as_ref()) {
+            self.database_changed.store(true, Ordering::SeqCst);
+        }
         if let Some(ws) = self.async_client.get() {
             ws.s_query(sql.as_ref()).await
         } else {
@@ -222,6 +283,22 @@ impl taos_query::AsyncQueryable for Taos {
                 .await
         }
     }
+
+    async fn write_raw_block_to(
+        &self,
+        table: &str,
+        block: &taos_query::RawBlock,
+    ) -> Result<(), Self::Error> {
+        if let Some(ws) = self.async_client.get() {
+            ws.write_raw_block_to(table, block).await
+        } else {
+            let async_client = WsTaos::from_wsinfo(&self.dsn).await?;
+            self.async_client
+                .get_or_init(|| async_client)
+                .write_raw_block_to(table, block)
+                .await
+        }
+    }
 }
 
 impl taos_query::Queryable for Taos {
@@ -237,6 +314,45 @@ impl taos_query::Queryable for Taos {
     fn write_meta(&self, meta: RawMeta) -> Result<(), Self::Error> {
         block_in_place_or_global(<Self as AsyncQueryable>::write_raw_meta(self, meta))
     }
+
+    fn write_raw_block(&self, block: &taos_query::RawBlock) -> Result<(), Self::Error> {
+        block_in_place_or_global(<Self as AsyncQueryable>::write_raw_block(self, block))
+    }
+
+    fn write_raw_block_to(
+        &self,
+        table: &str,
+        block: &taos_query::RawBlock,
+    ) -> Result<(), Self::Error> {
+        block_in_place_or_global(<Self as AsyncQueryable>::write_raw_block_to(
+            self, table, block,
+        ))
+    }
+}
+
+impl taos_query::insert::Insertable for Taos {
+    type Error = asyn::Error;
+
+    fn insert(&self, sql: &str) -> Result<usize, Self::Error> {
+        <Self as Queryable>::exec(self, sql)
+    }
+
+    /// Insert columns with stmt in one batch, views are checked against the table columns.
+    fn insert_columns(
+        &self,
+        table: &str,
+        names: &[&str],
+        columns: &[taos_query::common::ColumnView],
+    ) -> Result<usize, Self::Error> {
+        let sql = taos_query::insert::insert_sql(table, names)?;
+        block_in_place_or_global(async {
+            let mut stmt: Stmt = <Self as AsyncQueryable>::stmt_init(self).await?;
+            stmt.stmt_prepare(&sql).await?;
+            stmt.stmt_bind_block(columns).await?;
+            stmt.stmt_add_batch().await?;
+            stmt.stmt_exec().await
+        })
+    }
 }
 
 #[cfg(test)]
@@ -246,6 +362,18 @@ mod tests {
 
     use crate::TaosBuilder;
 
+    #[test]
+    fn test_is_use_sql() {
+        use super::is_use_sql;
+
+        assert!(is_use_sql("use db1"));
+        assert!(is_use_sql("  USE `db1`"));
+        assert!(is_use_sql("use\tdb1"));
+        assert!(!is_use_sql("user_tables"));
+        assert!(!is_use_sql("select * from db1.tb1"));
+        assert!(!is_use_sql("us"));
+    }
+
     #[test]
     fn ws_sync_json() -> anyhow::Result<()> {
         std::env::set_var("RUST_LOG", "debug");
@@ -511,4 +639,24 @@ mod tests {
         assert_eq!(client.exec("drop database ws_abc_a").await?, 0);
         Ok(())
     }
+
+    #[test]
+    fn test_insert_macro() -> anyhow::Result<()> {
+        use taos_query::{insert, Queryable};
+
+        let taos = TaosBuilder::from_dsn("ws://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists ws_insert",
+            "create database ws_insert",
+            "create table ws_insert.tb1 (ts timestamp, n int, g nchar(8))",
+        ])?;
+        let rows = insert!(taos, "ws_insert.tb1", {
+            "ts" => [1654570964022i64, 1654570965022]: Timestamp,
+            "n" => [0, 1],
+            "g" => ["abc", "def"]: NChar,
+        })?;
+        assert_eq!(rows, 2);
+        taos.exec("drop database ws_insert")?;
+        Ok(())
+    }
 }
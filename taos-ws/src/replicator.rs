@@ -0,0 +1,318 @@
+//! Replicate databases between clusters with tmq.
+//!
+//! A [Replicator] consumes a database topic created `WITH META` and applies each meta and
+//! data message to the target in the polled order, so changes of each table are kept in order.
+//!
+//! A message may be applied partially when an error occurs, e.g. some blocks of a data message
+//! are written. Its offset is not committed, and the replicator refuses to continue with
+//! [Error::Failed], as committing later messages would skip the failed one. Recreate the
+//! consumer and the replicator to resume from the last committed offset, the partially applied
+//! message is applied again.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use taos_error::Code;
+use taos_query::common::RawMeta;
+use taos_query::tmq::{AsAsyncConsumer, IsAsyncMeta, IsOffset, MessageSet, Timeout};
+use taos_query::{AsyncQueryable, IntoDsn};
+
+use crate::asyn::Error as TargetError;
+use crate::consumer::{Consumer, Data, Meta, TmqBuilder};
+use crate::Taos;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("consume error: {0}")]
+    Consumer(#[from] crate::consumer::Error),
+    #[error("apply error: {0}")]
+    Target(#[from] TargetError),
+    #[error("replicator failed at an uncommitted message, recreate it to resume from the last committed offset")]
+    Failed,
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+type TableMap = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Error codes of the target which are worth retrying: not ready, timed out or disconnected.
+const TRANSIENT_CODES: [Code; 6] = [
+    Code::new(0x0005),
+    Code::new(0x0009),
+    Code::new(0x000B),
+    Code::new(0x0014),
+    Code::new(0x0213),
+    Code::new(0x033C),
+];
+
+/// Statistics of applied messages.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReplicateStats {
+    /// Meta messages applied.
+    pub metas: usize,
+    /// Raw blocks written.
+    pub blocks: usize,
+    /// Rows written.
+    pub rows: usize,
+}
+
+pub struct Replicator {
+    consumer: Consumer,
+    target: Taos,
+    databases: HashMap<String, String>,
+    table_map: Option<TableMap>,
+    retries: u32,
+    backoff: Duration,
+    current_db: Option<String>,
+    stats: ReplicateStats,
+    failed: bool,
+}
+
+impl Replicator {
+    /// Subscribe `topics` with the `source` tmq dsn, which requires `group.id`, and replicate to `target`.
+    pub async fn subscribe<D, T, I>(source: D, topics: I, target: Taos) -> Result<Self>
+    where
+        D: IntoDsn,
+        T: Into<String>,
+        I: IntoIterator<Item = T> + Send,
+    {
+        let mut consumer = TmqBuilder::new(source)?.build_consumer().await?;
+        consumer.subscribe(topics).await?;
+        Ok(Self::new(consumer, target))
+    }
+
+    /// Replicate with a subscribed consumer.
+    pub fn new(consumer: Consumer, target: Taos) -> Self {
+        Self {
+            consumer,
+            target,
+            databases: HashMap::new(),
+            table_map: None,
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            current_db: None,
+            stats: ReplicateStats::default(),
+            failed: false,
+        }
+    }
+
+    /// Apply changes of `source` database to `target` database, the target database should exist.
+    pub fn with_database(mut self, source: impl Into<String>, target: impl Into<String>) -> Self {
+        self.databases.insert(source.into(), target.into());
+        self
+    }
+
+    /// Rename tables in the target.
+    ///
+    /// Meta messages are applied with sql generated from json meta in this case, instead of raw meta.
+    pub fn with_table_map<F>(mut self, map: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.table_map = Some(Arc::new(map));
+        self
+    }
+
+    /// Retry transient errors `retries` times, the backoff grows linearly by `backoff`.
+    ///
+    /// Default is 3 retries with 500ms backoff.
+    pub fn with_retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// The source consumer.
+    pub fn consumer(&self) -> &Consumer {
+        &self.consumer
+    }
+
+    /// Statistics of applied messages.
+    pub fn stats(&self) -> ReplicateStats {
+        self.stats
+    }
+
+    /// Replicate one message, returns `false` if no message received in `timeout`.
+    ///
+    /// The offset is committed only after the message is applied successfully. If applying or
+    /// committing fails, the replicator is failed and following calls return [Error::Failed],
+    /// see the module docs.
+    pub async fn replicate_once(&mut self, timeout: Timeout) -> Result<bool> {
+        if self.failed {
+            return Err(Error::Failed);
+        }
+        let (offset, message) = match self.consumer.recv_timeout(timeout).await? {
+            Some(message) => message,
+            None => return Ok(false),
+        };
+        self.failed = true;
+        self.use_database(offset.database()).await?;
+        match message {
+            MessageSet::Meta(meta) => self.apply_meta(&meta).await?,
+            MessageSet::Data(data) => self.apply_data(&data).await?,
+        }
+        self.consumer.commit(offset).await?;
+        self.failed = false;
+        Ok(true)
+    }
+
+    /// Whether a message failed to apply, the replicator could not continue then.
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Keep replicating until an error occurs.
+    pub async fn run(&mut self) -> Result<()> {
+        loop {
+            self.replicate_once(Timeout::Never).await?;
+        }
+    }
+
+    /// Stop replicating and close the consumer gracefully.
+    pub async fn close(self) -> Result<()> {
+        self.consumer.close().await?;
+        Ok(())
+    }
+
+    async fn use_database(&mut self, source: &str) -> Result<()> {
+        let db = self
+            .databases
+            .get(source)
+            .map(String::as_str)
+            .unwrap_or(source);
+        if self.current_db.as_deref() == Some(db) {
+            return Ok(());
+        }
+        let sql = format!("USE `{db}`");
+        let mut retry = 0;
+        while let Err(err) = self.target.exec(&sql).await {
+            self.wait_retry(&mut retry, err).await?;
+        }
+        self.current_db = Some(db.to_string());
+        Ok(())
+    }
+
+    async fn apply_meta(&mut self, meta: &Meta) -> Result<()> {
+        let mut retry = 0;
+        match self.table_map.clone() {
+            None => {
+                let raw = meta.as_raw_meta().await?.as_bytes().into_owned();
+                while let Err(err) = self.target.write_raw_meta(RawMeta::new(raw.clone())).await {
+                    self.wait_retry(&mut retry, err).await?;
+                }
+            }
+            Some(map) => {
+                let mut json = meta.as_json_meta().await?;
+                json.rename_tables(&*map);
+                let sql = json.to_string();
+                log::trace!("apply meta with sql: {sql}");
+                while let Err(err) = self.target.exec(&sql).await {
+                    self.wait_retry(&mut retry, err).await?;
+                }
+            }
+        }
+        self.stats.metas += 1;
+        Ok(())
+    }
+
+    async fn apply_data(&mut self, data: &Data) -> Result<()> {
+        while let Some(mut block) = data.fetch_block().await? {
+            let renamed = match (&self.table_map, block.table_name()) {
+                (Some(map), Some(name)) => Some(map.as_ref()(name)),
+                _ => None,
+            };
+            if let Some(name) = renamed {
+                block.with_table_name(name);
+            }
+
+            let mut retry = 0;
+            while let Err(err) = self.target.write_raw_block(&block).await {
+                self.wait_retry(&mut retry, err).await?;
+            }
+            self.stats.blocks += 1;
+            self.stats.rows += block.nrows();
+        }
+        Ok(())
+    }
+
+    /// Wait for the next retry, or return the error if it's not transient or retries exhausted.
+    ///
+    /// Server errors are transient only for codes in [TRANSIENT_CODES].
+    async fn wait_retry(&self, retry: &mut u32, err: TargetError) -> Result<()> {
+        let transient = match &err {
+            TargetError::TaosError(err) => TRANSIENT_CODES.contains(&err.code()),
+            TargetError::DeError(_) | TargetError::Dsn(_) | TargetError::RowsError(_) => false,
+            _ => true,
+        };
+        if !transient || *retry >= self.retries {
+            return Err(err.into());
+        }
+        *retry += 1;
+        let backoff = self.backoff * *retry;
+        log::warn!(
+            "apply failed, retry in {backoff:?} ({retry}/{}): {err}",
+            self.retries
+        );
+        tokio::time::sleep(backoff).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use taos_query::tmq::Timeout;
+    use taos_query::{AsyncQueryable, TBuilder};
+
+    use super::Replicator;
+    use crate::TaosBuilder;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_replicate_database() -> anyhow::Result<()> {
+        let source = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        source
+            .exec_many([
+                "drop topic if exists ws_repl_src",
+                "drop database if exists ws_repl_src",
+                "drop database if exists ws_repl_dst",
+                "create database ws_repl_src",
+                "create database ws_repl_dst",
+                "create topic ws_repl_src with meta as database ws_repl_src",
+                "use ws_repl_src",
+                "create table stb1(ts timestamp, v int) tags(t1 int)",
+                "create table tb1 using stb1 tags(1)",
+                "insert into tb1 values(now, 1)(now+1s, 2)",
+            ])
+            .await?;
+
+        let target = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        let mut replicator = Replicator::subscribe(
+            "taos://localhost:6041?group.id=repl&auto.offset.reset=earliest",
+            ["ws_repl_src"],
+            target,
+        )
+        .await?
+        .with_database("ws_repl_src", "ws_repl_dst")
+        .with_table_map(|name| format!("r_{name}"))
+        .with_retries(1, Duration::from_millis(100));
+
+        while replicator.replicate_once(Timeout::from_secs(2)).await? {}
+        assert_eq!(replicator.stats().rows, 2);
+        replicator.close().await?;
+
+        let count: Option<i64> = source
+            .query_one("select count(*) from ws_repl_dst.r_stb1")
+            .await?;
+        assert_eq!(count, Some(2));
+
+        source
+            .exec_many([
+                "drop topic ws_repl_src",
+                "drop database ws_repl_src",
+                "drop database ws_repl_dst",
+            ])
+            .await?;
+        Ok(())
+    }
+}
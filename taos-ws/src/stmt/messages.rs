@@ -4,9 +4,12 @@ use serde_with::serde_as;
 use serde_with::NoneAsEmptyString;
 
 use taos_error::Error;
+use taos_query::common::views::decimal_bytes;
+use taos_query::common::{Field, Precision, Ty};
 
 use crate::infra::ToMessage;
 use crate::infra::WsConnReq;
+use crate::infra::{ResId, WsFetchResp};
 
 pub type ReqId = u64;
 
@@ -113,6 +116,9 @@ pub enum StmtSend {
     },
     AddBatch(StmtArgs),
     Exec(StmtArgs),
+    UseResult(StmtArgs),
+    GetTagFields(StmtArgs),
+    GetColFields(StmtArgs),
     Close(StmtArgs),
 }
 
@@ -153,6 +159,99 @@ pub enum StmtRecvData {
         #[serde(default)]
         affected: usize,
     },
+    UseResult(StmtUseResult),
+    GetTagFields {
+        #[serde(default)]
+        stmt_id: StmtId,
+        #[serde(default)]
+        fields: Vec<StmtField>,
+    },
+    GetColFields {
+        #[serde(default)]
+        stmt_id: StmtId,
+        #[serde(default)]
+        fields: Vec<StmtField>,
+    },
+    Fetch(WsFetchResp),
+}
+
+impl StmtRecvData {
+    fn stmt_id(&self) -> StmtId {
+        match self {
+            StmtRecvData::Init { stmt_id }
+            | StmtRecvData::Prepare { stmt_id }
+            | StmtRecvData::SetTableName { stmt_id }
+            | StmtRecvData::SetTags { stmt_id }
+            | StmtRecvData::Bind { stmt_id }
+            | StmtRecvData::AddBatch { stmt_id }
+            | StmtRecvData::Exec { stmt_id, .. }
+            | StmtRecvData::GetTagFields { stmt_id, .. }
+            | StmtRecvData::GetColFields { stmt_id, .. } => *stmt_id,
+            StmtRecvData::UseResult(result) => result.stmt_id,
+            StmtRecvData::Conn | StmtRecvData::Fetch(_) => 0,
+        }
+    }
+}
+
+/// Result of a stmt query.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct StmtUseResult {
+    pub stmt_id: StmtId,
+    pub result_id: ResId,
+    pub fields_count: usize,
+    pub fields_names: Option<Vec<String>>,
+    pub fields_types: Option<Vec<Ty>>,
+    pub fields_lengths: Option<Vec<u32>>,
+    pub precision: Precision,
+}
+
+impl StmtUseResult {
+    pub fn fields(&self) -> Vec<Field> {
+        match (&self.fields_names, &self.fields_types, &self.fields_lengths) {
+            (Some(names), Some(types), Some(lengths)) => names
+                .iter()
+                .zip(types)
+                .zip(lengths)
+                .map(|((name, ty), len)| Field::new(name, *ty, *len))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Field of a prepared statement placeholder.
+#[derive(Debug, Deserialize, Clone)]
+pub struct StmtField {
+    pub name: String,
+    pub field_type: Ty,
+    /// Timestamp precision of TIMESTAMP fields, or the precision of DECIMAL fields.
+    #[serde(default)]
+    pub precision: u8,
+    #[serde(default)]
+    pub scale: u8,
+    pub bytes: u32,
+}
+
+impl StmtField {
+    /// Timestamp precision of a TIMESTAMP field.
+    pub fn timestamp_precision(&self) -> Precision {
+        match self.precision {
+            1 => Precision::Microsecond,
+            2 => Precision::Nanosecond,
+            _ => Precision::Millisecond,
+        }
+    }
+}
+
+impl From<&StmtField> for Field {
+    fn from(field: &StmtField) -> Self {
+        let bytes = match field.field_type {
+            Ty::Decimal | Ty::Decimal64 => decimal_bytes(field.precision, field.scale),
+            _ => field.bytes,
+        };
+        Field::new(&field.name, field.field_type, bytes)
+    }
 }
 
 #[serde_as]
@@ -170,7 +269,8 @@ pub struct StmtRecv {
 pub enum StmtOk {
     Conn(Result<(), Error>),
     Init(ReqId, Result<StmtId, Error>),
-    Stmt(StmtId, Result<Option<usize>, Error>),
+    Stmt(StmtId, ReqId, Result<StmtRecvData, Error>),
+    Fetch(ResId, Result<WsFetchResp, Error>),
 }
 
 impl StmtRecv {
@@ -198,20 +298,16 @@ impl StmtRecv {
                     _e!()
                 }
             }),
-            StmtRecvData::Prepare { stmt_id }
-            | StmtRecvData::SetTableName { stmt_id }
-            | StmtRecvData::SetTags { stmt_id }
-            | StmtRecvData::Bind { stmt_id }
-            | StmtRecvData::AddBatch { stmt_id } => StmtOk::Stmt(stmt_id, {
+            StmtRecvData::Fetch(fetch) => StmtOk::Fetch(fetch.id, {
                 if self.code == 0 {
-                    Ok(None)
+                    Ok(fetch)
                 } else {
                     _e!()
                 }
             }),
-            StmtRecvData::Exec { stmt_id, affected } => StmtOk::Stmt(stmt_id, {
+            data => StmtOk::Stmt(data.stmt_id(), self.req_id, {
                 if self.code == 0 {
-                    Ok(Some(affected))
+                    Ok(data)
                 } else {
                     _e!()
                 }
@@ -224,8 +320,77 @@ impl StmtRecv {
 mod tests {
     use anyhow::Ok;
 
+    use super::*;
+
     #[test]
     fn stmt() -> anyhow::Result<()> {
         Ok(())
     }
+
+    #[test]
+    fn use_result() -> anyhow::Result<()> {
+        let json = r#"{
+            "code": 0,
+            "message": "",
+            "action": "use_result",
+            "req_id": 3,
+            "stmt_id": 1,
+            "result_id": 5,
+            "fields_count": 2,
+            "fields_names": ["ts", "v"],
+            "fields_types": [9, 4],
+            "fields_lengths": [8, 4],
+            "precision": 0
+        }"#;
+        let recv: StmtRecv = serde_json::from_str(json)?;
+        match recv.ok() {
+            StmtOk::Stmt(1, 3, res) => match res? {
+                StmtRecvData::UseResult(result) => {
+                    assert_eq!(result.result_id, 5);
+                    let fields = result.fields();
+                    assert_eq!(fields.len(), 2);
+                    assert_eq!(fields[1].name(), "v");
+                    assert_eq!(fields[1].ty(), Ty::Int);
+                }
+                data => panic!("unexpected response: {data:?}"),
+            },
+            ok => panic!("unexpected response: {ok:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn get_col_fields() -> anyhow::Result<()> {
+        let json = r#"{
+            "code": 0,
+            "message": "",
+            "action": "get_col_fields",
+            "req_id": 4,
+            "stmt_id": 2,
+            "fields": [
+                {"name": "ts", "field_type": 9, "precision": 1, "scale": 0, "bytes": 8},
+                {"name": "v", "field_type": 4, "precision": 0, "scale": 0, "bytes": 4},
+                {"name": "d", "field_type": 21, "precision": 10, "scale": 2, "bytes": 8},
+                {"name": "d2", "field_type": 17, "precision": 20, "scale": 4, "bytes": 16}
+            ]
+        }"#;
+        let recv: StmtRecv = serde_json::from_str(json)?;
+        match recv.ok() {
+            StmtOk::Stmt(2, 4, res) => match res? {
+                StmtRecvData::GetColFields { fields, .. } => {
+                    assert_eq!(fields[0].timestamp_precision(), Precision::Microsecond);
+                    let fields: Vec<Field> = fields.iter().map(Field::from).collect();
+                    assert_eq!(fields[1].name(), "v");
+                    assert_eq!(fields[1].ty(), Ty::Int);
+                    assert_eq!(fields[2].ty(), Ty::Decimal64);
+                    assert_eq!(fields[2].bytes(), decimal_bytes(10, 2));
+                    assert_eq!(fields[3].ty(), Ty::Decimal);
+                    assert_eq!(fields[3].bytes(), 16 | 20 << 16 | 4 << 24);
+                }
+                data => panic!("unexpected response: {data:?}"),
+            },
+            ok => panic!("unexpected response: {ok:?}"),
+        }
+        Ok(())
+    }
 }
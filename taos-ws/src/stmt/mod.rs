@@ -2,10 +2,12 @@ use futures::{SinkExt, StreamExt};
 use itertools::Itertools;
 use scc::HashMap;
 
+use serde::Serialize;
 use taos_query::common::views::views_to_raw_block;
-use taos_query::common::ColumnView;
+use taos_query::common::{ColumnView, Field, Precision, Ty, Value};
 use taos_query::prelude::InlinableWrite;
-use taos_query::stmt::Bindable;
+use taos_query::stmt::{check_views, rows_to_views, tags_to_views, AsyncBindable, Bindable};
+pub use taos_query::stmt::{StmtBindStats, StmtTableBindStats};
 use taos_query::{
     block_in_place_or_global, AsyncFetchable, AsyncQueryable, DeError, DsnError, IntoDsn, RawBlock,
     TBuilder,
@@ -17,22 +19,24 @@ use tokio::time;
 use tokio_tungstenite::tungstenite::Error as WsError;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 
-use crate::infra::ToMessage;
+use crate::asyn::{FetchSender, ResultSet};
+use crate::infra::{ResId, ToMessage, WsFetchData, WsResArgs};
 use crate::{Taos, TaosBuilder};
 use messages::*;
 
 use std::fmt::Debug;
 use std::result::Result as StdResult;
 
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 mod messages;
 
-type StmtResult = StdResult<Option<usize>, taos_error::Error>;
-type StmtSender = std::sync::mpsc::SyncSender<StmtResult>;
-type StmtReceiver = std::sync::mpsc::Receiver<StmtResult>;
+/// Response of a stmt request with its req id.
+type StmtResult = (ReqId, StdResult<StmtRecvData, taos_error::Error>);
+type StmtSender = tokio::sync::mpsc::UnboundedSender<StmtResult>;
+type StmtReceiver = tokio::sync::mpsc::UnboundedReceiver<StmtResult>;
 
 type WsSender = tokio::sync::mpsc::Sender<Message>;
 
@@ -61,6 +65,11 @@ impl ToJsonValue for ColumnView {
             ColumnView::UInt(view) => serde_json::json!(view.to_vec()),
             ColumnView::UBigInt(view) => serde_json::json!(view.to_vec()),
             ColumnView::Json(view) => serde_json::json!(view.to_vec()),
+            ColumnView::VarBinary(view) | ColumnView::Blob(view) | ColumnView::MediumBlob(view) => {
+                serde_json::json!(view.to_vec())
+            }
+            // Decimals are serialized as strings to keep the precision.
+            ColumnView::Decimal(view) => serde_json::json!(view.to_string_vec()),
         }
     }
 }
@@ -69,13 +78,7 @@ impl Bindable<super::Taos> for Stmt {
     type Error = Error;
 
     fn init(taos: &super::Taos) -> StdResult<Self, Self::Error> {
-        let mut dsn = taos.dsn.clone();
-        let database: Option<String> =
-            <Taos as taos_query::Queryable>::query_one(taos, "select database()")?;
-        dsn.database = database;
-        let mut stmt = block_in_place_or_global(Self::from_wsinfo(&dsn))?;
-        block_in_place_or_global(stmt.stmt_init())?;
-        Ok(stmt)
+        block_in_place_or_global(<Self as AsyncBindable<super::Taos>>::init(taos))
     }
 
     fn prepare<S: AsRef<str>>(&mut self, sql: S) -> StdResult<&mut Self, Self::Error> {
@@ -88,15 +91,8 @@ impl Bindable<super::Taos> for Stmt {
         Ok(self)
     }
 
-    fn set_tags(
-        &mut self,
-        tags: &[taos_query::common::Value],
-    ) -> StdResult<&mut Self, Self::Error> {
-        let tags = tags
-            .into_iter()
-            .map(|tag| tag.to_json_value())
-            .collect_vec();
-        block_in_place_or_global(self.stmt_set_tags(tags))?;
+    fn set_tags(&mut self, tags: &[Value]) -> StdResult<&mut Self, Self::Error> {
+        block_in_place_or_global(self.stmt_set_tag_values(tags))?;
         Ok(self)
     }
 
@@ -121,55 +117,115 @@ impl Bindable<super::Taos> for Stmt {
         Ok(block_in_place_or_global(self.stmt_exec())?)
     }
 
+    fn execute_bind_stats(&mut self) -> StdResult<StmtBindStats, Self::Error> {
+        block_in_place_or_global(self.stmt_exec_bind_stats())
+    }
+
     fn affected_rows(&self) -> usize {
         self.affected_rows
     }
+
+    fn result_set(&mut self) -> StdResult<ResultSet, Self::Error> {
+        block_in_place_or_global(self.stmt_use_result())
+    }
 }
 
-pub struct Stmt {
+#[async_trait::async_trait]
+impl AsyncBindable<super::Taos> for Stmt {
+    type Error = Error;
+
+    async fn init(taos: &super::Taos) -> StdResult<Self, Self::Error> {
+        let mut stmt = Self::new(taos.stmt_client().await?);
+        stmt.stmt_init().await?;
+        Ok(stmt)
+    }
+
+    async fn prepare<S: AsRef<str> + Send>(&mut self, sql: S) -> StdResult<&mut Self, Self::Error> {
+        self.stmt_prepare(sql.as_ref()).await?;
+        Ok(self)
+    }
+
+    async fn set_tbname<S: AsRef<str> + Send>(
+        &mut self,
+        name: S,
+    ) -> StdResult<&mut Self, Self::Error> {
+        self.stmt_set_tbname(name.as_ref()).await?;
+        Ok(self)
+    }
+
+    async fn set_tags(&mut self, tags: &[Value]) -> StdResult<&mut Self, Self::Error> {
+        self.stmt_set_tag_values(tags).await?;
+        Ok(self)
+    }
+
+    async fn bind(&mut self, params: &[ColumnView]) -> StdResult<&mut Self, Self::Error> {
+        self.stmt_bind_block(params).await?;
+        Ok(self)
+    }
+
+    async fn add_batch(&mut self) -> StdResult<&mut Self, Self::Error> {
+        self.stmt_add_batch().await?;
+        Ok(self)
+    }
+
+    async fn execute(&mut self) -> StdResult<usize, Self::Error> {
+        self.stmt_exec().await
+    }
+
+    async fn execute_bind_stats(&mut self) -> StdResult<StmtBindStats, Self::Error> {
+        self.stmt_exec_bind_stats().await
+    }
+
+    fn affected_rows(&self) -> usize {
+        self.affected_rows
+    }
+
+    async fn result_set(&mut self) -> StdResult<ResultSet, Self::Error> {
+        self.stmt_use_result().await
+    }
+}
+
+/// A stmt websocket connection, shared by statements with different stmt ids.
+pub(crate) struct StmtClient {
     req_id: Arc<AtomicU64>,
-    timeout: Duration,
     ws: WsSender,
     close_signal: watch::Sender<bool>,
     queries: Arc<HashMap<ReqId, oneshot::Sender<StdResult<StmtId, taos_error::Error>>>>,
     fetches: Arc<HashMap<StmtId, StmtSender>>,
+    results: Arc<HashMap<ResId, FetchSender>>,
+    alive: Arc<AtomicBool>,
+}
+
+pub struct Stmt {
+    client: Arc<StmtClient>,
+    timeout: Duration,
+    sql: Option<String>,
+    tag_fields: Option<Vec<StmtField>>,
+    col_fields: Option<Vec<StmtField>>,
     receiver: Option<StmtReceiver>,
     args: Option<StmtArgs>,
     affected_rows: usize,
+    pending_rows: usize,
+    tables: Vec<StmtTableBindStats>,
+    current_table: Option<usize>,
+    bind_stats: StmtBindStats,
 }
 
-// pub struct WsAsyncStmt {
-//     /// Message sending timeout, default is 5min.
-//     timeout: Duration,
-//     ws: WsSender,
-//     fetches: Arc<HashMap<StmtId, StmtSender>>,
-//     receiver: StmtReceiver,
-//     args: StmtArgs,
-// }
-// impl Debug for WsAsyncStmt {
-//     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-//         f.debug_struct("WsAsyncStmt")
-//             .field("ws", &"...")
-//             .field("fetches", &"...")
-//             .field("receiver", &self.receiver)
-//             .field("args", &self.args)
-//             .finish()
-//     }
-// }
-
-// impl Drop for WsAsyncStmt {
-//     fn drop(&mut self) {
-//         self.fetches.remove(&self.args.stmt_id);
-//         let args = self.args;
-//         let ws = self.ws.clone();
-//         let _ = taos_query::block_in_place_or_global(ws.send(StmtSend::Close(args).to_msg()));
-//     }
-// }
+impl Debug for StmtClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StmtClient")
+            .field("req_id", &self.req_id)
+            .field("alive", &self.alive)
+            .field("...", &"...")
+            .finish()
+    }
+}
 
 impl Debug for Stmt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("WsClient")
-            .field("req_id", &self.req_id)
+        f.debug_struct("Stmt")
+            .field("client", &self.client)
+            .field("args", &self.args)
             .field("...", &"...")
             .finish()
     }
@@ -217,15 +273,22 @@ use super::asyn::Error;
 
 type Result<T> = std::result::Result<T, Error>;
 
-impl Drop for Stmt {
+impl Drop for StmtClient {
     fn drop(&mut self) {
         // send close signal to reader/writer spawned tasks.
         let _ = self.close_signal.send(true);
     }
 }
 
-impl Stmt {
-    pub(crate) async fn from_wsinfo(info: &TaosBuilder) -> Result<Self> {
+impl Drop for Stmt {
+    fn drop(&mut self) {
+        // Free the stmt in server, the connection is kept for other statements.
+        self.close_stmt();
+    }
+}
+
+impl StmtClient {
+    pub(crate) async fn from_wsinfo(info: &TaosBuilder) -> Result<Arc<Self>> {
         let (ws, _) = connect_async(info.to_stmt_url()).await?;
         let req_id = 0;
         let (mut sender, mut reader) = ws.split();
@@ -255,9 +318,13 @@ impl Stmt {
 
         use std::collections::hash_map::RandomState;
         let fetches = Arc::new(HashMap::<StmtId, StmtSender>::new(100, RandomState::new()));
+        let results = Arc::new(HashMap::<ResId, FetchSender>::new(100, RandomState::new()));
+        let results_sender = results.clone();
 
         let queries_sender = queries.clone();
         let fetches_sender = fetches.clone();
+        let alive = Arc::new(AtomicBool::new(true));
+        let alive_reader = alive.clone();
 
         let (ws, mut msg_recv) = tokio::sync::mpsc::channel(100);
         let ws2 = ws.clone();
@@ -305,22 +372,35 @@ impl Stmt {
                                                 log::error!("Stmt init failed because req id {req_id} not exist");
                                             }
                                         }
-                                        StmtOk::Stmt(stmt_id, res) => {
+                                        StmtOk::Stmt(stmt_id, req_id, res) => {
                                             if let Some(sender) = fetches_sender.read(&stmt_id, |_, sender| sender.clone()) {
                                                 log::debug!("send data to fetches with id {}", stmt_id);
-                                                // let res = res.clone();
-                                                sender.send(res).unwrap();
-                                            // }) {
-
+                                                // The stmt may be closed before the response.
+                                                let _ = sender.send((req_id, res));
                                             } else {
                                                 log::error!("Got unknown stmt id: {stmt_id} with result: {res:?}");
                                             }
                                         }
-                                        _ => unreachable!("unknown stmt response"),
+                                        StmtOk::Fetch(res_id, res) => {
+                                            if let Some(sender) = results_sender.read(&res_id, |_, sender| sender.clone()) {
+                                                let _ = sender.send(res.map(WsFetchData::Fetch));
+                                            } else {
+                                                log::error!("Got unknown result id: {res_id} with fetch: {res:?}");
+                                            }
+                                        }
                                     }
                                 }
-                                Message::Binary(_) => {
-                                    log::warn!("received (unexpected) binary message, do nothing");
+                                Message::Binary(block) => {
+                                    // Blocks of stmt query results: timing(u64), result id(u64), raw block.
+                                    use taos_query::util::InlinableRead;
+                                    let mut slice = block.as_slice();
+                                    let timing = Duration::from_nanos(slice.read_u64().unwrap());
+                                    let res_id = slice.read_u64().unwrap();
+                                    if let Some(sender) = results_sender.read(&res_id, |_, sender| sender.clone()) {
+                                        let _ = sender.send(Ok(WsFetchData::Block(timing, block[16..].to_vec())));
+                                    } else {
+                                        log::error!("result not found: {res_id}");
+                                    }
                                 }
                                 Message::Close(_) => {
                                     log::warn!("websocket connection is closed (unexpected?)");
@@ -351,19 +431,55 @@ impl Stmt {
                     }
                 }
             }
+            // Statements waiting for responses will get errors instead of timeout.
+            alive_reader.store(false, Ordering::SeqCst);
+            fetches_sender.clear();
+            results_sender.clear();
         });
 
-        Ok(Self {
+        Ok(Arc::new(Self {
             req_id: Arc::new(AtomicU64::new(req_id + 1)),
             queries,
-            timeout: Duration::from_secs(5),
             fetches,
+            results,
             ws,
             close_signal: tx,
+            alive,
+        }))
+    }
+
+    /// Check if the connection is still available for new statements.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst)
+    }
+}
+
+impl Stmt {
+    pub(crate) async fn from_wsinfo(info: &TaosBuilder) -> Result<Self> {
+        Ok(Self::new(StmtClient::from_wsinfo(info).await?))
+    }
+
+    /// Check if the connection of the stmt is alive.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.client.is_alive()
+    }
+
+    /// New statement on a shared connection, it's not usable until [Stmt::stmt_init].
+    pub(crate) fn new(client: Arc<StmtClient>) -> Self {
+        Self {
+            client,
+            timeout: Duration::from_secs(5),
+            sql: None,
+            tag_fields: None,
+            col_fields: None,
             receiver: None,
             args: None,
             affected_rows: 0,
-        })
+            pending_rows: 0,
+            tables: Vec::new(),
+            current_table: None,
+            bind_stats: StmtBindStats::default(),
+        }
     }
     /// Build TDengine websocket client from dsn.
     ///
@@ -377,24 +493,68 @@ impl Stmt {
     }
 
     fn req_id(&self) -> u64 {
-        self.req_id
+        self.client
+            .req_id
             .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
     }
 
+    /// Close current stmt id if initialized.
+    fn close_stmt(&mut self) {
+        self.reset_tables();
+        self.receiver = None;
+        self.sql = None;
+        self.tag_fields = None;
+        self.col_fields = None;
+        if let Some(args) = self.args.take() {
+            self.client.fetches.remove(&args.stmt_id);
+            if self.client.is_alive() {
+                use tokio::sync::mpsc::error::TrySendError;
+                let stmt_id = args.stmt_id;
+                match self.client.ws.try_send(StmtSend::Close(args).to_msg()) {
+                    Ok(()) => (),
+                    // Channel is full, send it in background rather than leak the stmt in server.
+                    Err(TrySendError::Full(msg)) => match tokio::runtime::Handle::try_current() {
+                        Ok(handle) => {
+                            let ws = self.client.ws.clone();
+                            handle.spawn(async move {
+                                if let Err(err) = ws.send(msg).await {
+                                    log::warn!("failed to close stmt {stmt_id}: {err}");
+                                }
+                            });
+                        }
+                        Err(_) => log::warn!("failed to close stmt {stmt_id}: channel is full"),
+                    },
+                    Err(TrySendError::Closed(_)) => {
+                        log::warn!("failed to close stmt {stmt_id}: connection closed")
+                    }
+                }
+            }
+        }
+    }
+
+    /// Initialize a new stmt id in the connection in timeout, the previous one is closed.
     pub async fn stmt_init(&mut self) -> Result<&mut Self> {
+        self.close_stmt();
+        self.affected_rows = 0;
         let req_id = self.req_id();
         let action = StmtSend::Init { req_id };
         let (tx, rx) = oneshot::channel();
         {
-            self.queries.insert(req_id, tx).unwrap();
-            self.ws.send(action.to_msg()).await?;
+            self.client.queries.insert(req_id, tx).unwrap();
+            self.client.ws.send(action.to_msg()).await?;
         }
-        let stmt_id = rx.await??; // 1. RecvError, 2. TaosError
+        let stmt_id = match time::timeout(self.timeout, rx).await {
+            Ok(res) => res??, // 1. RecvError, 2. TaosError
+            Err(_) => {
+                self.client.queries.remove(&req_id);
+                return Err(Error::QueryTimeout("stmt init".to_string()));
+            }
+        };
         let args = StmtArgs { req_id, stmt_id };
 
-        let (sender, receiver) = std::sync::mpsc::sync_channel(2);
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
 
-        let _ = self.fetches.insert(stmt_id, sender);
+        let _ = self.client.fetches.insert(stmt_id, sender);
 
         self.args = Some(args);
         self.receiver = Some(receiver);
@@ -411,51 +571,131 @@ impl Stmt {
         self.timeout = timeout;
         self
     }
+
+    /// Args of current stmt with a new req id, error if it's not initialized.
+    fn next_args(&mut self) -> Result<StmtArgs> {
+        let stmt_id = match self.args {
+            Some(args) => args.stmt_id,
+            None => return Err(not_initialized()),
+        };
+        Ok(StmtArgs {
+            req_id: self.req_id(),
+            stmt_id,
+        })
+    }
+
+    /// Receive the response of request `req_id` in timeout.
+    ///
+    /// Late responses of timed out requests are dropped.
+    async fn recv(&mut self, req_id: ReqId) -> Result<StmtRecvData> {
+        let receiver = self.receiver.as_mut().ok_or_else(not_initialized)?;
+        let deadline = time::Instant::now() + self.timeout;
+        loop {
+            match time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Some((id, res))) if id == req_id => return Ok(res?),
+                Ok(Some((id, _))) => log::debug!("drop stale stmt response of req {id}"),
+                Ok(None) => return Err(Error::RecvError(std::sync::mpsc::RecvError)),
+                Err(_) => return Err(Error::QueryTimeout("stmt response".to_string())),
+            }
+        }
+    }
     pub async fn stmt_prepare(&mut self, sql: &str) -> Result<()> {
+        let args = self.next_args()?;
         let prepare = StmtSend::Prepare {
-            args: self.args.unwrap(),
+            args,
             sql: sql.to_string(),
         };
-        self.ws.send(prepare.to_msg()).await?;
-        let _ = self
-            .receiver
-            .as_ref()
-            .unwrap()
-            .recv_timeout(self.timeout)??;
+        self.client.ws.send(prepare.to_msg()).await?;
+        self.recv(args.req_id).await?;
+        self.sql = Some(sql.to_string());
+        self.tag_fields = None;
+        self.col_fields = None;
+        self.reset_tables();
         Ok(())
     }
+
+    fn reset_tables(&mut self) {
+        self.pending_rows = 0;
+        self.tables.clear();
+        self.current_table = None;
+    }
+
+    /// Stats of current table, which is an unnamed one if table name is not set.
+    fn current_table(&mut self) -> &mut StmtTableBindStats {
+        let index = match self.current_table {
+            Some(index) => index,
+            None => {
+                self.tables.push(StmtTableBindStats::default());
+                self.tables.len() - 1
+            }
+        };
+        self.current_table = Some(index);
+        &mut self.tables[index]
+    }
+
+    /// Record the binding error to current table.
+    fn track_bind<T>(&mut self, res: Result<T>) -> Result<T> {
+        if let Err(err) = &res {
+            let table = self.current_table();
+            if table.error.is_none() {
+                table.error = Some(err.to_string());
+            }
+        }
+        res
+    }
+
     pub async fn stmt_add_batch(&mut self) -> Result<()> {
         log::debug!("add batch");
-        let message = StmtSend::AddBatch(self.args.unwrap());
-        self.ws.send(message.to_msg()).await?;
-        let _ = self
-            .receiver
-            .as_ref()
-            .unwrap()
-            .recv_timeout(self.timeout)??;
+        let args = self.next_args()?;
+        let message = StmtSend::AddBatch(args);
+        let res = match self.client.ws.send(message.to_msg()).await {
+            Ok(()) => self.recv(args.req_id).await.map(|_| ()),
+            Err(err) => Err(err.into()),
+        };
+        self.track_bind(res)?;
+        let rows = std::mem::take(&mut self.pending_rows);
+        let table = self.current_table();
+        table.batches += 1;
+        table.bound_rows += rows;
         Ok(())
     }
     pub async fn stmt_bind(&mut self, columns: Vec<serde_json::Value>) -> Result<()> {
+        let rows = columns
+            .first()
+            .and_then(|column| column.as_array())
+            .map_or(0, |column| column.len());
+        let args = self.next_args()?;
         let message = StmtSend::Bind {
-            args: self.args.unwrap(),
+            args,
             columns: columns,
         };
-        {
-            log::debug!("bind with: {message:?}");
-            log::debug!("bind string: {}", message.to_msg());
-            self.ws.send(message.to_msg()).await?;
-        }
-        log::debug!("begin receive");
-        let _ = self
-            .receiver
-            .as_ref()
-            .unwrap()
-            .recv_timeout(self.timeout)??;
+        log::debug!("bind with: {message:?}");
+        let res = match self.client.ws.send(message.to_msg()).await {
+            Ok(()) => self.recv(args.req_id).await.map(|_| ()),
+            Err(err) => Err(err.into()),
+        };
+        self.track_bind(res)?;
+        self.pending_rows = rows;
         Ok(())
     }
 
     pub async fn stmt_bind_block(&mut self, columns: &[ColumnView]) -> Result<()> {
-        let args = self.args.unwrap();
+        if self.is_insert() {
+            let checked = match self.col_fields().await {
+                Ok(fields) => check_views(columns, &fields).map_err(Error::from),
+                Err(err) => Err(err),
+            };
+            self.track_bind(checked)?;
+        }
+        let res = self.send_block(2, columns).await; // bind: 2
+        self.track_bind(res)?;
+        self.pending_rows = columns.first().map_or(0, |column| column.len());
+        Ok(())
+    }
+
+    /// Send views in raw block for binary tags or columns binding.
+    async fn send_block(&mut self, action: u64, columns: &[ColumnView]) -> Result<()> {
+        let args = self.next_args()?;
 
         let mut bytes = Vec::new();
         // p0 uin64  req_id
@@ -466,7 +706,7 @@ impl Stmt {
         // p0+40 raw block
         bytes.write_u64_le(args.req_id)?;
         bytes.write_u64_le(args.stmt_id)?;
-        bytes.write_u64_le(2)?; // bind: 2
+        bytes.write_u64_le(action)?;
         bytes.write_u64_le(columns.len() as u64)?;
         let rows = columns.first().map(|c| c.len()).unwrap_or_default() as u64;
         bytes.write_u64_le(rows)?;
@@ -475,16 +715,101 @@ impl Stmt {
 
         bytes.extend(&block);
 
-        self.ws.send(Message::Binary(bytes)).await?;
-        let _ = self
-            .receiver
-            .as_ref()
-            .unwrap()
-            .recv_timeout(self.timeout)??;
+        self.client.ws.send(Message::Binary(bytes)).await?;
+        self.recv(args.req_id).await?;
 
         Ok(())
     }
 
+    /// Get fields of the placeholders in the prepared sql, cached until next prepare, or next
+    /// table name if the super table is not fixed.
+    async fn stmt_get_fields(&mut self, tags: bool) -> Result<&[StmtField]> {
+        let cached = if tags {
+            self.tag_fields.is_some()
+        } else {
+            self.col_fields.is_some()
+        };
+        if !cached {
+            let args = self.next_args()?;
+            let message = if tags {
+                StmtSend::GetTagFields(args)
+            } else {
+                StmtSend::GetColFields(args)
+            };
+            self.client.ws.send_timeout(message.to_msg(), self.timeout).await?;
+            match self.recv(args.req_id).await? {
+                StmtRecvData::GetTagFields { fields, .. } => self.tag_fields = Some(fields),
+                StmtRecvData::GetColFields { fields, .. } => self.col_fields = Some(fields),
+                data => return Err(unexpected_response("get fields", &data)),
+            }
+        }
+        let fields = if tags {
+            &self.tag_fields
+        } else {
+            &self.col_fields
+        };
+        Ok(fields.as_deref().unwrap_or_default())
+    }
+
+    /// Fields of the tag placeholders in the prepared sql, like `insert into ? using stb tags(?, ?) values(?, ?)`.
+    pub async fn tag_fields(&mut self) -> Result<Vec<Field>> {
+        Ok(self.stmt_get_fields(true).await?.iter().map(Field::from).collect())
+    }
+
+    /// Fields of the column placeholders in the prepared sql.
+    ///
+    /// Use it to build [ColumnView]s with the expected types, the bound columns are checked against it.
+    pub async fn col_fields(&mut self) -> Result<Vec<Field>> {
+        Ok(self.stmt_get_fields(false).await?.iter().map(Field::from).collect())
+    }
+
+    /// Fields of the tag or column placeholders, with the timestamp precision of the table.
+    pub(crate) async fn fields_with_precision(
+        &mut self,
+        tags: bool,
+    ) -> Result<(Vec<Field>, Precision)> {
+        let fields = self.stmt_get_fields(tags).await?;
+        let precision = fields
+            .iter()
+            .find(|field| field.field_type == Ty::Timestamp)
+            .map(StmtField::timestamp_precision)
+            .unwrap_or_default();
+        Ok((fields.iter().map(Field::from).collect(), precision))
+    }
+
+    /// Bind serializable rows, like structs or tuples, to the column placeholders.
+    ///
+    /// Rows are transposed to column views with the field types of the prepared sql,
+    /// see [rows_to_views] for how values are matched.
+    ///
+    /// ```rust,ignore
+    /// #[derive(Serialize)]
+    /// struct Row {
+    ///     ts: i64,
+    ///     current: f32,
+    /// }
+    /// stmt.stmt_prepare("insert into d0 values(?, ?)").await?;
+    /// stmt.bind_rows(&[Row { ts: 0, current: 0.1 }]).await?;
+    /// stmt.stmt_add_batch().await?;
+    /// ```
+    pub async fn bind_rows<T: Serialize>(&mut self, rows: &[T]) -> Result<()> {
+        let (fields, precision) = self.fields_with_precision(false).await?;
+        let columns = rows_to_views(rows, &fields, precision).map_err(Error::from);
+        let columns = self.track_bind(columns)?;
+        self.stmt_bind_block(&columns).await
+    }
+
+    /// Set tags with a serializable row, like a struct or tuple in the order of tag placeholders.
+    ///
+    /// Values are converted with [Stmt::tag_fields] as [Stmt::bind_rows] does.
+    pub async fn set_tags_row<T: Serialize>(&mut self, tags: &T) -> Result<()> {
+        let (fields, precision) = self.fields_with_precision(true).await?;
+        let tags =
+            rows_to_views(std::slice::from_ref(tags), &fields, precision).map_err(Error::from);
+        let tags = self.track_bind(tags)?;
+        self.stmt_set_tags_block(&tags).await
+    }
+
     /// Call bind and add batch.
     pub async fn bind_all(&mut self, columns: Vec<serde_json::Value>) -> Result<()> {
         self.stmt_bind(columns).await?;
@@ -492,45 +817,190 @@ impl Stmt {
     }
 
     pub async fn stmt_set_tbname(&mut self, name: &str) -> Result<()> {
+        // Stats are kept by table, in case of switching back to a table.
+        self.current_table = match self.tables.iter().position(|table| table.table == name) {
+            Some(index) => Some(index),
+            None => {
+                self.tables.push(StmtTableBindStats {
+                    table: name.to_string(),
+                    ..Default::default()
+                });
+                Some(self.tables.len() - 1)
+            }
+        };
+        let args = self.next_args()?;
         let message = StmtSend::SetTableName {
-            args: self.args.unwrap(),
+            args,
             name: name.to_string(),
         };
-        self.ws.send_timeout(message.to_msg(), self.timeout).await?;
-        let _ = self
-            .receiver
-            .as_ref()
-            .unwrap()
-            .recv_timeout(self.timeout)??;
+        let res = match self.client.ws.send_timeout(message.to_msg(), self.timeout).await {
+            Ok(()) => self.recv(args.req_id).await.map(|_| ()),
+            Err(err) => Err(err.into()),
+        };
+        self.track_bind(res)?;
+        // Fields depend on the table when it's a placeholder, unless the super table is fixed.
+        if !self.sql.as_deref().map_or(false, has_fixed_stable) {
+            self.tag_fields = None;
+            self.col_fields = None;
+        }
         Ok(())
     }
 
+    /// Set tags with values checked against [Stmt::tag_fields].
+    ///
+    /// Tags are sent in raw block, so NCHAR and JSON values are kept exact, and timestamps are
+    /// converted to the table precision.
+    pub async fn stmt_set_tag_values(&mut self, tags: &[Value]) -> Result<()> {
+        let views = match self.fields_with_precision(true).await {
+            Ok((fields, precision)) => tags_to_views(tags, &fields, precision).map_err(Error::from),
+            Err(err) => Err(err),
+        };
+        let views = self.track_bind(views)?;
+        self.stmt_set_tags_block(&views).await
+    }
+
+    /// Set tags in raw block, each view is a tag with one row.
+    pub async fn stmt_set_tags_block(&mut self, tags: &[ColumnView]) -> Result<()> {
+        let res = self.send_block(1, tags).await; // set tag: 1
+        self.track_bind(res)
+    }
+
     pub async fn stmt_set_tags(&mut self, tags: Vec<serde_json::Value>) -> Result<()> {
-        let message = StmtSend::SetTags {
-            args: self.args.unwrap(),
-            tags: tags,
+        let args = self.next_args()?;
+        let message = StmtSend::SetTags { args, tags: tags };
+        let res = match self.client.ws.send_timeout(message.to_msg(), self.timeout).await {
+            Ok(()) => self.recv(args.req_id).await.map(|_| ()),
+            Err(err) => Err(err.into()),
         };
-        self.ws.send_timeout(message.to_msg(), self.timeout).await?;
-        let _ = self.receiver.as_ref().unwrap().recv_timeout(self.timeout)?;
-        Ok(())
+        self.track_bind(res)
     }
 
     pub async fn stmt_exec(&mut self) -> Result<usize> {
+        self.stmt_exec_bind_stats()
+            .await
+            .map(|stats| stats.affected_rows)
+    }
+
+    /// Execute the batches with per-table binding statistics.
+    ///
+    /// Tables failed in binding are reported with its error while others are executed.
+    /// If the execution failed, the error is returned and the binding stats are still
+    /// available in [Stmt::bind_stats], the server does not tell which tables are failed.
+    pub async fn stmt_exec_bind_stats(&mut self) -> Result<StmtBindStats> {
         log::debug!("exec");
-        let message = StmtSend::Exec(self.args.unwrap());
-        self.ws.send_timeout(message.to_msg(), self.timeout).await?;
-        if let Some(affected) = self
-            .receiver
-            .as_ref()
-            .unwrap()
-            .recv_timeout(self.timeout)??
-        {
-            self.affected_rows += affected;
-            Ok(affected)
+        let args = self.next_args()?;
+        let message = StmtSend::Exec(args);
+        let res = match self.client.ws.send_timeout(message.to_msg(), self.timeout).await {
+            Ok(()) => self.recv(args.req_id).await,
+            Err(err) => Err(err.into()),
+        };
+        let tables = std::mem::take(&mut self.tables);
+        self.reset_tables();
+        let res = res.and_then(|data| match data {
+            StmtRecvData::Exec { affected, .. } => Ok(affected),
+            data => Err(unexpected_response("exec", &data)),
+        });
+        match res {
+            Ok(affected) => {
+                self.affected_rows += affected;
+                self.bind_stats = StmtBindStats {
+                    affected_rows: affected,
+                    tables,
+                };
+                Ok(self.bind_stats.clone())
+            }
+            Err(err) => {
+                self.bind_stats = StmtBindStats {
+                    affected_rows: 0,
+                    tables,
+                };
+                Err(err)
+            }
+        }
+    }
+
+    /// Binding statistics of the last execution.
+    pub fn bind_stats(&self) -> &StmtBindStats {
+        &self.bind_stats
+    }
+
+    /// Check if the prepared sql is an insert statement.
+    pub fn is_insert(&self) -> bool {
+        self.sql.as_deref().map_or(false, is_insert_sql)
+    }
+
+    /// Get the result set of an executed query statement, like `select * from t where ts > ?`.
+    ///
+    /// The result is freed with the stmt, so it should be consumed before next execution.
+    pub async fn stmt_use_result(&mut self) -> Result<ResultSet> {
+        let args = self.next_args()?;
+        let message = StmtSend::UseResult(args);
+        self.client.ws.send_timeout(message.to_msg(), self.timeout).await?;
+        match self.recv(args.req_id).await? {
+            StmtRecvData::UseResult(result) => Ok(ResultSet::from_stmt(
+                self.client.ws.clone(),
+                self.client.results.clone(),
+                self.timeout,
+                WsResArgs {
+                    req_id: args.req_id,
+                    id: result.result_id,
+                },
+                result.fields(),
+                result.precision,
+            )),
+            data => Err(unexpected_response("use_result", &data)),
+        }
+    }
+}
+
+/// Check if the sql inserts into a fixed super table, like `insert into ? using stb tags(?) values(?)`.
+///
+/// Tag and column fields of such sql do not change with the table name.
+fn has_fixed_stable(sql: &str) -> bool {
+    let mut words = sql.split_whitespace();
+    while let Some(word) = words.next() {
+        if word.eq_ignore_ascii_case("using") {
+            return words.next().map_or(false, |stable| !stable.starts_with('?'));
+        }
+    }
+    false
+}
+
+fn not_initialized() -> Error {
+    Error::TaosError(taos_error::Error::new(
+        taos_error::Code::Failed,
+        "stmt is not initialized, call stmt_init first",
+    ))
+}
+
+/// Error of a response that does not match the request.
+fn unexpected_response(action: &str, data: &StmtRecvData) -> Error {
+    Error::TaosError(taos_error::Error::new(
+        taos_error::Code::Failed,
+        format!("unexpected response for {action}: {data:?}"),
+    ))
+}
+
+/// Check if the sql is `INSERT` or `IMPORT`, leading comments and parentheses are skipped.
+fn is_insert_sql(sql: &str) -> bool {
+    let mut sql = sql;
+    loop {
+        sql = sql.trim_start();
+        if let Some(rest) = sql.strip_prefix("--") {
+            sql = rest.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(rest) = sql.strip_prefix("/*") {
+            sql = rest.split_once("*/").map_or("", |(_, rest)| rest);
+        } else if let Some(rest) = sql.strip_prefix('(') {
+            sql = rest;
         } else {
-            panic!("")
+            break;
         }
     }
+    let keyword = sql
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+    keyword.eq_ignore_ascii_case("insert") || keyword.eq_ignore_ascii_case("import")
 }
 
 #[cfg(test)]
@@ -540,6 +1010,94 @@ mod tests {
 
     use crate::{stmt::Stmt, TaosBuilder};
 
+    #[test]
+    fn test_is_insert_sql() {
+        use super::is_insert_sql;
+
+        assert!(is_insert_sql("insert into ? values(?, ?)"));
+        assert!(is_insert_sql("  INSERT INTO tb1 VALUES(?, ?)"));
+        assert!(is_insert_sql("import into tb1 values(?, ?)"));
+        assert!(is_insert_sql("-- comment\ninsert into tb1 values(?, ?)"));
+        assert!(is_insert_sql("/* hint */ insert into tb1 values(?, ?)"));
+        assert!(is_insert_sql("(insert into tb1 values(?, ?))"));
+        assert!(!is_insert_sql("select * from tb1 where ts > ?"));
+        assert!(!is_insert_sql("/* insert */ select * from tb1"));
+        assert!(!is_insert_sql("-- insert into tb1"));
+        assert!(!is_insert_sql("inserted"));
+        assert!(!is_insert_sql(""));
+    }
+
+    #[test]
+    fn test_has_fixed_stable() {
+        use super::has_fixed_stable;
+
+        assert!(has_fixed_stable("insert into ? using stb1 tags(?) values(?, ?)"));
+        assert!(has_fixed_stable("INSERT INTO ? USING db1.stb1 TAGS(?) VALUES(?, ?)"));
+        assert!(!has_fixed_stable("insert into ? using ? tags(?) values(?, ?)"));
+        assert!(!has_fixed_stable("insert into ? values(?, ?)"));
+    }
+
+    /// Stmt on a client without connection, requests are sent to the returned receiver.
+    pub(super) fn mock_stmt() -> (
+        Stmt,
+        tokio::sync::mpsc::UnboundedSender<super::StmtResult>,
+        tokio::sync::mpsc::Receiver<super::Message>,
+    ) {
+        use super::*;
+        use std::collections::hash_map::RandomState;
+
+        let (ws, requests) = tokio::sync::mpsc::channel(100);
+        let (close_signal, _) = watch::channel(false);
+        let client = Arc::new(StmtClient {
+            req_id: Arc::new(AtomicU64::new(10)),
+            ws,
+            close_signal,
+            queries: Arc::new(HashMap::new(10, RandomState::new())),
+            fetches: Arc::new(HashMap::new(10, RandomState::new())),
+            results: Arc::new(HashMap::new(10, RandomState::new())),
+            alive: Arc::new(AtomicBool::new(true)),
+        });
+        let mut stmt = Stmt::new(client);
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        stmt.args = Some(StmtArgs {
+            req_id: 1,
+            stmt_id: 1,
+        });
+        stmt.receiver = Some(receiver);
+        (stmt, sender, requests)
+    }
+
+    #[tokio::test]
+    async fn test_stmt_stale_response() -> anyhow::Result<()> {
+        use super::StmtRecvData;
+
+        let (mut stmt, sender, mut requests) = mock_stmt();
+        // A late response of a timed out request is dropped, and an unexpected one is an error.
+        let fields = StmtRecvData::GetColFields {
+            stmt_id: 1,
+            fields: Vec::new(),
+        };
+        sender.send((1, Ok(fields)))?;
+        sender.send((10, Ok(StmtRecvData::Bind { stmt_id: 1 })))?;
+        let err = stmt.stmt_exec().await.unwrap_err();
+        assert!(
+            err.to_string().contains("unexpected response for exec"),
+            "{err}"
+        );
+
+        let exec = StmtRecvData::Exec {
+            stmt_id: 1,
+            affected: 2,
+        };
+        sender.send((11, Ok(exec)))?;
+        assert_eq!(stmt.stmt_exec().await?, 2);
+        assert!(requests.try_recv().is_ok());
+
+        stmt.args = None;
+        assert!(stmt.stmt_exec().await.is_err());
+        Ok(())
+    }
+
     // !Websocket tests should always use `multi_thread`
     #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
     async fn test_client() -> anyhow::Result<()> {
@@ -570,6 +1128,357 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_bindable() -> anyhow::Result<()> {
+        use taos_query::common::{ColumnView, Value};
+        use taos_query::prelude::AsyncBindable;
+        use taos_query::AsyncQueryable;
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists stmt_async",
+            "create database stmt_async",
+            "use stmt_async",
+            "create table stb (ts timestamp, v int) tags(t1 int)",
+        ])
+        .await?;
+
+        let mut stmt: Stmt = taos.stmt_init().await?;
+        stmt.prepare("insert into ? using stb tags(?) values(?, ?)")
+            .await?
+            .set_tbname_tags("tb1", &[Value::Int(1)])
+            .await?
+            .bind(&[
+                ColumnView::from_millis_timestamp(vec![1654570964022, 1654570965022]),
+                ColumnView::from_ints(vec![2, 3]),
+            ])
+            .await?
+            .add_batch()
+            .await?;
+        assert_eq!(stmt.execute().await?, 2);
+        assert_eq!(stmt.affected_rows(), 2);
+
+        taos.exec("drop database stmt_async").await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_stmt_shared_connection() -> anyhow::Result<()> {
+        use std::sync::Arc;
+        use taos_query::prelude::AsyncBindable;
+        use taos_query::AsyncQueryable;
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists stmt_shared",
+            "create database stmt_shared",
+            "use stmt_shared",
+            "create table tb1 (ts timestamp, v int)",
+        ])
+        .await?;
+
+        let mut stmt1: Stmt = taos.stmt_init().await?;
+        let mut stmt2: Stmt = taos.stmt_init().await?;
+        assert!(Arc::ptr_eq(&stmt1.client, &stmt2.client));
+        assert_ne!(
+            stmt1.args.unwrap().stmt_id,
+            stmt2.args.unwrap().stmt_id
+        );
+
+        for (stmt, ts) in [(&mut stmt1, 1654570964022i64), (&mut stmt2, 1654570965022)] {
+            stmt.prepare("insert into tb1 values(?, ?)")
+                .await?
+                .bind(&[
+                    taos_query::common::ColumnView::from_millis_timestamp(vec![ts]),
+                    taos_query::common::ColumnView::from_ints(vec![1]),
+                ])
+                .await?
+                .add_batch()
+                .await?;
+            assert_eq!(stmt.execute().await?, 1);
+        }
+        drop(stmt1);
+
+        // The connection is kept after a statement dropped.
+        let stmt3: Stmt = taos.stmt_init().await?;
+        assert!(Arc::ptr_eq(&stmt2.client, &stmt3.client));
+
+        taos.exec("drop database stmt_shared").await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stmt_use_database() -> anyhow::Result<()> {
+        use std::sync::Arc;
+        use taos_query::prelude::AsyncBindable;
+        use taos_query::AsyncQueryable;
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists stmt_use1",
+            "drop database if exists stmt_use2",
+            "create database stmt_use1",
+            "create database stmt_use2",
+            "create table stmt_use1.tb1 (ts timestamp, v int)",
+            "create table stmt_use2.tb1 (ts timestamp, v int)",
+            "use stmt_use1",
+        ])
+        .await?;
+
+        let stmt1: Stmt = taos.stmt_init().await?;
+        taos.exec("use stmt_use2").await?;
+        // Statements after `use` prepare against the new database.
+        let mut stmt2: Stmt = taos.stmt_init().await?;
+        assert!(!Arc::ptr_eq(&stmt1.client, &stmt2.client));
+        stmt2
+            .prepare("insert into tb1 values(?, ?)")
+            .await?
+            .bind(&[
+                taos_query::common::ColumnView::from_millis_timestamp(vec![1654570964022]),
+                taos_query::common::ColumnView::from_ints(vec![1]),
+            ])
+            .await?
+            .add_batch()
+            .await?;
+        assert_eq!(stmt2.execute().await?, 1);
+
+        let count: Option<i64> = taos.query_one("select count(*) from stmt_use2.tb1").await?;
+        assert_eq!(count, Some(1));
+
+        taos.exec_many(["drop database stmt_use1", "drop database stmt_use2"])
+            .await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_stmt_query() -> anyhow::Result<()> {
+        use taos_query::common::ColumnView;
+        use taos_query::prelude::{AsyncBindable, AsyncFetchable, TryStreamExt};
+        use taos_query::AsyncQueryable;
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists stmt_q",
+            "create database stmt_q",
+            "use stmt_q",
+            "create table tb1 (ts timestamp, v int)",
+            "insert into tb1 values(1654570964022, 1)(1654570965022, 2)(1654570966022, 3)",
+        ])
+        .await?;
+
+        let mut stmt: Stmt = taos.stmt_init().await?;
+        stmt.prepare("select v from tb1 where ts > ? and v < ?")
+            .await?
+            .bind(&[
+                ColumnView::from_millis_timestamp(vec![1654570964022]),
+                ColumnView::from_ints(vec![3]),
+            ])
+            .await?
+            .add_batch()
+            .await?;
+        assert!(!stmt.is_insert());
+        stmt.execute().await?;
+
+        let mut rs = stmt.result_set().await?;
+        let rows: Vec<(i32,)> = rs.deserialize().try_collect().await?;
+        assert_eq!(rows, vec![(2,)]);
+
+        taos.exec("drop database stmt_q").await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_stmt_bind_rows() -> anyhow::Result<()> {
+        use taos_query::prelude::AsyncBindable;
+        use taos_query::AsyncQueryable;
+
+        #[derive(serde::Serialize)]
+        struct SensorRow<'a> {
+            ts: &'a str,
+            current: f32,
+            location: Option<&'a str>,
+        }
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists stmt_rows",
+            "create database stmt_rows",
+            "use stmt_rows",
+            "create table tb1 (ts timestamp, current float, location binary(16))",
+        ])
+        .await?;
+
+        let mut stmt: Stmt = taos.stmt_init().await?;
+        stmt.stmt_prepare("insert into tb1 values(?, ?, ?)").await?;
+        stmt.bind_rows(&[
+            SensorRow {
+                ts: "2022-06-07T11:02:44.022+08:00",
+                current: 10.1,
+                location: Some("beijing"),
+            },
+            SensorRow {
+                ts: "2022-06-07T11:02:45.022+08:00",
+                current: 10.2,
+                location: None,
+            },
+        ])
+        .await?;
+        stmt.stmt_add_batch().await?;
+        assert_eq!(stmt.execute().await?, 2);
+
+        // Mismatched types are reported with the column name.
+        let err = stmt
+            .bind_rows(&[(1654570966022i64, "10.3", "shanghai")])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("`current`"));
+
+        taos.exec("drop database stmt_rows").await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_stmt_fields() -> anyhow::Result<()> {
+        use taos_query::common::{ColumnView, Ty, Value};
+        use taos_query::prelude::AsyncBindable;
+        use taos_query::AsyncQueryable;
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists stmt_fields",
+            "create database stmt_fields",
+            "use stmt_fields",
+            "create table stb (ts timestamp, v int) tags(t1 int, t2 binary(8))",
+        ])
+        .await?;
+
+        let mut stmt: Stmt = taos.stmt_init().await?;
+        stmt.prepare("insert into ? using stb tags(?, ?) values(?, ?)")
+            .await?
+            .set_tbname("tb1")
+            .await?;
+        let tags = stmt.tag_fields().await?;
+        assert_eq!(
+            tags.iter().map(|f| f.ty()).collect::<Vec<_>>(),
+            [Ty::Int, Ty::VarChar]
+        );
+        let cols = stmt.col_fields().await?;
+        assert_eq!(cols[0].name(), "ts");
+        assert_eq!(cols[1].ty(), Ty::Int);
+
+        // Tags and columns are validated before sending.
+        let err = stmt.set_tags(&[Value::Int(1)]).await.unwrap_err();
+        assert!(err.to_string().contains("expect 2 parameters"));
+        stmt.set_tags(&[Value::Int(1), Value::Null]).await?;
+        let err = stmt
+            .bind(&[
+                ColumnView::from_millis_timestamp(vec![1654570964022]),
+                ColumnView::from_big_ints(vec![1]),
+            ])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("`v`"));
+
+        taos.exec("drop database stmt_fields").await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_stmt_binary_tags() -> anyhow::Result<()> {
+        use taos_query::common::views::TimestampView;
+        use taos_query::common::{ColumnView, Precision, Timestamp, Value};
+        use taos_query::prelude::{AsyncBindable, AsyncFetchable, TryStreamExt};
+        use taos_query::AsyncQueryable;
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists stmt_tags",
+            "create database stmt_tags precision 'ns'",
+            "use stmt_tags",
+            "create table stb (ts timestamp, v int) tags(t1 timestamp, t2 nchar(8))",
+        ])
+        .await?;
+
+        let ts = 1654570964022450088i64;
+        let mut stmt: Stmt = taos.stmt_init().await?;
+        stmt.prepare("insert into ? using stb tags(?, ?) values(?, ?)")
+            .await?
+            .set_tbname_tags(
+                "tb1",
+                &[
+                    Value::Timestamp(Timestamp::new(ts, Precision::Nanosecond)),
+                    Value::NChar("涛思数据".to_string()),
+                ],
+            )
+            .await?
+            .bind(&[
+                ColumnView::Timestamp(TimestampView::from_nanos(vec![ts])),
+                ColumnView::from_ints(vec![1]),
+            ])
+            .await?
+            .add_batch()
+            .await?;
+        assert_eq!(stmt.execute().await?, 1);
+
+        let rows: Vec<(i64, String)> = taos
+            .query("select cast(t1 as bigint), t2 from stb")
+            .await?
+            .deserialize()
+            .try_collect()
+            .await?;
+        assert_eq!(rows, vec![(ts, "涛思数据".to_string())]);
+
+        taos.exec("drop database stmt_tags").await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
+    async fn test_stmt_exec_bind_stats() -> anyhow::Result<()> {
+        use taos_query::common::Value;
+        use taos_query::AsyncQueryable;
+
+        let taos = TaosBuilder::from_dsn("taos://localhost:6041")?.build()?;
+        taos.exec_many([
+            "drop database if exists stmt_stats",
+            "create database stmt_stats",
+            "use stmt_stats",
+            "create table stb (ts timestamp, v int) tags(t1 int)",
+        ])
+        .await?;
+
+        let mut stmt: Stmt = taos.stmt_init().await?;
+        stmt.stmt_prepare("insert into ? using stb tags(?) values(?, ?)")
+            .await?;
+        stmt.stmt_set_tbname("tb1").await?;
+        stmt.stmt_set_tag_values(&[Value::Int(1)]).await?;
+        stmt.bind_rows(&[(1654570964022i64, 1), (1654570964023i64, 2)])
+            .await?;
+        stmt.stmt_add_batch().await?;
+
+        // Failed table is skipped, others are still executed.
+        stmt.stmt_set_tbname("tb2").await?;
+        stmt.set_tags_row(&("x",)).await.unwrap_err();
+
+        stmt.stmt_set_tbname("tb1").await?;
+        stmt.bind_rows(&[(1654570964024i64, 3)]).await?;
+        stmt.stmt_add_batch().await?;
+
+        let stats = stmt.stmt_exec_bind_stats().await?;
+        assert_eq!(stats.affected_rows, 3);
+        assert!(stats.is_partial());
+        assert_eq!(stats.tables.len(), 2);
+        assert_eq!(stats.tables[0].table, "tb1");
+        assert_eq!(stats.tables[0].batches, 2);
+        assert_eq!(stats.tables[0].bound_rows, 3);
+        assert!(stats.tables[0].is_ok());
+        let failed: Vec<_> = stats.failed_tables().map(|t| t.table.as_str()).collect();
+        assert_eq!(failed, ["tb2"]);
+        assert_eq!(stmt.bind_stats(), &stats);
+
+        taos.exec("drop database stmt_stats").await?;
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 10)]
     async fn test_stmt_stable() -> anyhow::Result<()> {
         use taos_query::AsyncQueryable;
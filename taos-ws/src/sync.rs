@@ -100,6 +100,8 @@ pub enum Error {
     ConnClosed,
     #[error(transparent)]
     AsyncError(#[from] super::asyn::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
 }
 
 #[repr(C)]
@@ -245,6 +247,12 @@ impl WsClient {
                             } else {
                             }
                         }
+                        Some(WsSend::Binary(bytes)) => {
+                            if let Err(err) = async_sender.send(Message::Binary(bytes)).await {
+                                log::error!("send websocket message packet error: {}", err);
+                                break;
+                            }
+                        }
                         Some(msg) => {
                             if let Err(err) = async_sender.send(msg.to_msg()).await {
                                 log::error!("send websocket message packet error: {}", err);
@@ -310,6 +318,11 @@ impl WsClient {
                                             }
                                         }
                                     }
+                                    WsRecvData::WriteMeta | WsRecvData::WriteRaw => {
+                                        if let Some((_, sender)) = queries_sender.remove(&req_id) {
+                                            let _ = sender.send(ok.map(|_| WsQueryResp::default()));
+                                        }
+                                    }
                                     // Block type is for binary.
                                     _ => unreachable!(),
                                 }
@@ -504,9 +517,41 @@ impl WsClient {
         self.s_exec_timeout(sql, self.timeout)
     }
 
-    pub fn s_write_meta(&self, _: RawMeta) -> Result<()> {
-        // todo: unimplemented
-        todo!()
+    pub fn s_write_meta(&self, raw: RawMeta) -> Result<()> {
+        let req_id = self.req_id();
+        let message = raw_meta_message(req_id, &raw)?;
+        self.s_write_binary(req_id, message)
+    }
+
+    /// Write raw block into the table named in the block.
+    pub fn s_write_raw_block(&self, raw: &RawBlock) -> Result<()> {
+        self.s_write_raw_block_to(raw_block_table_name(raw)?, raw)
+    }
+
+    /// Write raw block into `table`.
+    pub fn s_write_raw_block_to(&self, table: &str, raw: &RawBlock) -> Result<()> {
+        let req_id = self.req_id();
+        let message = raw_block_message(req_id, table, raw)?;
+        self.s_write_binary(req_id, message)
+    }
+
+    fn s_write_binary(&self, req_id: ReqId, message: Vec<u8>) -> Result<()> {
+        if !self.alive.load(std::sync::atomic::Ordering::SeqCst) {
+            Err(taos_error::Error::new(
+                Code::new(WS_ERROR_NO::CONN_CLOSED as _),
+                "connection closed",
+            ))?;
+        }
+        let (tx, rx) = std::sync::mpsc::sync_channel(2);
+        {
+            self.queries.insert(req_id, tx).unwrap();
+            self.rt.block_on(
+                self.sender
+                    .send_timeout(WsSend::Binary(message), self.timeout),
+            )?;
+        }
+        rx.recv_timeout(self.timeout)??;
+        Ok(())
     }
     pub fn s_exec_timeout(&self, sql: &str, timeout: Duration) -> Result<usize> {
         if !self.alive.load(std::sync::atomic::Ordering::SeqCst) {
@@ -622,7 +667,7 @@ impl ResultSet {
                     fetch_resp.lengths.as_ref().unwrap(),
                     fetch_resp.rows,
                     self.precision,
-                );
+                )?;
 
                 for row in 0..raw.nrows() {
                     for col in 0..raw.ncols() {
@@ -717,6 +762,18 @@ impl Queryable for WsClient {
     fn write_meta(&self, raw: taos_query::common::RawMeta) -> std::result::Result<(), Self::Error> {
         self.s_write_meta(raw)
     }
+
+    fn write_raw_block(&self, block: &RawBlock) -> std::result::Result<(), Self::Error> {
+        self.s_write_raw_block(block)
+    }
+
+    fn write_raw_block_to(
+        &self,
+        table: &str,
+        block: &RawBlock,
+    ) -> std::result::Result<(), Self::Error> {
+        self.s_write_raw_block_to(table, block)
+    }
 }
 
 #[test]
@@ -754,3 +811,43 @@ fn test_client() -> anyhow::Result<()> {
     assert_eq!(client.exec("drop database abc")?, 0);
     Ok(())
 }
+
+#[test]
+fn test_write_raw_block() -> anyhow::Result<()> {
+    use taos_query::common::{Ty, Value};
+
+    let raw = RawBlock::parse_from_raw_block_v2(
+        &[0, 0, 0, 0, 0, 0, 0, 0, 2][..],
+        &[
+            Field::new("ts", Ty::Timestamp, 8),
+            Field::new("v", Ty::Bool, 1),
+        ],
+        &[8, 1],
+        1,
+        Precision::Millisecond,
+    )?;
+    let client = WsClient::from_dsn("ws://localhost:6041/")?;
+    client.exec_many([
+        "drop database if exists ws_sync_raw",
+        "create database ws_sync_raw keep 36500",
+        "use ws_sync_raw",
+        "create table tb1(ts timestamp, v bool)",
+        "create stable stb1(ts timestamp, v bool) tags(t1 int)",
+    ])?;
+
+    // No table name in the block.
+    assert!(client.write_raw_block(&raw).is_err());
+
+    client.write_raw_block_to("tb1", &raw)?;
+    let mut rs = client.query("select * from tb1")?;
+    assert_eq!(rs.to_rows_vec()?.len(), 1);
+
+    // Subtable is created from the stable when missing.
+    client.write_raw_block_with_tags("tb2", "stb1", &[Value::Int(1)], &raw)?;
+    client.write_raw_block_with_tags("tb2", "stb1", &[Value::Int(1)], &raw)?;
+    let mut rs = client.query("select t1 from stb1")?;
+    assert_eq!(rs.to_rows_vec()?.len(), 1);
+
+    assert_eq!(client.exec("drop database ws_sync_raw")?, 0);
+    Ok(())
+}